@@ -0,0 +1,57 @@
+/*!
+A counter restricted with the access control of `near-contract-standards`: the members of the
+"incrementer" role increment it while the contract isn't paused, and the owner pauses the contract
+and resets the counter while it is paused.
+*/
+use near_contract_standards::access_control::{Ownership, Pause, Roles};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Roles,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Counter {
+    ownership: Ownership,
+    roles: Roles,
+    pause: Pause,
+    value: u64,
+}
+
+#[near_bindgen]
+impl Counter {
+    /// Initializes the contract with `owner_id` as the owner and the only admin of the roles.
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            ownership: Ownership::new(owner_id.clone()),
+            roles: Roles::new(StorageKey::Roles, owner_id),
+            pause: Pause::new(),
+            value: 0,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    #[only(role = "incrementer")]
+    #[when_not_paused]
+    pub fn increment(&mut self) -> u64 {
+        self.value += 1;
+        self.value
+    }
+
+    #[only(owner)]
+    #[when_paused]
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+}
+
+near_contract_standards::impl_ownable!(Counter, ownership);
+near_contract_standards::impl_access_control!(Counter, roles);
+near_contract_standards::impl_pausable!(Counter, pause, owner);
@@ -0,0 +1,126 @@
+use near_primitives::views::FinalExecutionStatus;
+use near_units::parse_near;
+use workspaces::prelude::*;
+use workspaces::{Account, Contract, DevNetwork, Worker};
+
+/// Deploys the counter owned by the contract account and creates "alice", who is granted the
+/// "incrementer" role.
+async fn init(worker: &Worker<impl DevNetwork>) -> anyhow::Result<(Contract, Account)> {
+    let wasm = tokio::fs::read("res/access_control.wasm").await?;
+    let contract = worker.dev_deploy(wasm).await?;
+
+    let res = contract
+        .call(worker, "new")
+        .args_json((contract.id(),))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    let alice = contract
+        .as_account()
+        .create_subaccount(worker, "alice")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let res = contract
+        .call(worker, "grant_role")
+        .args_json(("incrementer", alice.id()))?
+        .gas(300_000_000_000_000)
+        .deposit(1)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    Ok((contract, alice))
+}
+
+async fn call(
+    worker: &Worker<impl DevNetwork>,
+    account: &Account,
+    contract: &Contract,
+    method: &str,
+) -> anyhow::Result<FinalExecutionStatus> {
+    let res = account
+        .call(worker, contract.id().clone(), method)
+        .gas(300_000_000_000_000)
+        .deposit(1)
+        .transact()
+        .await?;
+    Ok(res.status)
+}
+
+async fn value(worker: &Worker<impl DevNetwork>, contract: &Contract) -> anyhow::Result<u64> {
+    Ok(contract.call(worker, "value").view().await?.json::<u64>()?)
+}
+
+#[tokio::test]
+async fn only_role() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (contract, alice) = init(&worker).await?;
+
+    let status = call(&worker, &alice, &contract, "increment").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+    assert_eq!(value(&worker, &contract).await?, 1);
+
+    // The owner is an admin, but not an incrementer.
+    let status = call(&worker, contract.as_account(), &contract, "increment").await?;
+    assert!(matches!(status, FinalExecutionStatus::Failure(_)));
+    assert_eq!(value(&worker, &contract).await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_owner() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (contract, alice) = init(&worker).await?;
+
+    let status = call(&worker, &alice, &contract, "pause").await?;
+    assert!(matches!(status, FinalExecutionStatus::Failure(_)));
+    assert!(!contract.call(&worker, "paused").view().await?.json::<bool>()?);
+
+    let status = call(&worker, contract.as_account(), &contract, "pause").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+    assert!(contract.call(&worker, "paused").view().await?.json::<bool>()?);
+
+    let status = call(&worker, &alice, &contract, "reset").await?;
+    assert!(matches!(status, FinalExecutionStatus::Failure(_)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn when_paused() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (contract, alice) = init(&worker).await?;
+
+    let status = call(&worker, &alice, &contract, "increment").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+
+    // The counter can't be reset while the contract isn't paused.
+    let status = call(&worker, contract.as_account(), &contract, "reset").await?;
+    assert!(matches!(status, FinalExecutionStatus::Failure(_)));
+    assert_eq!(value(&worker, &contract).await?, 1);
+
+    let status = call(&worker, contract.as_account(), &contract, "pause").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+
+    // Nor incremented while it is paused.
+    let status = call(&worker, &alice, &contract, "increment").await?;
+    assert!(matches!(status, FinalExecutionStatus::Failure(_)));
+
+    let status = call(&worker, contract.as_account(), &contract, "reset").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+    assert_eq!(value(&worker, &contract).await?, 0);
+
+    let status = call(&worker, contract.as_account(), &contract, "unpause").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+    let status = call(&worker, &alice, &contract, "increment").await?;
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)));
+    assert_eq!(value(&worker, &contract).await?, 1);
+
+    Ok(())
+}
@@ -1,41 +1,29 @@
 use near_sdk::require;
-use near_sdk::{env, ext_contract, near_bindgen, Promise, PromiseError};
+use near_sdk::{env, near_bindgen, Promise, PromiseError};
 
 const A_VALUE: u8 = 8;
 
 #[near_bindgen]
 pub struct Callback;
 
-// One can provide a name, e.g. `ext` to use for generated methods.
-#[ext_contract(ext)]
-pub trait ExtCrossContract {
-    fn a() -> Promise;
-    fn b(fail: bool) -> &'static str;
-    fn c(value: u8) -> u8;
-    fn d(value: u8);
-    fn handle_callbacks(
-        #[callback_unwrap] a: u8,
-        #[callback_result] b: Result<String, PromiseError>,
-        #[callback_result] c: Result<u8, PromiseError>,
-        #[callback_result] d: Result<(), PromiseError>,
-    ) -> (bool, bool, bool);
-}
-
 #[near_bindgen]
 impl Callback {
     /// Call functions a, b, and c asynchronously and handle results with `handle_callbacks`.
     pub fn call_all(fail_b: bool, c_value: u8, d_value: u8) -> Promise {
+        // `Self::ext` is generated by `#[near_bindgen]` to call the methods of this contract.
         let gas_per_promise = env::prepaid_gas() / 7;
-        ext::a(env::current_account_id(), 0, gas_per_promise)
-            .and(ext::b(fail_b, env::current_account_id(), 0, gas_per_promise))
-            .and(ext::c(c_value, env::current_account_id(), 0, gas_per_promise))
-            .and(ext::d(d_value, env::current_account_id(), 0, gas_per_promise))
-            .then(ext::handle_callbacks(env::current_account_id(), 0, gas_per_promise))
+        let ext = || Self::ext(env::current_account_id()).with_static_gas(gas_per_promise);
+        ext()
+            .a()
+            .and(ext().b(fail_b))
+            .and(ext().c(c_value))
+            .and(ext().d(d_value))
+            .then(ext().handle_callbacks())
     }
 
     /// Calls function c with a value that will always succeed
     pub fn a() -> Promise {
-        ext::c(A_VALUE, env::current_account_id(), 0, env::prepaid_gas() / 2)
+        Self::ext(env::current_account_id()).with_static_gas(env::prepaid_gas() / 2).c(A_VALUE)
     }
 
     /// Returns a static string if fail is false, return
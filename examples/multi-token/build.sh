@@ -0,0 +1,8 @@
+#!/bin/bash
+TARGET="${CARGO_TARGET_DIR:-target}"
+set -e
+cd "`dirname $0`"
+cargo build --all --target wasm32-unknown-unknown --release
+mkdir -p ./res
+cp $TARGET/wasm32-unknown-unknown/release/multi_token.wasm ./res/
+cp $TARGET/wasm32-unknown-unknown/release/mt_token_receiver.wasm ./res/
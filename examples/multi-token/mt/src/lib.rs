@@ -0,0 +1,373 @@
+/*!
+Multi Token implementation with JSON serialization, for example the items of a game.
+NOTES:
+  - The maximum balance value is limited by U128 (2**128 - 1).
+  - JSON calls should pass U128 as a base-10 string. E.g. "100".
+  - A token with a supply of one is non-fungible, a token with a larger supply is fungible. Both
+    kinds are minted, transferred and approved the same way.
+  - The contract tracks the change in storage before and after the call. If the storage increases,
+    the contract requires the caller of the contract to attach enough deposit to the function call
+    to cover the storage cost.
+    This is done to prevent a denial of service attack on the contract by taking all available storage.
+    If the storage decreases, the contract will issue a refund for the cost of the released storage.
+    The unused tokens from the attached deposit are also refunded, so it's safe to
+    attach more deposit than required.
+  - To prevent the deployed contract from being modified or deleted, it should not have any access
+    keys on its account.
+*/
+use near_contract_standards::multi_token::metadata::{
+    MTContractMetadata, MultiTokenMetadataProvider, TokenMetadata, MT_METADATA_SPEC,
+};
+use near_contract_standards::multi_token::{MultiToken, Token, TokenId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LazyOption;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    env, near_bindgen, require, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue,
+};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    tokens: MultiToken,
+    metadata: LazyOption<MTContractMetadata>,
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    MultiToken,
+    Metadata,
+    TokenMetadata,
+    Enumeration,
+    Approval,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Initializes the contract owned by `owner_id` with
+    /// default metadata (for example purposes only).
+    #[init]
+    pub fn new_default_meta(owner_id: AccountId) -> Self {
+        Self::new(
+            owner_id,
+            MTContractMetadata {
+                spec: MT_METADATA_SPEC.to_string(),
+                name: "Example NEAR game items".to_string(),
+            },
+        )
+    }
+
+    #[init]
+    pub fn new(owner_id: AccountId, metadata: MTContractMetadata) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+        metadata.assert_valid();
+        Self {
+            tokens: MultiToken::new(
+                StorageKey::MultiToken,
+                owner_id,
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
+        }
+    }
+
+    /// Mint `amount` of a new token with ID=`token_id` belonging to `token_owner_id`.
+    ///
+    /// Since this example implements metadata, it also requires per-token metadata to be provided
+    /// in this call. `self.tokens.internal_mint` will also require it to be Some, since
+    /// `StorageKey::TokenMetadata` was provided at initialization.
+    #[payable]
+    pub fn mt_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        amount: U128,
+        token_metadata: TokenMetadata,
+    ) -> Token {
+        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized");
+        token_metadata.assert_valid();
+        self.tokens.internal_mint(token_id, token_owner_id, amount.into(), Some(token_metadata))
+    }
+
+    /// Burn `amount` of the token with ID=`token_id` owned by the caller.
+    #[payable]
+    pub fn mt_burn(&mut self, token_id: TokenId, amount: U128, memo: Option<String>) {
+        near_sdk::assert_one_yocto();
+        self.tokens.internal_burn(&env::predecessor_account_id(), &token_id, amount.into(), memo)
+    }
+}
+
+near_contract_standards::impl_multi_token_core!(Contract, tokens);
+near_contract_standards::impl_multi_token_approval!(Contract, tokens);
+near_contract_standards::impl_multi_token_enumeration!(Contract, tokens);
+
+#[near_bindgen]
+impl MultiTokenMetadataProvider for Contract {
+    fn mt_metadata_contract(&self) -> MTContractMetadata {
+        self.metadata.get().unwrap()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, ONE_YOCTO};
+
+    use super::*;
+
+    const MINT_STORAGE_COST: u128 = 10_000_000_000_000_000_000_000;
+    const APPROVAL_STORAGE_COST: u128 = 1_000_000_000_000_000_000_000;
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
+
+    fn sample_token_metadata() -> TokenMetadata {
+        TokenMetadata {
+            title: Some("Iron sword".into()),
+            description: Some("A plain but reliable sword".into()),
+            media: None,
+            media_hash: None,
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    /// Initializes the contract and mints 100 of the token "sword" to `accounts(0)`.
+    fn setup_contract(context: &mut VMContextBuilder) -> Contract {
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.mt_mint("sword".to_string(), accounts(0), U128(100), sample_token_metadata());
+        contract
+    }
+
+    fn set_caller(context: &mut VMContextBuilder, caller: AccountId, deposit: u128) {
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(deposit)
+            .predecessor_account_id(caller)
+            .build());
+    }
+
+    #[test]
+    fn test_new() {
+        let mut context = get_context(accounts(1));
+        testing_env!(context.build());
+        let contract = Contract::new_default_meta(accounts(1));
+        testing_env!(context.is_view(true).build());
+        assert_eq!(contract.mt_token(vec!["1".to_string()]), vec![None]);
+        assert_eq!(contract.mt_metadata_contract().spec, MT_METADATA_SPEC);
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is not initialized")]
+    fn test_default() {
+        let context = get_context(accounts(1));
+        testing_env!(context.build());
+        let _contract = Contract::default();
+    }
+
+    #[test]
+    fn test_mint() {
+        let mut context = get_context(accounts(0));
+        let contract = setup_contract(&mut context);
+
+        let token = contract.mt_token(vec!["sword".to_string()]).remove(0).unwrap();
+        assert_eq!(token.owner_id, accounts(0));
+        assert_eq!(token.supply, U128(100));
+        assert_eq!(token.metadata.unwrap(), sample_token_metadata());
+        assert_eq!(contract.mt_balance_of(accounts(0), "sword".to_string()), U128(100));
+        assert_eq!(contract.mt_supply("sword".to_string()), Some(U128(100)));
+    }
+
+    #[test]
+    #[should_panic(expected = "token_id must be unique")]
+    fn test_mint_twice() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+        set_caller(&mut context, accounts(0), MINT_STORAGE_COST);
+        contract.mt_mint("sword".to_string(), accounts(1), U128(1), sample_token_metadata());
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_transfer(accounts(1), "sword".to_string(), U128(30), None, None);
+
+        assert_eq!(contract.mt_balance_of(accounts(0), "sword".to_string()), U128(70));
+        assert_eq!(contract.mt_balance_of(accounts(1), "sword".to_string()), U128(30));
+        assert_eq!(contract.mt_supply("sword".to_string()), Some(U128(100)));
+        assert_eq!(contract.mt_tokens_for_owner(accounts(1), None, None).len(), 1);
+    }
+
+    #[test]
+    fn test_batch_transfer() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+        set_caller(&mut context, accounts(0), MINT_STORAGE_COST);
+        contract.mt_mint("shield".to_string(), accounts(0), U128(1), sample_token_metadata());
+
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_batch_transfer(
+            accounts(1),
+            vec!["sword".to_string(), "shield".to_string()],
+            vec![U128(10), U128(1)],
+            None,
+            Some("starter kit".to_string()),
+        );
+
+        assert_eq!(
+            contract
+                .mt_batch_balance_of(accounts(1), vec!["sword".to_string(), "shield".to_string()]),
+            vec![U128(10), U128(1)]
+        );
+        // The sender no longer owns any shield.
+        let tokens: Vec<_> = contract
+            .mt_tokens_for_owner(accounts(0), None, None)
+            .into_iter()
+            .map(|token| token.token_id)
+            .collect();
+        assert_eq!(tokens, vec!["sword".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn test_transfer_insufficient_balance() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_transfer(accounts(1), "sword".to_string(), U128(101), None, None);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_burn("sword".to_string(), U128(40), None);
+
+        assert_eq!(contract.mt_balance_of(accounts(0), "sword".to_string()), U128(60));
+        assert_eq!(contract.mt_supply("sword".to_string()), Some(U128(60)));
+    }
+
+    #[test]
+    fn test_approve_and_transfer() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        // alice approves bob for 20 swords
+        set_caller(&mut context, accounts(0), APPROVAL_STORAGE_COST);
+        contract.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(1), None);
+        assert!(contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(20)],
+            Some(vec![1])
+        ));
+
+        // bob transfers 15 of them to charlie
+        set_caller(&mut context, accounts(1), ONE_YOCTO);
+        contract.mt_transfer(
+            accounts(2),
+            "sword".to_string(),
+            U128(15),
+            Some((accounts(0), 1)),
+            None,
+        );
+
+        assert_eq!(contract.mt_balance_of(accounts(0), "sword".to_string()), U128(85));
+        assert_eq!(contract.mt_balance_of(accounts(2), "sword".to_string()), U128(15));
+        assert!(contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(5)],
+            None
+        ));
+        assert!(!contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(6)],
+            None
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough approved amount")]
+    fn test_transfer_exceeding_approval() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        set_caller(&mut context, accounts(0), APPROVAL_STORAGE_COST);
+        contract.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(1), None);
+
+        set_caller(&mut context, accounts(1), ONE_YOCTO);
+        contract.mt_transfer(
+            accounts(2),
+            "sword".to_string(),
+            U128(21),
+            Some((accounts(0), 1)),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_revoke() {
+        let mut context = get_context(accounts(0));
+        let mut contract = setup_contract(&mut context);
+
+        // alice approves bob and charlie
+        set_caller(&mut context, accounts(0), APPROVAL_STORAGE_COST);
+        contract.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(1), None);
+        set_caller(&mut context, accounts(0), APPROVAL_STORAGE_COST);
+        contract.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(2), None);
+
+        // alice revokes bob
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_revoke(vec!["sword".to_string()], accounts(1));
+        assert!(!contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(1)],
+            None
+        ));
+        assert!(contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(2),
+            vec![U128(1)],
+            None
+        ));
+
+        // alice revokes everyone
+        set_caller(&mut context, accounts(0), ONE_YOCTO);
+        contract.mt_revoke_all(vec!["sword".to_string()]);
+        assert!(!contract.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(2),
+            vec![U128(1)],
+            None
+        ));
+    }
+}
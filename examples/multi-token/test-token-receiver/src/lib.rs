@@ -0,0 +1,81 @@
+/*!
+A stub contract that implements mt_on_transfer for testing mt_transfer_call and mt_batch_transfer_call.
+*/
+use near_contract_standards::multi_token::core::MultiTokenReceiver;
+use near_contract_standards::multi_token::TokenId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, log, near_bindgen, require, AccountId, Gas, PanicOnDefault, PromiseOrValue};
+
+const BASE_GAS: u64 = 5_000_000_000_000;
+const PROMISE_CALL: u64 = 5_000_000_000_000;
+const GAS_FOR_MT_ON_TRANSFER: Gas = Gas(BASE_GAS + PROMISE_CALL);
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct TokenReceiver {
+    multi_token_account_id: AccountId,
+}
+
+#[near_bindgen]
+impl TokenReceiver {
+    #[init]
+    pub fn new(multi_token_account_id: AccountId) -> Self {
+        Self { multi_token_account_id }
+    }
+
+    pub fn ok_go(&self, unused_amounts: Vec<U128>) -> PromiseOrValue<Vec<U128>> {
+        log!("in ok_go, unused_amounts={:?}", unused_amounts);
+        PromiseOrValue::Value(unused_amounts)
+    }
+}
+
+#[near_bindgen]
+impl MultiTokenReceiver for TokenReceiver {
+    /// Returns the amounts of each token that should be returned to their previous owners.
+    /// Five supported `msg`s:
+    /// * "return-it-now" - immediately return all of the tokens
+    /// * "keep-it-now" - immediately keep all of the tokens
+    /// * "return-half-now" - immediately return half of each amount, rounded down
+    /// * "return-it-later" - make cross-contract call which resolves with all of the tokens
+    /// * "keep-it-later" - make cross-contract call which resolves with none of the tokens
+    /// Otherwise panics, which should also return the tokens to their previous owners
+    fn mt_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_ids: Vec<AccountId>,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        // Verifying that we were called by multi token contract that we expect.
+        require!(
+            env::predecessor_account_id() == self.multi_token_account_id,
+            "Only supports the one multi token contract"
+        );
+        log!(
+            "in mt_on_transfer; sender_id={}, previous_owner_ids={:?}, token_ids={:?}, msg={}",
+            &sender_id,
+            &previous_owner_ids,
+            &token_ids,
+            msg
+        );
+        let keep_all = || amounts.iter().map(|_| U128(0)).collect::<Vec<_>>();
+        match msg.as_str() {
+            "return-it-now" => PromiseOrValue::Value(amounts),
+            "keep-it-now" => PromiseOrValue::Value(keep_all()),
+            "return-half-now" => {
+                PromiseOrValue::Value(amounts.iter().map(|amount| U128(amount.0 / 2)).collect())
+            }
+            "return-it-later" => Self::ext(env::current_account_id())
+                .with_static_gas(env::prepaid_gas() - GAS_FOR_MT_ON_TRANSFER)
+                .ok_go(amounts)
+                .into(),
+            "keep-it-later" => Self::ext(env::current_account_id())
+                .with_static_gas(env::prepaid_gas() - GAS_FOR_MT_ON_TRANSFER)
+                .ok_go(keep_all())
+                .into(),
+            _ => env::panic_str("unsupported msg"),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod test_core;
+mod utils;
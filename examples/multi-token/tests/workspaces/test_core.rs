@@ -0,0 +1,223 @@
+use crate::utils::{balance_of, init, SHIELD, SWORD};
+use near_contract_standards::multi_token::Token;
+use near_primitives::views::FinalExecutionStatus;
+use near_sdk::json_types::U128;
+use near_sdk::ONE_YOCTO;
+use workspaces::{Contract, DevNetwork, Worker};
+
+/// Calls `mt_batch_transfer_call` sending 10 swords and the shield to the token receiver.
+async fn batch_transfer_call(
+    mt_contract: &Contract,
+    worker: &Worker<impl DevNetwork>,
+    token_receiver_contract: &Contract,
+    msg: &str,
+    gas: u64,
+) -> anyhow::Result<()> {
+    let res = mt_contract
+        .call(&worker, "mt_batch_transfer_call")
+        .args_json((
+            token_receiver_contract.id(),
+            [SWORD, SHIELD],
+            [U128(10), U128(1)],
+            Option::<Vec<Option<(String, u64)>>>::None,
+            Some("transfer & call"),
+            msg,
+        ))?
+        .gas(gas)
+        .deposit(ONE_YOCTO)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_simple_transfer() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, alice, _) = init(&worker).await?;
+
+    let tokens = mt_contract
+        .call(&worker, "mt_token")
+        .args_json(([SWORD],))?
+        .view()
+        .await?
+        .json::<Vec<Option<Token>>>()?;
+    let token = tokens[0].as_ref().unwrap();
+    assert_eq!(token.owner_id.to_string(), mt_contract.id().to_string());
+    assert_eq!(token.supply, U128(100));
+
+    let res = mt_contract
+        .call(&worker, "mt_transfer")
+        .args_json((
+            alice.id(),
+            SWORD,
+            U128(30),
+            Option::<(String, u64)>::None,
+            Some("simple transfer".to_string()),
+        ))?
+        .gas(300_000_000_000_000)
+        .deposit(ONE_YOCTO)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 70);
+    assert_eq!(balance_of(&mt_contract, &worker, alice.id(), SWORD).await?, 30);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_batch_transfer() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, alice, _) = init(&worker).await?;
+
+    let res = mt_contract
+        .call(&worker, "mt_batch_transfer")
+        .args_json((
+            alice.id(),
+            [SWORD, SHIELD],
+            [U128(10), U128(1)],
+            Option::<Vec<Option<(String, u64)>>>::None,
+            Option::<String>::None,
+        ))?
+        .gas(300_000_000_000_000)
+        .deposit(ONE_YOCTO)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    let balances = mt_contract
+        .call(&worker, "mt_batch_balance_of")
+        .args_json((alice.id(), [SWORD, SHIELD]))?
+        .view()
+        .await?
+        .json::<Vec<U128>>()?;
+    assert_eq!(balances, vec![U128(10), U128(1)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_fast_return_to_sender() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "return-it-now",
+        300_000_000_000_000,
+    )
+    .await?;
+
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 100);
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SHIELD).await?, 1);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SWORD).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_slow_return_to_sender() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "return-it-later",
+        300_000_000_000_000,
+    )
+    .await?;
+
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 100);
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SHIELD).await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_fast_keep_with_sender() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "keep-it-now",
+        300_000_000_000_000,
+    )
+    .await?;
+
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 90);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SWORD).await?, 10);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SHIELD).await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_slow_keep_with_sender() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "keep-it-later",
+        300_000_000_000_000,
+    )
+    .await?;
+
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SWORD).await?, 10);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SHIELD).await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_partial_return() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "return-half-now",
+        300_000_000_000_000,
+    )
+    .await?;
+
+    // Half of the 10 swords are returned, the single shield rounds down to being kept.
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 95);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SWORD).await?, 5);
+    assert_eq!(balance_of(&mt_contract, &worker, token_receiver_contract.id(), SHIELD).await?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn simulate_transfer_call_receiver_panics() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox();
+    let (mt_contract, _, token_receiver_contract) = init(&worker).await?;
+
+    batch_transfer_call(
+        &mt_contract,
+        &worker,
+        &token_receiver_contract,
+        "incorrect message",
+        35_000_000_000_000 + 1,
+    )
+    .await?;
+
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SWORD).await?, 100);
+    assert_eq!(balance_of(&mt_contract, &worker, mt_contract.id(), SHIELD).await?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use near_contract_standards::multi_token::metadata::TokenMetadata;
+use near_contract_standards::multi_token::TokenId;
+use near_primitives::views::FinalExecutionStatus;
+use near_sdk::json_types::U128;
+use near_units::parse_near;
+use workspaces::prelude::DevAccountDeployer;
+use workspaces::{Account, AccountId, Contract, DevNetwork, Worker};
+
+pub const SWORD: &str = "sword";
+pub const SHIELD: &str = "shield";
+
+fn token_metadata(title: &str) -> TokenMetadata {
+    TokenMetadata {
+        title: Some(title.to_string()),
+        description: None,
+        media: None,
+        media_hash: None,
+        issued_at: None,
+        expires_at: None,
+        starts_at: None,
+        updated_at: None,
+        extra: None,
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+pub async fn helper_mint(
+    mt_contract: &Contract,
+    worker: &Worker<impl DevNetwork>,
+    token_id: TokenId,
+    amount: u128,
+) -> anyhow::Result<()> {
+    let res = mt_contract
+        .call(&worker, "mt_mint")
+        .args_json((&token_id, mt_contract.id(), U128(amount), token_metadata(&token_id)))?
+        .gas(300_000_000_000_000)
+        .deposit(parse_near!("10 mN"))
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    Ok(())
+}
+
+pub async fn balance_of(
+    mt_contract: &Contract,
+    worker: &Worker<impl DevNetwork>,
+    account_id: &AccountId,
+    token_id: &str,
+) -> anyhow::Result<u128> {
+    let balance = mt_contract
+        .call(&worker, "mt_balance_of")
+        .args_json((account_id, token_id))?
+        .view()
+        .await?
+        .json::<U128>()?;
+    Ok(balance.0)
+}
+
+/// Deploy and initialize contracts and return:
+/// * mt_contract: the MT contract, owning 100 swords and a single shield
+/// * alice: a user account, does not yet own any tokens
+/// * token_receiver_contract: a contract implementing `mt_on_transfer` for use with `mt_transfer_call`
+///
+/// The contracts are read from `res/`, run `./build.sh` first.
+pub async fn init(
+    worker: &Worker<impl DevNetwork>,
+) -> anyhow::Result<(Contract, Account, Contract)> {
+    let mt_contract = worker.dev_deploy(std::fs::read("res/multi_token.wasm")?).await?;
+
+    let res = mt_contract
+        .call(&worker, "new_default_meta")
+        .args_json((mt_contract.id(),))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    helper_mint(&mt_contract, worker, SWORD.to_string(), 100).await?;
+    helper_mint(&mt_contract, worker, SHIELD.to_string(), 1).await?;
+
+    let res = mt_contract
+        .as_account()
+        .create_subaccount(&worker, "alice")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?;
+    assert!(matches!(res.details.status, FinalExecutionStatus::SuccessValue(_)));
+    let alice = res.result;
+
+    let token_receiver_contract =
+        worker.dev_deploy(std::fs::read("res/mt_token_receiver.wasm")?).await?;
+    let res = token_receiver_contract
+        .call(&worker, "new")
+        .args_json((mt_contract.id(),))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
+
+    return Ok((mt_contract, alice, token_receiver_contract));
+}
@@ -120,6 +120,7 @@ mod tests {
     use super::*;
 
     const MINT_STORAGE_COST: u128 = 5870000000000000000000;
+    const APPROVAL_STORAGE_COST: u128 = 2000000000000000000000;
 
     fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
@@ -238,7 +239,7 @@ mod tests {
         // alice approves bob
         testing_env!(context
             .storage_usage(env::storage_usage())
-            .attached_deposit(150000000000000000000)
+            .attached_deposit(APPROVAL_STORAGE_COST)
             .predecessor_account_id(accounts(0))
             .build());
         contract.nft_approve(token_id.clone(), accounts(1), None);
@@ -269,7 +270,7 @@ mod tests {
         // alice approves bob
         testing_env!(context
             .storage_usage(env::storage_usage())
-            .attached_deposit(150000000000000000000)
+            .attached_deposit(APPROVAL_STORAGE_COST)
             .predecessor_account_id(accounts(0))
             .build());
         contract.nft_approve(token_id.clone(), accounts(1), None);
@@ -307,7 +308,7 @@ mod tests {
         // alice approves bob
         testing_env!(context
             .storage_usage(env::storage_usage())
-            .attached_deposit(150000000000000000000)
+            .attached_deposit(APPROVAL_STORAGE_COST)
             .predecessor_account_id(accounts(0))
             .build());
         contract.nft_approve(token_id.clone(), accounts(1), None);
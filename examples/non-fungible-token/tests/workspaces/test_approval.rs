@@ -17,7 +17,7 @@ async fn simulate_simple_approve() -> anyhow::Result<()> {
         .call(&worker, "nft_approve")
         .args_json((TOKEN_ID, alice.id(), Option::<String>::None))?
         .gas(300_000_000_000_000)
-        .deposit(510000000000000000000)
+        .deposit(2000000000000000000000)
         .transact()
         .await?;
     assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
@@ -110,7 +110,7 @@ async fn simulate_approval_with_call() -> anyhow::Result<()> {
         .call(&worker, "nft_approve")
         .args_json((TOKEN_ID, approval_receiver_contract.id(), Some("return-now".to_string())))?
         .gas(300_000_000_000_000)
-        .deposit(450000000000000000000)
+        .deposit(2000000000000000000000)
         .transact()
         .await?;
     assert_eq!(res.json::<String>()?, "cool".to_string());
@@ -141,7 +141,7 @@ async fn simulate_approved_account_transfers_token() -> anyhow::Result<()> {
         .call(&worker, "nft_approve")
         .args_json((TOKEN_ID, alice.id(), Option::<String>::None))?
         .gas(300_000_000_000_000)
-        .deposit(510000000000000000000)
+        .deposit(2000000000000000000000)
         .transact()
         .await?;
     assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
@@ -178,7 +178,7 @@ async fn simulate_revoke() -> anyhow::Result<()> {
         .call(&worker, "nft_approve")
         .args_json((TOKEN_ID, alice.id(), Option::<String>::None))?
         .gas(300_000_000_000_000)
-        .deposit(510000000000000000000)
+        .deposit(2000000000000000000000)
         .transact()
         .await?;
     assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
@@ -262,7 +262,7 @@ async fn simulate_revoke_all() -> anyhow::Result<()> {
         .call(&worker, "nft_approve")
         .args_json((TOKEN_ID, alice.id(), Option::<String>::None))?
         .gas(300_000_000_000_000)
-        .deposit(510000000000000000000)
+        .deposit(2000000000000000000000)
         .transact()
         .await?;
     assert!(matches!(res.status, FinalExecutionStatus::SuccessValue(_)));
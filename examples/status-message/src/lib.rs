@@ -61,4 +61,15 @@ mod tests {
         assert_eq!(None, contract.get_status("francis.near".parse().unwrap()));
         assert_eq!(get_logs(), vec!["get_status for account_id francis.near"])
     }
+
+    #[test]
+    fn abi_describes_methods() {
+        let abi = contract_abi();
+        let names: Vec<_> = abi.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["set_status", "get_status"]);
+        assert!(abi.functions[0].is_payable);
+        assert!(abi.functions[1].is_view);
+        assert_eq!(abi.functions[1].params[0].name, "account_id");
+        assert!(abi.definitions.contains_key("AccountId"));
+    }
 }
@@ -0,0 +1,207 @@
+//! Events of the access control module, logged when the ownership, the roles or the pause state
+//! of the contract change.
+//!
+//! These events follow the events format (nep-297):
+//! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
+//!
+//! The events are [`OwnershipTransferStarted`], [`OwnershipTransferred`], [`RoleGranted`],
+//! [`RoleRevoked`], [`Paused`] and [`Unpaused`]. They are logged by calling `.emit()` on them.
+
+use crate::event::NearEvent;
+use near_sdk::AccountId;
+use serde::Serialize;
+
+/// Data to log when a new owner is proposed. To log this event,
+/// call [`.emit()`](OwnershipTransferStarted::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct OwnershipTransferStarted<'a> {
+    pub previous_owner_id: &'a AccountId,
+    pub new_owner_id: &'a AccountId,
+}
+
+impl OwnershipTransferStarted<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::OwnershipTransferStarted(&[self])).emit()
+    }
+}
+
+/// Data to log when the ownership is transferred, or renounced if there is no new owner.
+/// To log this event, call [`.emit()`](OwnershipTransferred::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct OwnershipTransferred<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_owner_id: Option<&'a AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_owner_id: Option<&'a AccountId>,
+}
+
+impl OwnershipTransferred<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::OwnershipTransferred(&[self])).emit()
+    }
+}
+
+/// Data to log when a role is granted. To log this event, call [`.emit()`](RoleGranted::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct RoleGranted<'a> {
+    pub role: &'a str,
+    pub account_id: &'a AccountId,
+    pub sender_id: &'a AccountId,
+}
+
+impl RoleGranted<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::RoleGranted(&[self])).emit()
+    }
+}
+
+/// Data to log when a role is revoked or renounced. To log this event,
+/// call [`.emit()`](RoleRevoked::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct RoleRevoked<'a> {
+    pub role: &'a str,
+    pub account_id: &'a AccountId,
+    pub sender_id: &'a AccountId,
+}
+
+impl RoleRevoked<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::RoleRevoked(&[self])).emit()
+    }
+}
+
+/// Data to log when the contract is paused. To log this event, call [`.emit()`](Paused::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct Paused<'a> {
+    pub account_id: &'a AccountId,
+}
+
+impl Paused<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::Paused(&[self])).emit()
+    }
+}
+
+/// Data to log when the contract is unpaused. To log this event,
+/// call [`.emit()`](Unpaused::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct Unpaused<'a> {
+    pub account_id: &'a AccountId,
+}
+
+impl Unpaused<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_access_control_v1(AccessControlEventKind::Unpaused(&[self])).emit()
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct AccessControlEvent<'a> {
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: AccessControlEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+enum AccessControlEventKind<'a> {
+    OwnershipTransferStarted(&'a [OwnershipTransferStarted<'a>]),
+    OwnershipTransferred(&'a [OwnershipTransferred<'a>]),
+    RoleGranted(&'a [RoleGranted<'a>]),
+    RoleRevoked(&'a [RoleRevoked<'a>]),
+    Paused(&'a [Paused<'a>]),
+    Unpaused(&'a [Unpaused<'a>]),
+}
+
+fn new_access_control<'a>(
+    version: &'static str,
+    event_kind: AccessControlEventKind<'a>,
+) -> NearEvent<'a> {
+    NearEvent::AccessControl(AccessControlEvent { version, event_kind })
+}
+
+fn new_access_control_v1(event_kind: AccessControlEventKind) -> NearEvent {
+    new_access_control("1.0.0", event_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils, AccountId};
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice".to_string())
+    }
+
+    #[test]
+    fn ownership_transfer_started() {
+        OwnershipTransferStarted { previous_owner_id: &bob(), new_owner_id: &alice() }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"ownership_transfer_started","data":[{"previous_owner_id":"bob","new_owner_id":"alice"}]}"#
+        );
+    }
+
+    #[test]
+    fn ownership_renounced() {
+        OwnershipTransferred { previous_owner_id: Some(&bob()), new_owner_id: None }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"ownership_transferred","data":[{"previous_owner_id":"bob"}]}"#
+        );
+    }
+
+    #[test]
+    fn role_granted() {
+        RoleGranted { role: "minter", account_id: &alice(), sender_id: &bob() }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"role_granted","data":[{"role":"minter","account_id":"alice","sender_id":"bob"}]}"#
+        );
+    }
+
+    #[test]
+    fn role_revoked() {
+        RoleRevoked { role: "minter", account_id: &alice(), sender_id: &alice() }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"role_revoked","data":[{"role":"minter","account_id":"alice","sender_id":"alice"}]}"#
+        );
+    }
+
+    #[test]
+    fn paused() {
+        Paused { account_id: &bob() }.emit();
+        Unpaused { account_id: &alice() }.emit();
+        assert_eq!(
+            test_utils::get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"paused","data":[{"account_id":"bob"}]}"#,
+                r#"EVENT_JSON:{"standard":"access_control","version":"1.0.0","event":"unpaused","data":[{"account_id":"alice"}]}"#
+            ]
+        );
+    }
+}
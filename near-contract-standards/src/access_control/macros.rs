@@ -0,0 +1,99 @@
+/// Implements [`Ownable`](crate::access_control::Ownable) for the contract, delegating to its
+/// [`Ownership`](crate::access_control::Ownership) field. The methods of the contract marked with
+/// `#[only(owner)]` are restricted to the owner.
+#[macro_export]
+macro_rules! impl_ownable {
+    ($contract: ident, $ownership: ident) => {
+        use $crate::access_control::Ownable;
+
+        #[near_bindgen]
+        impl Ownable for $contract {
+            fn owner(&self) -> Option<AccountId> {
+                self.$ownership.owner()
+            }
+
+            fn pending_owner(&self) -> Option<AccountId> {
+                self.$ownership.pending_owner()
+            }
+
+            #[payable]
+            fn transfer_ownership(&mut self, new_owner_id: AccountId) {
+                self.$ownership.transfer_ownership(new_owner_id)
+            }
+
+            #[payable]
+            fn accept_ownership(&mut self) {
+                self.$ownership.accept_ownership()
+            }
+
+            #[payable]
+            fn renounce_ownership(&mut self) {
+                self.$ownership.renounce_ownership()
+            }
+        }
+    };
+}
+
+/// Implements [`AccessControl`](crate::access_control::AccessControl) for the contract,
+/// delegating to its [`Roles`](crate::access_control::Roles) field. The methods of the contract
+/// marked with `#[only(role = "...")]` are restricted to the members of the role.
+#[macro_export]
+macro_rules! impl_access_control {
+    ($contract: ident, $roles: ident) => {
+        use $crate::access_control::AccessControl;
+
+        #[near_bindgen]
+        impl AccessControl for $contract {
+            fn has_role(&self, role: $crate::access_control::Role, account_id: AccountId) -> bool {
+                self.$roles.has_role(role, account_id)
+            }
+
+            #[payable]
+            fn grant_role(&mut self, role: $crate::access_control::Role, account_id: AccountId) {
+                self.$roles.grant_role(role, account_id)
+            }
+
+            #[payable]
+            fn revoke_role(&mut self, role: $crate::access_control::Role, account_id: AccountId) {
+                self.$roles.revoke_role(role, account_id)
+            }
+
+            #[payable]
+            fn renounce_role(&mut self, role: $crate::access_control::Role) {
+                self.$roles.renounce_role(role)
+            }
+        }
+    };
+}
+
+/// Implements [`Pausable`](crate::access_control::Pausable) for the contract, delegating to its
+/// [`Pause`](crate::access_control::Pause) field. The accounts allowed to pause and unpause the
+/// contract are given like in the `#[only]` attribute, for example
+/// `impl_pausable!(Contract, pause, owner)` or `impl_pausable!(Contract, pause, role = "pauser")`.
+#[macro_export]
+macro_rules! impl_pausable {
+    ($contract: ident, $pause: ident, $($only: tt)+) => {
+        use $crate::access_control::Pausable;
+
+        #[near_bindgen]
+        impl Pausable for $contract {
+            fn paused(&self) -> bool {
+                self.$pause.is_paused()
+            }
+
+            #[payable]
+            #[only($($only)+)]
+            fn pause(&mut self) {
+                near_sdk::assert_one_yocto();
+                self.$pause.internal_pause()
+            }
+
+            #[payable]
+            #[only($($only)+)]
+            fn unpause(&mut self) {
+                near_sdk::assert_one_yocto();
+                self.$pause.internal_unpause()
+            }
+        }
+    };
+}
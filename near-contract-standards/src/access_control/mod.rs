@@ -0,0 +1,39 @@
+//! Access control of the methods of a contract.
+//!
+//! - [`Ownable`] is a single owner with a two-step transfer of the ownership, implemented with
+//!   [`impl_ownable`](crate::impl_ownable).
+//! - [`AccessControl`] grants and revokes named roles to accounts, implemented with
+//!   [`impl_access_control`](crate::impl_access_control).
+//! - [`Pausable`] stops the contract in an emergency, implemented with
+//!   [`impl_pausable`](crate::impl_pausable).
+//!
+//! The methods of a `#[near_bindgen]` contract are restricted with the `#[only(owner)]`,
+//! `#[only(role = "...")]`, `#[when_not_paused]` and `#[when_paused]` attributes, which call
+//! [`Ownable::assert_owner`], [`AccessControl::assert_role`], [`Pausable::assert_not_paused`] and
+//! [`Pausable::assert_paused`] on the contract state before the method. The changes are logged as
+//! [`events`].
+//!
+//! ```ignore
+//! #[near_bindgen]
+//! impl Contract {
+//!     #[only(role = "minter")]
+//!     #[when_not_paused]
+//!     pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+//!         // ...
+//!     }
+//! }
+//!
+//! near_contract_standards::impl_ownable!(Contract, ownership);
+//! near_contract_standards::impl_access_control!(Contract, roles);
+//! near_contract_standards::impl_pausable!(Contract, pause, owner);
+//! ```
+
+pub mod events;
+mod macros;
+mod ownable;
+mod pausable;
+mod roles;
+
+pub use ownable::{Ownable, Ownership};
+pub use pausable::{Pausable, Pause};
+pub use roles::{AccessControl, Role, Roles, DEFAULT_ADMIN_ROLE};
@@ -0,0 +1,188 @@
+use crate::access_control::events::{OwnershipTransferStarted, OwnershipTransferred};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{assert_one_yocto, env, require, AccountId};
+
+/// Ownership of a contract that is transferred in two steps: the owner proposes a new owner, which
+/// becomes the owner once it accepts the ownership. This prevents transferring the ownership to an
+/// account that can't use it.
+pub trait Ownable {
+    /// Returns the owner of the contract, `None` if the ownership was renounced.
+    fn owner(&self) -> Option<AccountId>;
+
+    /// Returns the account proposed as the new owner which hasn't accepted the ownership yet.
+    fn pending_owner(&self) -> Option<AccountId>;
+
+    /// Proposes `new_owner_id` as the new owner of the contract, replacing the previous proposal.
+    /// Can only be called by the owner with exactly 1 yoctoNEAR attached.
+    fn transfer_ownership(&mut self, new_owner_id: AccountId);
+
+    /// Accepts the ownership proposed to the predecessor. Requires exactly 1 yoctoNEAR attached.
+    fn accept_ownership(&mut self);
+
+    /// Leaves the contract without an owner, so that the methods restricted to the owner can't be
+    /// called anymore. Can only be called by the owner with exactly 1 yoctoNEAR attached.
+    fn renounce_ownership(&mut self);
+
+    /// Panics if the predecessor is not the owner. Called by the methods marked with
+    /// `#[only(owner)]`.
+    fn assert_owner(&self) {
+        require!(
+            self.owner() == Some(env::predecessor_account_id()),
+            "Method is only available to the owner"
+        );
+    }
+}
+
+/// Implementation of [`Ownable`] to be stored in the state of the contract.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Ownership {
+    owner_id: Option<AccountId>,
+    pending_owner_id: Option<AccountId>,
+}
+
+impl Ownership {
+    pub fn new(owner_id: AccountId) -> Self {
+        Self { owner_id: Some(owner_id), pending_owner_id: None }
+    }
+
+    /// Sets the owner of the contract without the confirmation of the new owner and cancels the
+    /// pending transfer. Doesn't check the predecessor.
+    pub fn internal_set_owner(&mut self, new_owner_id: Option<AccountId>) {
+        OwnershipTransferred {
+            previous_owner_id: self.owner_id.as_ref(),
+            new_owner_id: new_owner_id.as_ref(),
+        }
+        .emit();
+        self.owner_id = new_owner_id;
+        self.pending_owner_id = None;
+    }
+}
+
+impl Ownable for Ownership {
+    fn owner(&self) -> Option<AccountId> {
+        self.owner_id.clone()
+    }
+
+    fn pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    fn transfer_ownership(&mut self, new_owner_id: AccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        let owner_id = self.owner_id.as_ref().unwrap_or_else(|| env::abort());
+        OwnershipTransferStarted { previous_owner_id: owner_id, new_owner_id: &new_owner_id }
+            .emit();
+        self.pending_owner_id = Some(new_owner_id);
+    }
+
+    fn accept_ownership(&mut self) {
+        assert_one_yocto();
+        let predecessor = env::predecessor_account_id();
+        require!(
+            self.pending_owner_id.as_ref() == Some(&predecessor),
+            "The ownership is not proposed to the predecessor"
+        );
+        self.internal_set_owner(Some(predecessor));
+    }
+
+    fn renounce_ownership(&mut self) {
+        assert_one_yocto();
+        self.assert_owner();
+        self.internal_set_owner(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::set_caller;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn transfer_ownership() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+        assert_eq!(ownership.owner(), Some(accounts(0)));
+        assert_eq!(ownership.pending_owner(), Some(accounts(1)));
+
+        set_caller(&mut context, accounts(1), 1);
+        ownership.accept_ownership();
+        assert_eq!(ownership.owner(), Some(accounts(1)));
+        assert_eq!(ownership.pending_owner(), None);
+        ownership.assert_owner();
+    }
+
+    #[test]
+    #[should_panic(expected = "The ownership is not proposed to the predecessor")]
+    fn accept_ownership_by_other_account() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+
+        set_caller(&mut context, accounts(2), 1);
+        ownership.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the owner")]
+    fn transfer_ownership_by_other_account() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+
+        set_caller(&mut context, accounts(1), 1);
+        ownership.transfer_ownership(accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The ownership is not proposed to the predecessor")]
+    fn cancel_ownership_transfer_with_new_proposal() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+        ownership.transfer_ownership(accounts(2));
+        assert_eq!(ownership.pending_owner(), Some(accounts(2)));
+
+        set_caller(&mut context, accounts(1), 1);
+        ownership.accept_ownership();
+    }
+
+    #[test]
+    fn cancel_ownership_transfer_with_set_owner() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+        ownership.internal_set_owner(Some(accounts(2)));
+        assert_eq!(ownership.owner(), Some(accounts(2)));
+        assert_eq!(ownership.pending_owner(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the owner")]
+    fn renounce_ownership() {
+        let mut context = VMContextBuilder::new();
+        set_caller(&mut context, accounts(0), 1);
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+        ownership.renounce_ownership();
+        assert_eq!(ownership.owner(), None);
+        assert_eq!(ownership.pending_owner(), None);
+
+        ownership.assert_owner();
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn transfer_ownership_without_deposit() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let mut ownership = Ownership::new(accounts(0));
+        ownership.transfer_ownership(accounts(1));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::access_control::events::{Paused, Unpaused};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, require};
+
+/// Emergency stop of the methods of the contract marked with `#[when_not_paused]`, while the
+/// methods marked with `#[when_paused]` can only be called when the contract is paused.
+pub trait Pausable {
+    /// Returns whether the contract is paused.
+    fn paused(&self) -> bool;
+
+    /// Pauses the contract. Requires exactly 1 yoctoNEAR attached.
+    fn pause(&mut self);
+
+    /// Unpauses the contract. Requires exactly 1 yoctoNEAR attached.
+    fn unpause(&mut self);
+
+    /// Panics if the contract is paused. Called by the methods marked with `#[when_not_paused]`.
+    fn assert_not_paused(&self) {
+        require!(!self.paused(), "The contract is paused");
+    }
+
+    /// Panics if the contract is not paused. Called by the methods marked with `#[when_paused]`.
+    fn assert_paused(&self) {
+        require!(self.paused(), "The contract is not paused");
+    }
+}
+
+/// The pause state of the contract, to be stored in the state of the contract. [`Pausable`] is
+/// implemented on the contract with [`impl_pausable`](crate::impl_pausable), which restricts the
+/// accounts allowed to pause the contract.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Pause {
+    paused: bool,
+}
+
+impl Pause {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the contract is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses the contract without checking the predecessor.
+    pub fn internal_pause(&mut self) {
+        require!(!self.paused, "The contract is paused");
+        self.paused = true;
+        Paused { account_id: &env::predecessor_account_id() }.emit();
+    }
+
+    /// Unpauses the contract without checking the predecessor.
+    pub fn internal_unpause(&mut self) {
+        require!(self.paused, "The contract is not paused");
+        self.paused = false;
+        Unpaused { account_id: &env::predecessor_account_id() }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn pause_and_unpause() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let mut pause = Pause::new();
+        assert!(!pause.is_paused());
+
+        pause.internal_pause();
+        assert!(pause.is_paused());
+        pause.internal_unpause();
+        assert!(!pause.is_paused());
+        assert_eq!(get_logs().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is paused")]
+    fn pause_twice() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut pause = Pause::new();
+        pause.internal_pause();
+        pause.internal_pause();
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is not paused")]
+    fn unpause_not_paused() {
+        testing_env!(VMContextBuilder::new().build());
+        Pause::new().internal_unpause();
+    }
+}
@@ -0,0 +1,199 @@
+use crate::access_control::events::{RoleGranted, RoleRevoked};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::store::LookupSet;
+use near_sdk::{assert_one_yocto, env, require, AccountId, IntoStorageKey};
+
+/// Name of a role, for example `"minter"`.
+pub type Role = String;
+
+/// The role of the accounts allowed to grant and revoke the roles.
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+/// Role-based access control. Members of [`DEFAULT_ADMIN_ROLE`] grant and revoke the roles,
+/// including the admin role itself.
+pub trait AccessControl {
+    /// Returns whether `account_id` is a member of `role`.
+    fn has_role(&self, role: Role, account_id: AccountId) -> bool;
+
+    /// Grants `role` to `account_id`. Can only be called by an admin with exactly 1 yoctoNEAR
+    /// attached.
+    fn grant_role(&mut self, role: Role, account_id: AccountId);
+
+    /// Revokes `role` from `account_id`. Can only be called by an admin with exactly 1 yoctoNEAR
+    /// attached.
+    fn revoke_role(&mut self, role: Role, account_id: AccountId);
+
+    /// Removes the predecessor from `role`. Requires exactly 1 yoctoNEAR attached.
+    fn renounce_role(&mut self, role: Role);
+
+    /// Panics if the predecessor is not a member of `role`. Called by the methods marked with
+    /// `#[only(role = "...")]`.
+    fn assert_role(&self, role: &str) {
+        if !self.has_role(role.to_string(), env::predecessor_account_id()) {
+            env::panic_str(&format!("Method is only available to the role {}", role));
+        }
+    }
+}
+
+/// Implementation of [`AccessControl`] which stores the members of the roles in a
+/// [`LookupSet`]. The storage of the members is paid by the contract.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Roles {
+    members: LookupSet<(Role, AccountId)>,
+}
+
+impl Roles {
+    /// Creates the roles with `admin_id` as the first member of [`DEFAULT_ADMIN_ROLE`].
+    pub fn new<S>(prefix: S, admin_id: AccountId) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut this = Self { members: LookupSet::new(prefix) };
+        this.internal_grant_role(DEFAULT_ADMIN_ROLE.to_string(), admin_id);
+        this
+    }
+
+    /// Grants `role` to `account_id` without checking the predecessor. Returns whether the account
+    /// wasn't a member of the role.
+    pub fn internal_grant_role(&mut self, role: Role, account_id: AccountId) -> bool {
+        let granted = self.members.insert((role.clone(), account_id.clone()));
+        if granted {
+            let sender_id = env::predecessor_account_id();
+            RoleGranted { role: &role, account_id: &account_id, sender_id: &sender_id }.emit();
+        }
+        granted
+    }
+
+    /// Revokes `role` from `account_id` without checking the predecessor. Returns whether the
+    /// account was a member of the role.
+    pub fn internal_revoke_role(&mut self, role: Role, account_id: AccountId) -> bool {
+        let key = (role, account_id);
+        let revoked = self.members.remove(&key);
+        if revoked {
+            let (role, account_id) = &key;
+            let sender_id = env::predecessor_account_id();
+            RoleRevoked { role, account_id, sender_id: &sender_id }.emit();
+        }
+        revoked
+    }
+}
+
+impl AccessControl for Roles {
+    fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.members.contains(&(role, account_id))
+    }
+
+    fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        assert_one_yocto();
+        self.assert_role(DEFAULT_ADMIN_ROLE);
+        self.internal_grant_role(role, account_id);
+    }
+
+    fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        assert_one_yocto();
+        self.assert_role(DEFAULT_ADMIN_ROLE);
+        self.internal_revoke_role(role, account_id);
+    }
+
+    fn renounce_role(&mut self, role: Role) {
+        assert_one_yocto();
+        require!(
+            self.internal_revoke_role(role, env::predecessor_account_id()),
+            "The predecessor is not a member of the role"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::set_caller;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+
+    fn setup(context: &mut VMContextBuilder) -> Roles {
+        set_caller(context, accounts(0), 1);
+        Roles::new(b"r", accounts(0))
+    }
+
+    #[test]
+    fn grant_and_revoke_role() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        assert!(roles.has_role(DEFAULT_ADMIN_ROLE.to_string(), accounts(0)));
+
+        roles.grant_role("minter".to_string(), accounts(1));
+        assert!(roles.has_role("minter".to_string(), accounts(1)));
+        assert!(!roles.has_role(DEFAULT_ADMIN_ROLE.to_string(), accounts(1)));
+        assert!(!roles.has_role("minter".to_string(), accounts(2)));
+
+        roles.revoke_role("minter".to_string(), accounts(1));
+        assert!(!roles.has_role("minter".to_string(), accounts(1)));
+    }
+
+    #[test]
+    fn grant_and_revoke_twice() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        assert!(roles.internal_grant_role("minter".to_string(), accounts(1)));
+        assert!(!roles.internal_grant_role("minter".to_string(), accounts(1)));
+        assert!(roles.internal_revoke_role("minter".to_string(), accounts(1)));
+        assert!(!roles.internal_revoke_role("minter".to_string(), accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the role admin")]
+    fn grant_role_by_non_admin() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        roles.grant_role("minter".to_string(), accounts(1));
+
+        set_caller(&mut context, accounts(1), 1);
+        roles.grant_role("minter".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is only available to the role admin")]
+    fn revoke_role_by_non_admin() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        roles.grant_role("minter".to_string(), accounts(1));
+
+        set_caller(&mut context, accounts(1), 1);
+        roles.revoke_role("minter".to_string(), accounts(1));
+    }
+
+    #[test]
+    fn granted_admin_manages_roles() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        roles.grant_role(DEFAULT_ADMIN_ROLE.to_string(), accounts(1));
+
+        set_caller(&mut context, accounts(1), 1);
+        roles.revoke_role(DEFAULT_ADMIN_ROLE.to_string(), accounts(0));
+        assert!(!roles.has_role(DEFAULT_ADMIN_ROLE.to_string(), accounts(0)));
+        roles.grant_role("minter".to_string(), accounts(2));
+        assert!(roles.has_role("minter".to_string(), accounts(2)));
+    }
+
+    #[test]
+    fn renounce_role() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+        roles.grant_role("minter".to_string(), accounts(1));
+
+        set_caller(&mut context, accounts(1), 1);
+        roles.assert_role("minter");
+        roles.renounce_role("minter".to_string());
+        assert!(!roles.has_role("minter".to_string(), accounts(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The predecessor is not a member of the role")]
+    fn renounce_role_of_non_member() {
+        let mut context = VMContextBuilder::new();
+        let mut roles = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        roles.renounce_role("minter".to_string());
+    }
+}
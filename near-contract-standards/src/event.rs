@@ -8,6 +8,12 @@ use serde::Serialize;
 pub(crate) enum NearEvent<'a> {
     Nep171(crate::non_fungible_token::events::Nep171Event<'a>),
     Nep141(crate::fungible_token::events::Nep141Event<'a>),
+    FtAllowance(crate::fungible_token::events::FtAllowanceEvent<'a>),
+    Nep245(crate::multi_token::events::Nep245Event<'a>),
+    #[cfg(feature = "unstable")]
+    AccessControl(crate::access_control::events::AccessControlEvent<'a>),
+    Vesting(crate::vesting::events::VestingEvent<'a>),
+    Multisig(crate::multisig::events::MultisigEvent<'a>),
 }
 
 impl<'a> NearEvent<'a> {
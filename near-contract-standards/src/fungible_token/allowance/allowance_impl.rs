@@ -0,0 +1,374 @@
+use crate::fungible_token::core_impl::ext_fungible_token_receiver;
+use crate::fungible_token::events::FtApprove;
+use crate::fungible_token::FungibleToken;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, require, AccountId, Balance, Gas, IntoStorageKey,
+    PromiseOrValue, StorageTracker, StorageUsage,
+};
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
+
+#[ext_contract(ext_self)]
+trait FungibleTokenAllowanceResolver {
+    fn ft_resolve_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        spender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128;
+}
+
+/// Implementation of the allowance extension of a [`FungibleToken`], see
+/// [`FungibleTokenAllowance`](super::FungibleTokenAllowance).
+///
+/// The allowances are stored separately from the [`FungibleToken`], so that they can be added to
+/// a contract without migrating the state of its token. The methods transferring tokens take the
+/// [`FungibleToken`] of the contract.
+///
+/// For example usage, see the `impl_fungible_token_allowance` macro.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FungibleTokenAllowances {
+    /// (Owner ID, Spender ID) -> Remaining allowance.
+    pub allowances: LookupMap<(AccountId, AccountId), Balance>,
+
+    /// The storage size in bytes for one allowance.
+    pub allowance_storage_usage: StorageUsage,
+}
+
+impl FungibleTokenAllowances {
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut this = Self { allowances: LookupMap::new(prefix), allowance_storage_usage: 0 };
+        this.measure_allowance_storage_usage();
+        this
+    }
+
+    fn measure_allowance_storage_usage(&mut self) {
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+        let key = (tmp_account_id.clone(), tmp_account_id);
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.allowances.insert(&key, &0u128));
+        self.allowance_storage_usage = tracker.bytes_added();
+        self.allowances.remove(&key);
+    }
+
+    /// Returns the storage deposit required to add a new allowance.
+    pub fn allowance_storage_cost(&self) -> Balance {
+        Balance::from(self.allowance_storage_usage) * env::storage_byte_cost()
+    }
+
+    pub fn internal_allowance(&self, owner_id: &AccountId, spender_id: &AccountId) -> Balance {
+        self.allowances.get(&(owner_id.clone(), spender_id.clone())).unwrap_or(0)
+    }
+
+    /// Sets the allowance of `spender_id` for the tokens of `owner_id` to `amount`, removing it if
+    /// `amount` is 0. Requires the attached deposit to cover the storage of a new allowance, and
+    /// refunds the rest of it to `owner_id`, along with the storage deposit of a removed
+    /// allowance.
+    pub fn internal_approve(
+        &mut self,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        amount: Balance,
+    ) {
+        require!(owner_id != spender_id, "The owner and spender should be different");
+        let key = (owner_id.clone(), spender_id.clone());
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| {
+            if amount == 0 {
+                self.allowances.remove(&key)
+            } else {
+                self.allowances.insert(&key, &amount)
+            }
+        });
+        tracker.refund_released_deposit_to(owner_id.clone());
+        tracker.refund_deposit_to(owner_id.clone());
+
+        FtApprove { owner_id, spender_id, amount: &U128(amount) }.emit();
+    }
+
+    /// Transfers `amount` of the tokens of `owner_id` to `receiver_id` on behalf of `spender_id`,
+    /// decreasing its allowance, and emits the transfer event.
+    pub fn internal_transfer_from(
+        &mut self,
+        token: &mut FungibleToken,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+        memo: Option<String>,
+    ) {
+        let key = (owner_id.clone(), spender_id.clone());
+        let allowance = self.allowances.get(&key).unwrap_or(0);
+        require!(amount <= allowance, "The allowance of the spender is not enough");
+        self.allowances.insert(&key, &(allowance - amount));
+        token.internal_transfer(owner_id, receiver_id, amount, memo);
+    }
+
+    /// Internal method resolving an `ft_transfer_call_from` in the same way as
+    /// [`FungibleToken::internal_ft_resolve_transfer`], which also adds the tokens refunded to
+    /// `owner_id` back to the allowance of `spender_id`, unless the allowance was removed.
+    /// Returns (Used token amount, Burned token amount)
+    pub fn internal_ft_resolve_transfer_from(
+        &mut self,
+        token: &mut FungibleToken,
+        owner_id: &AccountId,
+        spender_id: &AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> (u128, u128) {
+        let (used_amount, burned_amount) =
+            token.internal_ft_resolve_transfer(owner_id, receiver_id, amount);
+        let refunded_amount = amount.0 - used_amount;
+        if refunded_amount > 0 {
+            let key = (owner_id.clone(), spender_id.clone());
+            if let Some(allowance) = self.allowances.get(&key) {
+                self.allowances.insert(&key, &allowance.saturating_add(refunded_amount));
+            }
+        }
+        (used_amount, burned_amount)
+    }
+
+    pub fn ft_approve(&mut self, spender_id: AccountId, amount: U128) {
+        require!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR");
+        let owner_id = env::predecessor_account_id();
+        self.internal_approve(&owner_id, &spender_id, amount.into());
+    }
+
+    pub fn ft_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> U128 {
+        self.internal_allowance(&owner_id, &spender_id).into()
+    }
+
+    pub fn ft_allowance_storage_cost(&self) -> U128 {
+        self.allowance_storage_cost().into()
+    }
+
+    pub fn ft_transfer_from(
+        &mut self,
+        token: &mut FungibleToken,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let spender_id = env::predecessor_account_id();
+        self.internal_transfer_from(
+            token,
+            &owner_id,
+            &spender_id,
+            &receiver_id,
+            amount.into(),
+            memo,
+        );
+    }
+
+    pub fn ft_transfer_call_from(
+        &mut self,
+        token: &mut FungibleToken,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_one_yocto();
+        require!(env::prepaid_gas() > GAS_FOR_FT_TRANSFER_CALL, "More gas is required");
+        let spender_id = env::predecessor_account_id();
+        self.internal_transfer_from(
+            token,
+            &owner_id,
+            &spender_id,
+            &receiver_id,
+            amount.into(),
+            memo,
+        );
+        // Initiating receiver's call and the callback
+        ext_fungible_token_receiver::ext(receiver_id.clone())
+            .with_static_gas(env::prepaid_gas() - GAS_FOR_FT_TRANSFER_CALL)
+            .ft_on_transfer(owner_id.clone(), amount, msg)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer_from(owner_id, spender_id, receiver_id, amount),
+            )
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::core::FungibleTokenCore;
+    use crate::test_utils::{refunds_to, set_caller};
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{serde_json, testing_env, PromiseResult, RuntimeFeesConfig, VMConfig};
+
+    /// Registers `accounts(0)` to `accounts(2)` and mints 100 tokens to `accounts(0)`, which
+    /// approves `accounts(1)` to transfer 30 of them.
+    fn setup(context: &mut VMContextBuilder) -> (FungibleToken, FungibleTokenAllowances) {
+        testing_env!(context.build());
+        let mut token = FungibleToken::new(b"t");
+        for index in 0..3 {
+            token.internal_register_account(&accounts(index));
+        }
+        token.internal_deposit(&accounts(0), 100);
+        let mut allowances = FungibleTokenAllowances::new(b"a");
+
+        set_caller(context, accounts(0), allowances.allowance_storage_cost());
+        allowances.ft_approve(accounts(1), U128(30));
+        (token, allowances)
+    }
+
+    /// Sets the unused amount returned by `ft_on_transfer` for the call of
+    /// `ft_resolve_transfer_from`.
+    fn set_unused_amount(context: &mut VMContextBuilder, unused_amount: u128) {
+        testing_env!(
+            context
+                .storage_usage(env::storage_usage())
+                .predecessor_account_id(accounts(0))
+                .attached_deposit(0)
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(serde_json::to_vec(&U128(unused_amount)).unwrap())],
+        );
+    }
+
+    #[test]
+    fn approve() {
+        let mut context = VMContextBuilder::new();
+        let (_, allowances) = setup(&mut context);
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(1)), U128(30));
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(2)), U128(0));
+
+        // The storage cost is estimated for the longest account IDs, and the storage of the
+        // allowance of shorter account IDs is refunded.
+        let cost = allowances.allowance_storage_cost();
+        assert!(matches!(refunds_to(accounts(0)).as_slice(), [refund] if *refund < cost));
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn approve_without_storage_deposit() {
+        let mut context = VMContextBuilder::new();
+        let (_, mut allowances) = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), 1);
+        allowances.ft_approve(accounts(2), U128(30));
+    }
+
+    #[test]
+    fn approve_again() {
+        let mut context = VMContextBuilder::new();
+        let (_, mut allowances) = setup(&mut context);
+
+        // Replacing the allowance doesn't take more storage.
+        set_caller(&mut context, accounts(0), 1);
+        allowances.ft_approve(accounts(1), U128(50));
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(1)), U128(50));
+        assert!(refunds_to(accounts(0)).is_empty());
+    }
+
+    #[test]
+    fn approve_zero() {
+        let mut context = VMContextBuilder::new();
+        let (_, mut allowances) = setup(&mut context);
+        let charged = allowances.allowance_storage_cost() - refunds_to(accounts(0))[0];
+
+        // Removing the allowance refunds its storage, the attached yoctoNEAR is kept.
+        set_caller(&mut context, accounts(0), 1);
+        allowances.ft_approve(accounts(1), U128(0));
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(1)), U128(0));
+        assert!(allowances.allowances.get(&(accounts(0), accounts(1))).is_none());
+        assert_eq!(refunds_to(accounts(0)), vec![charged]);
+    }
+
+    #[test]
+    fn transfer_from() {
+        let mut context = VMContextBuilder::new();
+        let (mut token, mut allowances) = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        allowances.ft_transfer_from(&mut token, accounts(0), accounts(2), U128(20), None);
+        assert_eq!(token.ft_balance_of(accounts(0)), U128(80));
+        assert_eq!(token.ft_balance_of(accounts(2)), U128(20));
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(1)), U128(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "The allowance of the spender is not enough")]
+    fn transfer_from_more_than_allowance() {
+        let mut context = VMContextBuilder::new();
+        let (mut token, mut allowances) = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        allowances.ft_transfer_from(&mut token, accounts(0), accounts(2), U128(31), None);
+    }
+
+    #[test]
+    fn resolve_transfer_from() {
+        let mut context = VMContextBuilder::new();
+        let (mut token, mut allowances) = setup(&mut context);
+        set_caller(&mut context, accounts(1), 1);
+        allowances.internal_transfer_from(
+            &mut token,
+            &accounts(0),
+            &accounts(1),
+            &accounts(2),
+            20,
+            None,
+        );
+
+        // The refunded tokens are added back to the allowance.
+        set_unused_amount(&mut context, 15);
+        let (used, burned) = allowances.internal_ft_resolve_transfer_from(
+            &mut token,
+            &accounts(0),
+            &accounts(1),
+            accounts(2),
+            U128(20),
+        );
+        assert_eq!((used, burned), (5, 0));
+        assert_eq!(token.ft_balance_of(accounts(0)), U128(95));
+        assert_eq!(token.ft_balance_of(accounts(2)), U128(5));
+        assert_eq!(allowances.ft_allowance(accounts(0), accounts(1)), U128(25));
+    }
+
+    #[test]
+    fn resolve_transfer_from_removed_allowance() {
+        let mut context = VMContextBuilder::new();
+        let (mut token, mut allowances) = setup(&mut context);
+        set_caller(&mut context, accounts(1), 1);
+        allowances.internal_transfer_from(
+            &mut token,
+            &accounts(0),
+            &accounts(1),
+            &accounts(2),
+            20,
+            None,
+        );
+        set_caller(&mut context, accounts(0), 1);
+        allowances.ft_approve(accounts(1), U128(0));
+
+        // The allowance removed by the owner while the transfer was in flight isn't restored.
+        set_unused_amount(&mut context, 15);
+        allowances.internal_ft_resolve_transfer_from(
+            &mut token,
+            &accounts(0),
+            &accounts(1),
+            accounts(2),
+            U128(20),
+        );
+        assert_eq!(token.ft_balance_of(accounts(0)), U128(95));
+        assert!(allowances.allowances.get(&(accounts(0), accounts(1))).is_none());
+    }
+}
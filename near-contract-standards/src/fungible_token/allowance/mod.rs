@@ -0,0 +1,91 @@
+mod allowance_impl;
+
+pub use allowance_impl::*;
+
+use near_sdk::json_types::U128;
+use near_sdk::{AccountId, PromiseOrValue};
+
+/// Optional extension of a fungible token allowing accounts to spend the tokens of other
+/// accounts up to an allowance, for delegated spending by contracts. An allowance is set by the
+/// owner of the tokens with `ft_approve` and decreased by each `ft_transfer_from` or
+/// `ft_transfer_call_from` of the spender.
+///
+/// Each allowance requires the owner to attach a storage deposit, which is refunded when the
+/// allowance is set to 0. Setting an allowance logs an `ft_approve` event.
+pub trait FungibleTokenAllowance {
+    /// Sets the allowance of `spender_id` to transfer the tokens of the
+    /// `env::predecessor_account_id` to `amount`, replacing the previous allowance. An `amount`
+    /// of 0 removes the allowance.
+    ///
+    /// Requirements:
+    /// * Caller of the method must attach a deposit of at least 1 yoctoⓃ for security purposes.
+    /// * Caller must attach the deposit returned by `ft_allowance_storage_cost` when adding a new
+    ///   allowance. The remaining deposit is refunded.
+    /// * The storage deposit is refunded when the allowance is removed.
+    ///
+    /// Arguments:
+    /// - `spender_id` - the account ID allowed to transfer the tokens.
+    /// - `amount` - the amount of tokens the spender can transfer, in a decimal string
+    ///   representation.
+    fn ft_approve(&mut self, spender_id: AccountId, amount: U128);
+
+    /// Returns the amount of tokens of `owner_id` that `spender_id` can still transfer, `"0"` if
+    /// there is no allowance.
+    fn ft_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> U128;
+
+    /// Returns the storage deposit to attach to `ft_approve` to add a new allowance.
+    fn ft_allowance_storage_cost(&self) -> U128;
+
+    /// Transfers positive `amount` of tokens of `owner_id` to `receiver_id`, decreasing the
+    /// allowance of the `env::predecessor_account_id`. Both `owner_id` and `receiver_id` must be
+    /// registered with the contract. Exactly 1 yoctoNEAR must be attached.
+    ///
+    /// Arguments:
+    /// - `owner_id` - the account ID which owns the tokens.
+    /// - `receiver_id` - the account ID of the receiver.
+    /// - `amount` - the amount of tokens to transfer. Must be a positive number in decimal string
+    ///   representation, at most the allowance of the caller.
+    /// - `memo` - an optional string field in a free form to associate a memo with this transfer.
+    fn ft_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+    );
+
+    /// Transfers positive `amount` of tokens of `owner_id` to `receiver_id` as
+    /// [`ft_transfer_from`](Self::ft_transfer_from), then calls `ft_on_transfer` on the
+    /// `receiver_id` contract with `owner_id` as the sender, like `ft_transfer_call`. The unused
+    /// tokens are refunded to `owner_id` and added back to the allowance of the caller, unless the
+    /// allowance was removed in the meantime.
+    ///
+    /// Arguments:
+    /// - `owner_id` - the account ID which owns the tokens.
+    /// - `receiver_id` - the account ID of the receiver contract. This contract will be called.
+    /// - `amount` - the amount of tokens to transfer. Must be a positive number in decimal string
+    ///   representation, at most the allowance of the caller.
+    /// - `memo` - an optional string field in a free form to associate a memo with this transfer.
+    /// - `msg` - a string message that will be passed to `ft_on_transfer` contract call.
+    ///
+    /// Returns a promise which will result in the amount of tokens withdrawn from the owner's
+    /// account.
+    fn ft_transfer_call_from(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+}
+
+pub trait FungibleTokenAllowanceResolver {
+    fn ft_resolve_transfer_from(
+        &mut self,
+        owner_id: AccountId,
+        spender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128;
+}
@@ -0,0 +1,54 @@
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use near_sdk::PromiseOrValue;
+
+/// Transfers to several receivers in a single call, for example to airdrop tokens. This is an
+/// extension of the [NEP-141](https://nomicon.io/Standards/Tokens/FungibleToken/Core) standard.
+///
+/// The transfers are logged as a single `ft_transfer` event with the data of each transfer.
+/// The number of receivers is limited to [`MAX_BATCH_TRANSFER_RECEIVERS`] to keep the event
+/// within the log size limit, and the gas to prepay can be estimated with
+/// [`ft_batch_transfer_gas`] and [`ft_batch_transfer_call_gas`].
+///
+/// [`MAX_BATCH_TRANSFER_RECEIVERS`]: super::batch_impl::MAX_BATCH_TRANSFER_RECEIVERS
+/// [`ft_batch_transfer_gas`]: super::batch_impl::ft_batch_transfer_gas
+/// [`ft_batch_transfer_call_gas`]: super::batch_impl::ft_batch_transfer_call_gas
+pub trait FungibleTokenBatchTransfer {
+    /// Transfers positive `amounts` of tokens from the `env::predecessor_account_id` to each of
+    /// the `receiver_ids`. All the receivers must be registered with the contract, otherwise none
+    /// of the transfers are made. Exactly 1 yoctoNEAR must be attached.
+    ///
+    /// Arguments:
+    /// - `receiver_ids` - the account IDs of the receivers.
+    /// - `amounts` - the amount of tokens to transfer to each receiver, in the same order as
+    ///   `receiver_ids`. Must be positive numbers in decimal string representation.
+    /// - `memo` - an optional string field in a free form to associate a memo with the transfers.
+    fn ft_batch_transfer(
+        &mut self,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+    );
+
+    /// Transfers positive `amounts` of tokens from the `env::predecessor_account_id` to each of
+    /// the `receiver_ids`, as [`ft_batch_transfer`](Self::ft_batch_transfer). Then calls the
+    /// `ft_on_transfer` method of every receiver contract, splitting the remaining gas between
+    /// them, and attaches a single callback which refunds the unused tokens of all the receivers.
+    ///
+    /// Arguments:
+    /// - `receiver_ids` - the account IDs of the receiver contracts. These contracts will be called.
+    /// - `amounts` - the amount of tokens to transfer to each receiver, in the same order as
+    ///   `receiver_ids`. Must be positive numbers in decimal string representation.
+    /// - `memo` - an optional string field in a free form to associate a memo with the transfers.
+    /// - `msg` - a string message that will be passed to each `ft_on_transfer` contract call.
+    ///
+    /// Returns a promise which will result in the amount of tokens withdrawn from sender's
+    /// account for each receiver.
+    fn ft_batch_transfer_call(
+        &mut self,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+}
@@ -0,0 +1,416 @@
+use crate::fungible_token::batch::FungibleTokenBatchTransfer;
+use crate::fungible_token::core_impl::{
+    ext_fungible_token_receiver, unused_transfer_amount, FungibleToken,
+};
+use crate::fungible_token::events::{FtBurn, FtTransfer};
+use crate::fungible_token::resolver::FungibleTokenBatchResolver;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, log, require, AccountId, Balance, Gas, Promise,
+    PromiseOrValue,
+};
+
+/// The maximum number of receivers of a batch transfer, which keeps the transfer event within the
+/// log size limit for account IDs of the maximum length and short memos.
+pub const MAX_BATCH_TRANSFER_RECEIVERS: usize = 64;
+
+const GAS_FOR_BATCH_TRANSFER: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_BATCH_TRANSFER_PER_RECEIVER: Gas = Gas(1_000_000_000_000);
+const GAS_FOR_FT_BATCH_TRANSFER_CALL: Gas = Gas(25_000_000_000_000);
+const GAS_FOR_FT_BATCH_TRANSFER_CALL_PER_RECEIVER: Gas = Gas(3_000_000_000_000);
+const GAS_FOR_RESOLVE_BATCH_TRANSFER: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_RESOLVE_BATCH_TRANSFER_PER_RECEIVER: Gas = Gas(1_000_000_000_000);
+
+#[ext_contract(ext_self)]
+trait FungibleTokenBatchResolver {
+    fn ft_resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+    ) -> Vec<U128>;
+}
+
+/// Estimates the gas to prepay for an `ft_batch_transfer` to `receivers` accounts.
+pub fn ft_batch_transfer_gas(receivers: usize) -> Gas {
+    GAS_FOR_BATCH_TRANSFER + GAS_FOR_BATCH_TRANSFER_PER_RECEIVER * receivers as u64
+}
+
+/// Estimates the gas to prepay for an `ft_batch_transfer_call` to `receivers` accounts, so that
+/// the `ft_on_transfer` call of each receiver gets `gas_per_receiver`.
+pub fn ft_batch_transfer_call_gas(receivers: usize, gas_per_receiver: Gas) -> Gas {
+    let receivers = receivers as u64;
+    GAS_FOR_FT_BATCH_TRANSFER_CALL
+        + resolve_batch_transfer_gas(receivers)
+        + (GAS_FOR_FT_BATCH_TRANSFER_CALL_PER_RECEIVER + gas_per_receiver) * receivers
+}
+
+fn resolve_batch_transfer_gas(receivers: u64) -> Gas {
+    GAS_FOR_RESOLVE_BATCH_TRANSFER + GAS_FOR_RESOLVE_BATCH_TRANSFER_PER_RECEIVER * receivers
+}
+
+impl FungibleToken {
+    /// Transfers `amounts` of tokens from `sender_id` to each of `receiver_ids`, and emits a single
+    /// transfer event with all of the transfers. Panics without transferring anything if one of
+    /// the receivers isn't registered.
+    pub fn internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_ids: &[AccountId],
+        amounts: &[Balance],
+        memo: Option<String>,
+    ) {
+        require!(!receiver_ids.is_empty(), "At least one receiver is required");
+        require!(
+            receiver_ids.len() == amounts.len(),
+            "The number of receivers and amounts must match"
+        );
+        if receiver_ids.len() > MAX_BATCH_TRANSFER_RECEIVERS {
+            env::panic_str(&format!(
+                "The number of receivers should be at most {}",
+                MAX_BATCH_TRANSFER_RECEIVERS
+            ));
+        }
+        require!(
+            receiver_ids.iter().all(|receiver_id| receiver_id != sender_id),
+            "Sender and receiver should be different"
+        );
+        require!(
+            amounts.iter().all(|amount| *amount > 0),
+            "The amount should be a positive number"
+        );
+
+        let unregistered: Vec<&str> = receiver_ids
+            .iter()
+            .filter(|receiver_id| !self.accounts.contains_key(receiver_id))
+            .map(|receiver_id| receiver_id.as_str())
+            .collect();
+        if !unregistered.is_empty() {
+            env::panic_str(&format!("The accounts {} are not registered", unregistered.join(", ")));
+        }
+
+        let total_amount = amounts
+            .iter()
+            .try_fold(0, |total: Balance, amount| total.checked_add(*amount))
+            .unwrap_or_else(|| env::panic_str("Total amount overflow"));
+        self.internal_withdraw(sender_id, total_amount);
+        for (receiver_id, amount) in receiver_ids.iter().zip(amounts) {
+            self.internal_deposit(receiver_id, *amount);
+        }
+
+        let amounts: Vec<U128> = amounts.iter().map(|amount| U128(*amount)).collect();
+        let events: Vec<_> = receiver_ids
+            .iter()
+            .zip(&amounts)
+            .map(|(receiver_id, amount)| FtTransfer {
+                old_owner_id: sender_id,
+                new_owner_id: receiver_id,
+                amount,
+                memo: memo.as_deref(),
+            })
+            .collect();
+        FtTransfer::emit_many(&events);
+    }
+
+    /// Internal method that refunds the tokens unused by each receiver of an
+    /// `ft_batch_transfer_call`, and burns them in the same corner case as
+    /// [`FungibleToken::internal_ft_resolve_transfer`].
+    /// Returns (Used token amount of each receiver, Burned token amount)
+    pub fn internal_ft_resolve_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_ids: &[AccountId],
+        amounts: &[U128],
+    ) -> (Vec<u128>, u128) {
+        require!(
+            receiver_ids.len() == amounts.len()
+                && env::promise_results_count() == receiver_ids.len() as u64,
+            "The number of receivers, amounts and promise results must match"
+        );
+
+        let mut used_amounts = Vec::with_capacity(amounts.len());
+        let mut burned_amount: Balance = 0;
+        let mut refunds = vec![];
+        let mut burns = vec![];
+        for (index, (receiver_id, amount)) in receiver_ids.iter().zip(amounts).enumerate() {
+            let amount = amount.0;
+            let unused_amount = unused_transfer_amount(index as u64, amount);
+            let receiver_balance =
+                if unused_amount > 0 { self.accounts.get(receiver_id).unwrap_or(0) } else { 0 };
+            let refund_amount = std::cmp::min(receiver_balance, unused_amount);
+            if refund_amount == 0 {
+                used_amounts.push(amount);
+                continue;
+            }
+
+            self.accounts.insert(receiver_id, &(receiver_balance - refund_amount));
+            if let Some(sender_balance) = self.accounts.get(sender_id) {
+                self.accounts.insert(sender_id, &(sender_balance + refund_amount));
+                refunds.push((receiver_id, U128(refund_amount)));
+                used_amounts.push(amount - refund_amount);
+            } else {
+                // Sender's account was deleted, so we need to burn tokens.
+                self.total_supply -= refund_amount;
+                burned_amount += refund_amount;
+                burns.push((receiver_id, U128(refund_amount)));
+                used_amounts.push(amount);
+            }
+        }
+
+        if !refunds.is_empty() {
+            let events: Vec<_> = refunds
+                .iter()
+                .map(|(receiver_id, amount)| FtTransfer {
+                    old_owner_id: receiver_id,
+                    new_owner_id: sender_id,
+                    amount,
+                    memo: Some("refund"),
+                })
+                .collect();
+            FtTransfer::emit_many(&events);
+        }
+        if !burns.is_empty() {
+            log!("The account of the sender was deleted");
+            let events: Vec<_> = burns
+                .iter()
+                .map(|(receiver_id, amount)| FtBurn {
+                    owner_id: receiver_id,
+                    amount,
+                    memo: Some("refund"),
+                })
+                .collect();
+            FtBurn::emit_many(&events);
+        }
+        (used_amounts, burned_amount)
+    }
+}
+
+impl FungibleTokenBatchTransfer for FungibleToken {
+    fn ft_batch_transfer(
+        &mut self,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(|amount| amount.0).collect();
+        self.internal_batch_transfer(&sender_id, &receiver_ids, &amounts, memo);
+    }
+
+    fn ft_batch_transfer_call(
+        &mut self,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        assert_one_yocto();
+        let reserved_gas = ft_batch_transfer_call_gas(receiver_ids.len(), Gas(0));
+        require!(env::prepaid_gas() > reserved_gas, "More gas is required");
+        let sender_id = env::predecessor_account_id();
+        let raw_amounts: Vec<Balance> = amounts.iter().map(|amount| amount.0).collect();
+        self.internal_batch_transfer(&sender_id, &receiver_ids, &raw_amounts, memo);
+
+        // Initiating the receivers' calls, joined into a single callback
+        let gas_per_receiver = (env::prepaid_gas() - reserved_gas) / receiver_ids.len() as u64;
+        let transfers = receiver_ids
+            .iter()
+            .zip(&amounts)
+            .map(|(receiver_id, amount)| {
+                ext_fungible_token_receiver::ext(receiver_id.clone())
+                    .with_static_gas(gas_per_receiver)
+                    .ft_on_transfer(sender_id.clone(), *amount, msg.clone())
+            })
+            .reduce(Promise::and)
+            .unwrap_or_else(|| env::abort());
+        transfers
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(resolve_batch_transfer_gas(receiver_ids.len() as u64))
+                    .ft_resolve_batch_transfer(sender_id, receiver_ids, amounts),
+            )
+            .into()
+    }
+}
+
+impl FungibleTokenBatchResolver for FungibleToken {
+    fn ft_resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+    ) -> Vec<U128> {
+        self.internal_ft_resolve_batch_transfer(&sender_id, &receiver_ids, &amounts)
+            .0
+            .into_iter()
+            .map(U128)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::core::FungibleTokenCore;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::{serde_json, testing_env, PromiseResult, RuntimeFeesConfig, VMConfig};
+
+    /// Registers `accounts(0)` to `accounts(3)` and mints 100 tokens to `accounts(0)`.
+    fn setup() -> FungibleToken {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        let mut token = FungibleToken::new(b"t");
+        for index in 0..4 {
+            token.internal_register_account(&accounts(index));
+        }
+        token.internal_deposit(&accounts(0), 100);
+        token
+    }
+
+    fn balance_of(token: &FungibleToken, index: usize) -> u128 {
+        token.ft_balance_of(accounts(index)).0
+    }
+
+    /// Sets the results of the `ft_on_transfer` calls, given as the unused amounts.
+    fn set_results(unused_amounts: &[Option<u128>]) {
+        let results = unused_amounts
+            .iter()
+            .map(|unused_amount| match unused_amount {
+                Some(amount) => {
+                    PromiseResult::Successful(serde_json::to_vec(&U128(*amount)).unwrap())
+                }
+                None => PromiseResult::Failed,
+            })
+            .collect();
+        testing_env!(
+            VMContextBuilder::new().predecessor_account_id(accounts(0)).build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            results,
+        );
+    }
+
+    #[test]
+    fn batch_transfer() {
+        let mut token = setup();
+        token.ft_batch_transfer(
+            vec![accounts(1), accounts(2), accounts(1)],
+            vec![U128(10), U128(20), U128(30)],
+            None,
+        );
+        assert_eq!(balance_of(&token, 0), 40);
+        assert_eq!(balance_of(&token, 1), 40);
+        assert_eq!(balance_of(&token, 2), 20);
+        assert_eq!(token.ft_total_supply().0, 100);
+        assert_eq!(get_logs().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of receivers and amounts must match")]
+    fn batch_transfer_mismatched_lengths() {
+        let mut token = setup();
+        token.ft_batch_transfer(vec![accounts(1), accounts(2)], vec![U128(10)], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of receivers should be at most 64")]
+    fn batch_transfer_too_many_receivers() {
+        let mut token = setup();
+        let receivers = MAX_BATCH_TRANSFER_RECEIVERS + 1;
+        token.ft_batch_transfer(vec![accounts(1); receivers], vec![U128(1); receivers], None);
+    }
+
+    #[test]
+    fn batch_transfer_max_receivers() {
+        let mut token = setup();
+        let receivers = MAX_BATCH_TRANSFER_RECEIVERS;
+        token.ft_batch_transfer(vec![accounts(1); receivers], vec![U128(1); receivers], None);
+        assert_eq!(balance_of(&token, 1), receivers as u128);
+    }
+
+    #[test]
+    #[should_panic(expected = "The accounts charlie, danny are not registered")]
+    fn batch_transfer_unregistered_receivers() {
+        let mut token = setup();
+        token.accounts.remove(&accounts(2));
+        token.accounts.remove(&accounts(3));
+        token.ft_batch_transfer(
+            vec![accounts(2), accounts(1), accounts(3)],
+            vec![U128(10), U128(10), U128(10)],
+            None,
+        );
+    }
+
+    #[test]
+    fn resolve_batch_transfer() {
+        let mut token = setup();
+        let receiver_ids = vec![accounts(1), accounts(2), accounts(1), accounts(3)];
+        let amounts = vec![U128(10), U128(20), U128(30), U128(40)];
+        token.ft_batch_transfer(receiver_ids.clone(), amounts.clone(), None);
+
+        // The receivers of the first and the third transfers are the same account, which can be
+        // refunded both transfers.
+        set_results(&[Some(5), Some(0), None, Some(100)]);
+        let used = token.ft_resolve_batch_transfer(accounts(0), receiver_ids, amounts);
+        assert_eq!(used, vec![U128(5), U128(20), U128(0), U128(0)]);
+        assert_eq!(balance_of(&token, 0), 75);
+        assert_eq!(balance_of(&token, 1), 5);
+        assert_eq!(balance_of(&token, 2), 20);
+        assert_eq!(balance_of(&token, 3), 0);
+        assert_eq!(token.ft_total_supply().0, 100);
+        assert_eq!(get_logs().len(), 1);
+    }
+
+    #[test]
+    fn resolve_batch_transfer_of_spent_tokens() {
+        let mut token = setup();
+        let receiver_ids = vec![accounts(1), accounts(1)];
+        let amounts = vec![U128(10), U128(30)];
+        token.ft_batch_transfer(receiver_ids.clone(), amounts.clone(), None);
+        // The receiver spent all but 15 of the tokens before the callback.
+        token.internal_transfer(&accounts(1), &accounts(2), 25, None);
+
+        set_results(&[None, None]);
+        let used = token.ft_resolve_batch_transfer(accounts(0), receiver_ids, amounts);
+        assert_eq!(used, vec![U128(0), U128(25)]);
+        assert_eq!(balance_of(&token, 0), 75);
+        assert_eq!(balance_of(&token, 1), 0);
+    }
+
+    #[test]
+    fn resolve_batch_transfer_of_deleted_sender() {
+        let mut token = setup();
+        let receiver_ids = vec![accounts(1), accounts(2), accounts(1)];
+        let amounts = vec![U128(10), U128(20), U128(30)];
+        token.ft_batch_transfer(receiver_ids.clone(), amounts.clone(), None);
+        token.internal_withdraw(&accounts(0), 40);
+        token.accounts.remove(&accounts(0));
+
+        set_results(&[Some(10), Some(5), Some(0)]);
+        let (used, burned) =
+            token.internal_ft_resolve_batch_transfer(&accounts(0), &receiver_ids, &amounts);
+        assert_eq!(used, vec![10, 20, 30]);
+        assert_eq!(burned, 15);
+        assert_eq!(balance_of(&token, 1), 30);
+        assert_eq!(balance_of(&token, 2), 15);
+        assert_eq!(token.ft_total_supply().0, 45);
+        assert_eq!(get_logs()[0], "The account of the sender was deleted");
+        assert!(get_logs()[1].contains(r#""event":"ft_burn""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of receivers, amounts and promise results must match")]
+    fn resolve_batch_transfer_mismatched_results() {
+        let mut token = setup();
+        set_results(&[None]);
+        token.ft_resolve_batch_transfer(
+            accounts(0),
+            vec![accounts(1), accounts(2)],
+            vec![U128(10), U128(20)],
+        );
+    }
+}
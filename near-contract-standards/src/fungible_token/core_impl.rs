@@ -1,12 +1,13 @@
 use crate::fungible_token::core::FungibleTokenCore;
 use crate::fungible_token::events::{FtBurn, FtTransfer};
 use crate::fungible_token::resolver::FungibleTokenResolver;
+use crate::vesting::VestingGrants;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::LookupMap;
 use near_sdk::json_types::U128;
 use near_sdk::{
     assert_one_yocto, env, ext_contract, log, require, AccountId, Balance, Gas, IntoStorageKey,
-    PromiseOrValue, PromiseResult, StorageUsage,
+    PromiseOrValue, PromiseResult, StorageTracker, StorageUsage,
 };
 
 const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
@@ -60,6 +61,7 @@ pub trait FungibleTokenContract {
 ///     - FungibleTokenMetaData -- return metadata for the token in NEP-148, up to contract to implement.
 ///     - StorageManager -- interface for NEP-145 for allocating storage per account. FungibleToken provides methods for it.
 ///     - AccountRegistrar -- interface for an account to register and unregister
+///     - FungibleTokenVesting -- interface for tokens vesting over time, if `vesting` is set. FungibleToken provides methods for it.
 ///
 /// For example usage, see examples/fungible-token/src/lib.rs.
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -72,6 +74,9 @@ pub struct FungibleToken {
 
     /// The storage size in bytes for one account.
     pub account_storage_usage: StorageUsage,
+
+    /// Grants of the tokens vesting for the accounts, required by the vesting extension.
+    pub vesting: Option<VestingGrants>,
 }
 
 impl FungibleToken {
@@ -79,17 +84,21 @@ impl FungibleToken {
     where
         S: IntoStorageKey,
     {
-        let mut this =
-            Self { accounts: LookupMap::new(prefix), total_supply: 0, account_storage_usage: 0 };
+        let mut this = Self {
+            accounts: LookupMap::new(prefix),
+            total_supply: 0,
+            account_storage_usage: 0,
+            vesting: None,
+        };
         this.measure_account_storage_usage();
         this
     }
 
     fn measure_account_storage_usage(&mut self) {
-        let initial_storage_usage = env::storage_usage();
         let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
-        self.accounts.insert(&tmp_account_id, &0u128);
-        self.account_storage_usage = env::storage_usage() - initial_storage_usage;
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.accounts.insert(&tmp_account_id, &0u128));
+        self.account_storage_usage = tracker.bytes_added();
         self.accounts.remove(&tmp_account_id);
     }
 
@@ -115,9 +124,15 @@ impl FungibleToken {
         }
     }
 
+    /// Withdraws `amount` from the balance of `account_id`. Panics if the balance is not enough, or
+    /// if the tokens of a [vesting](crate::vesting) grant of the account which haven't vested yet
+    /// would be withdrawn, when the [`FungibleToken::vesting`] extension is enabled.
     pub fn internal_withdraw(&mut self, account_id: &AccountId, amount: Balance) {
         let balance = self.internal_unwrap_balance_of(account_id);
         if let Some(new_balance) = balance.checked_sub(amount) {
+            if let Some(vesting) = &self.vesting {
+                vesting.assert_vested_balance(account_id, new_balance);
+            }
             self.accounts.insert(account_id, &new_balance);
             self.total_supply = self
                 .total_supply
@@ -217,17 +232,7 @@ impl FungibleToken {
         let amount: Balance = amount.into();
 
         // Get the unused amount from the `ft_on_transfer` call result.
-        let unused_amount = match env::promise_result(0) {
-            PromiseResult::NotReady => env::abort(),
-            PromiseResult::Successful(value) => {
-                if let Ok(unused_amount) = near_sdk::serde_json::from_slice::<U128>(&value) {
-                    std::cmp::min(amount, unused_amount.0)
-                } else {
-                    amount
-                }
-            }
-            PromiseResult::Failed => amount,
-        };
+        let unused_amount = unused_transfer_amount(0, amount);
 
         if unused_amount > 0 {
             let receiver_balance = self.accounts.get(&receiver_id).unwrap_or(0);
@@ -263,6 +268,22 @@ impl FungibleToken {
     }
 }
 
+/// Returns the amount of tokens unused by the receiver from the result of the `ft_on_transfer`
+/// call at `result_index`, or all of the `amount` if the call failed or returned an invalid value.
+pub(crate) fn unused_transfer_amount(result_index: u64, amount: Balance) -> Balance {
+    match env::promise_result(result_index) {
+        PromiseResult::NotReady => env::abort(),
+        PromiseResult::Successful(value) => {
+            if let Ok(unused_amount) = near_sdk::serde_json::from_slice::<U128>(&value) {
+                std::cmp::min(amount, unused_amount.0)
+            } else {
+                amount
+            }
+        }
+        PromiseResult::Failed => amount,
+    }
+}
+
 impl FungibleTokenResolver for FungibleToken {
     fn ft_resolve_transfer(
         &mut self,
@@ -12,6 +12,10 @@
 //! These events can be logged by calling `.emit()` on them if a single event, or calling
 //! [`FtMint::emit_many`], [`FtTransfer::emit_many`],
 //! or [`FtBurn::emit_many`] respectively.
+//!
+//! The [`FtApprove`] event is logged by the [allowance extension](crate::fungible_token::allowance)
+//! when an allowance is set. It isn't part of nep141, so it is logged under the `ft_allowance`
+//! standard.
 
 use crate::event::NearEvent;
 use near_sdk::json_types::U128;
@@ -92,6 +96,30 @@ impl FtBurn<'_> {
     }
 }
 
+/// Data to log for an FT approve event, when the allowance of `spender_id` to transfer the
+/// tokens of `owner_id` is set to `amount`. To log this event, call [`.emit()`](FtApprove::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct FtApprove<'a> {
+    pub owner_id: &'a AccountId,
+    pub spender_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl FtApprove<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        Self::emit_many(&[self])
+    }
+
+    /// Emits an FT approve event, through [`env::log_str`](near_sdk::env::log_str),
+    /// where each [`FtApprove`] represents the data of each allowance.
+    pub fn emit_many(data: &[FtApprove<'_>]) {
+        new_ft_allowance_v1(FtAllowanceEventKind::FtApprove(data)).emit()
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub(crate) struct Nep141Event<'a> {
     version: &'static str,
@@ -117,6 +145,31 @@ fn new_141_v1(event_kind: Nep141EventKind) -> NearEvent {
     new_141("1.0.0", event_kind)
 }
 
+#[derive(Serialize, Debug)]
+pub(crate) struct FtAllowanceEvent<'a> {
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: FtAllowanceEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+enum FtAllowanceEventKind<'a> {
+    FtApprove(&'a [FtApprove<'a>]),
+}
+
+fn new_ft_allowance<'a>(
+    version: &'static str,
+    event_kind: FtAllowanceEventKind<'a>,
+) -> NearEvent<'a> {
+    NearEvent::FtAllowance(FtAllowanceEvent { version, event_kind })
+}
+
+fn new_ft_allowance_v1(event_kind: FtAllowanceEventKind) -> NearEvent {
+    new_ft_allowance("1.0.0", event_kind)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,4 +265,16 @@ mod tests {
             r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alice","new_owner_id":"bob","amount":"200","memo":"has memo"},{"old_owner_id":"bob","new_owner_id":"alice","amount":"100"}]}"#
         );
     }
+
+    #[test]
+    fn ft_approve() {
+        let owner_id = &bob();
+        let spender_id = &alice();
+        let amount = &U128(100);
+        FtApprove { owner_id, spender_id, amount }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"ft_allowance","version":"1.0.0","event":"ft_approve","data":[{"owner_id":"bob","spender_id":"alice","amount":"100"}]}"#
+        );
+    }
 }
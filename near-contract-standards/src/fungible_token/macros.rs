@@ -58,6 +58,154 @@ macro_rules! impl_fungible_token_core {
     };
 }
 
+/// Batch transfers to several receivers in a single call, see
+/// [`FungibleTokenBatchTransfer`](crate::fungible_token::batch::FungibleTokenBatchTransfer).
+/// Takes name of the Contract struct, the inner field for the token and optional method name to
+/// call when the tokens refunded to a deleted sender were burned.
+#[macro_export]
+macro_rules! impl_fungible_token_batch_transfer {
+    ($contract: ident, $token: ident $(, $on_tokens_burned_fn:ident)?) => {
+        use $crate::fungible_token::batch::FungibleTokenBatchTransfer;
+        use $crate::fungible_token::resolver::FungibleTokenBatchResolver;
+
+        #[near_bindgen]
+        impl FungibleTokenBatchTransfer for $contract {
+            #[payable]
+            fn ft_batch_transfer(
+                &mut self,
+                receiver_ids: Vec<AccountId>,
+                amounts: Vec<U128>,
+                memo: Option<String>,
+            ) {
+                self.$token.ft_batch_transfer(receiver_ids, amounts, memo)
+            }
+
+            #[payable]
+            fn ft_batch_transfer_call(
+                &mut self,
+                receiver_ids: Vec<AccountId>,
+                amounts: Vec<U128>,
+                memo: Option<String>,
+                msg: String,
+            ) -> PromiseOrValue<Vec<U128>> {
+                self.$token.ft_batch_transfer_call(receiver_ids, amounts, memo, msg)
+            }
+        }
+
+        #[near_bindgen]
+        impl FungibleTokenBatchResolver for $contract {
+            #[private]
+            fn ft_resolve_batch_transfer(
+                &mut self,
+                sender_id: AccountId,
+                receiver_ids: Vec<AccountId>,
+                amounts: Vec<U128>,
+            ) -> Vec<U128> {
+                let (used_amounts, burned_amount) = self.$token.internal_ft_resolve_batch_transfer(
+                    &sender_id,
+                    &receiver_ids,
+                    &amounts,
+                );
+                if burned_amount > 0 {
+                    $(self.$on_tokens_burned_fn(sender_id, burned_amount);)?
+                }
+                used_amounts.into_iter().map(U128).collect()
+            }
+        }
+    };
+}
+
+/// The allowance extension of a fungible token, see
+/// [`FungibleTokenAllowance`](crate::fungible_token::allowance::FungibleTokenAllowance).
+/// Takes name of the Contract struct, the inner field for the token, the inner field for the
+/// [`FungibleTokenAllowances`](crate::fungible_token::allowance::FungibleTokenAllowances) and
+/// optional method name to call when the tokens refunded to a deleted owner were burned.
+#[macro_export]
+macro_rules! impl_fungible_token_allowance {
+    ($contract: ident, $token: ident, $allowances: ident $(, $on_tokens_burned_fn:ident)?) => {
+        use $crate::fungible_token::allowance::{
+            FungibleTokenAllowance, FungibleTokenAllowanceResolver,
+        };
+
+        #[near_bindgen]
+        impl FungibleTokenAllowance for $contract {
+            #[payable]
+            fn ft_approve(&mut self, spender_id: AccountId, amount: U128) {
+                self.$allowances.ft_approve(spender_id, amount)
+            }
+
+            fn ft_allowance(&self, owner_id: AccountId, spender_id: AccountId) -> U128 {
+                self.$allowances.ft_allowance(owner_id, spender_id)
+            }
+
+            fn ft_allowance_storage_cost(&self) -> U128 {
+                self.$allowances.ft_allowance_storage_cost()
+            }
+
+            #[payable]
+            fn ft_transfer_from(
+                &mut self,
+                owner_id: AccountId,
+                receiver_id: AccountId,
+                amount: U128,
+                memo: Option<String>,
+            ) {
+                self.$allowances.ft_transfer_from(
+                    &mut self.$token,
+                    owner_id,
+                    receiver_id,
+                    amount,
+                    memo,
+                )
+            }
+
+            #[payable]
+            fn ft_transfer_call_from(
+                &mut self,
+                owner_id: AccountId,
+                receiver_id: AccountId,
+                amount: U128,
+                memo: Option<String>,
+                msg: String,
+            ) -> PromiseOrValue<U128> {
+                self.$allowances.ft_transfer_call_from(
+                    &mut self.$token,
+                    owner_id,
+                    receiver_id,
+                    amount,
+                    memo,
+                    msg,
+                )
+            }
+        }
+
+        #[near_bindgen]
+        impl FungibleTokenAllowanceResolver for $contract {
+            #[private]
+            fn ft_resolve_transfer_from(
+                &mut self,
+                owner_id: AccountId,
+                spender_id: AccountId,
+                receiver_id: AccountId,
+                amount: U128,
+            ) -> U128 {
+                let (used_amount, burned_amount) =
+                    self.$allowances.internal_ft_resolve_transfer_from(
+                        &mut self.$token,
+                        &owner_id,
+                        &spender_id,
+                        receiver_id,
+                        amount,
+                    );
+                if burned_amount > 0 {
+                    $(self.$on_tokens_burned_fn(owner_id, burned_amount);)?
+                }
+                used_amount.into()
+            }
+        }
+    };
+}
+
 /// Ensures that when fungible token storage grows by collections adding entries,
 /// the storage is be paid by the caller. This ensures that storage cannot grow to a point
 /// that the FT contract runs out of Ⓝ.
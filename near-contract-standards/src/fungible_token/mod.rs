@@ -1,3 +1,6 @@
+pub mod allowance;
+pub mod batch;
+pub mod batch_impl;
 pub mod core;
 pub mod core_impl;
 pub mod events;
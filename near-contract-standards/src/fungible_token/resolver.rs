@@ -8,3 +8,12 @@ pub trait FungibleTokenResolver {
         amount: U128,
     ) -> U128;
 }
+
+pub trait FungibleTokenBatchResolver {
+    fn ft_resolve_batch_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_ids: Vec<AccountId>,
+        amounts: Vec<U128>,
+    ) -> Vec<U128>;
+}
@@ -15,6 +15,9 @@ impl FungibleToken {
         let force = force.unwrap_or(false);
         if let Some(balance) = self.accounts.get(&account_id) {
             if balance == 0 || force {
+                if let Some(vesting) = &self.vesting {
+                    vesting.assert_vested_balance(&account_id, 0);
+                }
                 self.accounts.remove(&account_id);
                 self.total_supply -= balance;
                 Promise::new(account_id.clone()).transfer(self.storage_balance_bounds().min.0 + 1);
@@ -0,0 +1,92 @@
+use crate::non_fungible_token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{near_event, AccountId};
+
+/// Owned [nep141](crate::fungible_token::events) event, which can be deserialized from the logs of
+/// a fungible token contract.
+#[near_event(standard = "nep141", version = "1.0.0")]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+pub enum FtEvent {
+    FtMint(Vec<FtMintData>),
+    FtTransfer(Vec<FtTransferData>),
+    FtBurn(Vec<FtBurnData>),
+}
+
+/// Owned data of an [`FtMint`](crate::fungible_token::events::FtMint) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtMintData {
+    pub owner_id: AccountId,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Owned data of an [`FtTransfer`](crate::fungible_token::events::FtTransfer) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtTransferData {
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Owned data of an [`FtBurn`](crate::fungible_token::events::FtBurn) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtBurnData {
+    pub owner_id: AccountId,
+    pub amount: U128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Owned [nep171](crate::non_fungible_token::events) event, which can be deserialized from the
+/// logs of a non-fungible token contract.
+#[near_event(standard = "nep171", version = "1.0.0")]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+pub enum NftEvent {
+    NftMint(Vec<NftMintData>),
+    NftTransfer(Vec<NftTransferData>),
+    NftBurn(Vec<NftBurnData>),
+}
+
+/// Owned data of an [`NftMint`](crate::non_fungible_token::events::NftMint) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMintData {
+    pub owner_id: AccountId,
+    pub token_ids: Vec<TokenId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Owned data of an [`NftTransfer`](crate::non_fungible_token::events::NftTransfer) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftTransferData {
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    pub token_ids: Vec<TokenId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Owned data of an [`NftBurn`](crate::non_fungible_token::events::NftBurn) event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnData {
+    pub owner_id: AccountId,
+    pub token_ids: Vec<TokenId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
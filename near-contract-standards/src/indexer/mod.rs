@@ -0,0 +1,195 @@
+//! Parsing of the events of the fungible and non-fungible token standards from the logs of a
+//! contract, and replay of the events into the balances and owners of the tokens, for indexers
+//! which reconcile them against the state of the contract.
+//!
+//! The events logged by the contracts borrow their data, so this module mirrors them with owned
+//! types which can be deserialized: [`FtEvent`] for [nep141](crate::fungible_token::events) and
+//! [`NftEvent`] for [nep171](crate::non_fungible_token::events).
+//!
+//! # Example
+//!
+//! ```
+//! use near_contract_standards::indexer::{parse_event_log, EventReplay, FtEvent, StandardEvent};
+//!
+//! let log = r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"alice.near","amount":"100"}]}"#;
+//! match parse_event_log(log).unwrap() {
+//!     StandardEvent::Nep141(FtEvent::FtMint(mints)) => assert_eq!(mints[0].amount.0, 100),
+//!     _ => unreachable!(),
+//! }
+//!
+//! let replay = EventReplay::replay(&[log, "a log which isn't an event"]).unwrap();
+//! assert_eq!(replay.ft_balance_of(&"alice.near".parse().unwrap()), 100);
+//! assert_eq!(replay.ft_total_supply, 100);
+//! ```
+
+mod events;
+pub use self::events::*;
+
+mod replay;
+pub use self::replay::{EventReplay, ReplayError};
+
+use near_sdk::serde_json::{self, Map, Value};
+use near_sdk::NearEvent;
+use std::fmt;
+
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// An event of one of the standards supported by [`parse_event_log`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StandardEvent {
+    Nep141(FtEvent),
+    Nep171(NftEvent),
+}
+
+/// Parses an event of the fungible or non-fungible token standards from a log, dispatching on its
+/// `standard`, `version` and `event`.
+pub fn parse_event_log(log: &str) -> Result<StandardEvent, ParseEventError> {
+    let json = log.strip_prefix(EVENT_JSON_PREFIX).ok_or(ParseEventError::NotAnEvent)?;
+    let mut event: Map<String, Value> =
+        serde_json::from_str(json).map_err(|err| ParseEventError::InvalidEvent(err.to_string()))?;
+    let standard = take_string(&mut event, "standard")?;
+    let version = take_string(&mut event, "version")?;
+    let event = Value::Object(event);
+    let parsed = match (standard.as_str(), version.as_str()) {
+        (FtEvent::STANDARD, FtEvent::VERSION) => {
+            serde_json::from_value(event).map(StandardEvent::Nep141)
+        }
+        (NftEvent::STANDARD, NftEvent::VERSION) => {
+            serde_json::from_value(event).map(StandardEvent::Nep171)
+        }
+        _ => return Err(ParseEventError::UnknownStandard { standard, version }),
+    };
+    parsed.map_err(|err| ParseEventError::InvalidEvent(err.to_string()))
+}
+
+fn take_string(event: &mut Map<String, Value>, field: &str) -> Result<String, ParseEventError> {
+    match event.remove(field) {
+        Some(Value::String(value)) => Ok(value),
+        _ => Err(ParseEventError::InvalidEvent(format!("missing string field `{}`", field))),
+    }
+}
+
+/// Error of [`parse_event_log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseEventError {
+    /// The log doesn't start with `EVENT_JSON:`.
+    NotAnEvent,
+    /// The event isn't of one of the supported versions of the standards.
+    UnknownStandard { standard: String, version: String },
+    /// The log isn't a valid event of its standard.
+    InvalidEvent(String),
+}
+
+impl fmt::Display for ParseEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnEvent => write!(f, "the log is not an event"),
+            Self::UnknownStandard { standard, version } => {
+                write!(f, "unknown event standard {} version {}", standard, version)
+            }
+            Self::InvalidEvent(err) => write!(f, "invalid event: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseEventError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::events::{FtBurn, FtTransfer};
+    use crate::non_fungible_token::events::NftTransfer;
+    use near_sdk::json_types::U128;
+    use near_sdk::{test_utils, AccountId};
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice".to_string())
+    }
+
+    #[test]
+    fn parse_emitted_events() {
+        let amount = &U128(100);
+        FtTransfer { old_owner_id: &alice(), new_owner_id: &bob(), amount, memo: Some("hi") }
+            .emit();
+        FtBurn { owner_id: &bob(), amount, memo: None }.emit();
+        NftTransfer {
+            old_owner_id: &alice(),
+            new_owner_id: &bob(),
+            token_ids: &["0", "1"],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        let events: Vec<_> =
+            test_utils::get_logs().iter().map(|log| parse_event_log(log).unwrap()).collect();
+        assert_eq!(
+            events,
+            vec![
+                StandardEvent::Nep141(FtEvent::FtTransfer(vec![FtTransferData {
+                    old_owner_id: alice(),
+                    new_owner_id: bob(),
+                    amount: U128(100),
+                    memo: Some("hi".to_string()),
+                }])),
+                StandardEvent::Nep141(FtEvent::FtBurn(vec![FtBurnData {
+                    owner_id: bob(),
+                    amount: U128(100),
+                    memo: None,
+                }])),
+                StandardEvent::Nep171(NftEvent::NftTransfer(vec![NftTransferData {
+                    old_owner_id: alice(),
+                    new_owner_id: bob(),
+                    token_ids: vec!["0".to_string(), "1".to_string()],
+                    authorized_id: None,
+                    memo: None,
+                }])),
+            ]
+        );
+    }
+
+    #[test]
+    fn owned_events_log_as_standard_events() {
+        let amount = &U128(5);
+        FtBurn { owner_id: &alice(), amount, memo: None }.emit();
+        FtEvent::FtBurn(vec![FtBurnData { owner_id: alice(), amount: U128(5), memo: None }]).emit();
+        let logs = test_utils::get_logs();
+        assert_eq!(logs[0], logs[1]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse_event_log("hello"), Err(ParseEventError::NotAnEvent));
+        assert_eq!(
+            parse_event_log(
+                r#"EVENT_JSON:{"standard":"nep245","version":"1.0.0","event":"mt_mint","data":[]}"#
+            ),
+            Err(ParseEventError::UnknownStandard {
+                standard: "nep245".to_string(),
+                version: "1.0.0".to_string()
+            })
+        );
+        assert_eq!(
+            parse_event_log(
+                r#"EVENT_JSON:{"standard":"nep141","version":"2.0.0","event":"ft_mint","data":[]}"#
+            ),
+            Err(ParseEventError::UnknownStandard {
+                standard: "nep141".to_string(),
+                version: "2.0.0".to_string()
+            })
+        );
+        assert!(matches!(
+            parse_event_log(
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"nft_mint","data":[]}"#
+            ),
+            Err(ParseEventError::InvalidEvent(_))
+        ));
+        assert!(matches!(
+            parse_event_log(r#"EVENT_JSON:{"version":"1.0.0","event":"ft_mint","data":[]}"#),
+            Err(ParseEventError::InvalidEvent(_))
+        ));
+    }
+}
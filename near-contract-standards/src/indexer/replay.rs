@@ -0,0 +1,411 @@
+use super::{parse_event_log, FtEvent, NftEvent, ParseEventError, StandardEvent};
+use crate::non_fungible_token::TokenId;
+use near_sdk::{AccountId, Balance};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Balances of fungible tokens and owners of non-fungible tokens, built by replaying the events of
+/// a contract from its deployment, to be reconciled against the state of the contract.
+///
+/// Only the accounts with a positive balance are kept.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EventReplay {
+    /// Account ID -> Positive balance of fungible tokens.
+    pub ft_balances: BTreeMap<AccountId, Balance>,
+    /// Total supply of fungible tokens.
+    pub ft_total_supply: Balance,
+    /// Token ID -> Owner ID of non-fungible tokens.
+    pub nft_owners: BTreeMap<TokenId, AccountId>,
+}
+
+impl EventReplay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replays the events of the `logs` of a contract, in order, skipping the logs which aren't
+    /// events and the events of other standards.
+    pub fn replay<I>(logs: I) -> Result<Self, ReplayError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut replay = Self::new();
+        for log in logs {
+            replay.apply_log(log.as_ref())?;
+        }
+        Ok(replay)
+    }
+
+    /// Applies the event of a log, if it is an event of the fungible or non-fungible token
+    /// standards. Returns an error if the log is an invalid event of these standards.
+    pub fn apply_log(&mut self, log: &str) -> Result<(), ReplayError> {
+        match parse_event_log(log) {
+            Ok(event) => self.apply(&event),
+            Err(ParseEventError::NotAnEvent) | Err(ParseEventError::UnknownStandard { .. }) => {
+                Ok(())
+            }
+            Err(err) => Err(ReplayError::InvalidEvent(err)),
+        }
+    }
+
+    /// Applies an event. The balances and owners are left unchanged if the event is inconsistent
+    /// with them, in which case an error is returned.
+    pub fn apply(&mut self, event: &StandardEvent) -> Result<(), ReplayError> {
+        // Each entry of the event is checked before it is applied in place. The changes of the
+        // entries applied before an inconsistent one are undone, so that the event doesn't apply
+        // partially.
+        let mut undo = Vec::new();
+        let result = match event {
+            StandardEvent::Nep141(event) => self.apply_ft(event, &mut undo),
+            StandardEvent::Nep171(event) => self.apply_nft(event, &mut undo),
+        };
+        if result.is_err() {
+            self.rollback(undo);
+        }
+        result
+    }
+
+    /// Returns the balance of fungible tokens of `account_id`.
+    pub fn ft_balance_of(&self, account_id: &AccountId) -> Balance {
+        self.ft_balances.get(account_id).copied().unwrap_or(0)
+    }
+
+    /// Returns the owner of the non-fungible token `token_id`.
+    pub fn nft_owner_of(&self, token_id: &str) -> Option<&AccountId> {
+        self.nft_owners.get(token_id)
+    }
+
+    fn apply_ft(&mut self, event: &FtEvent, undo: &mut Vec<Undo>) -> Result<(), ReplayError> {
+        match event {
+            FtEvent::FtMint(mints) => {
+                for mint in mints {
+                    let total_supply = self
+                        .ft_total_supply
+                        .checked_add(mint.amount.0)
+                        .ok_or(ReplayError::TotalSupplyOverflow)?;
+                    let balance = self.ft_deposited_balance(&mint.owner_id, mint.amount.0)?;
+                    self.set_ft_total_supply(total_supply, undo);
+                    self.set_ft_balance(&mint.owner_id, balance, undo);
+                }
+            }
+            FtEvent::FtTransfer(transfers) => {
+                for transfer in transfers {
+                    let balance =
+                        self.ft_withdrawn_balance(&transfer.old_owner_id, transfer.amount.0)?;
+                    self.set_ft_balance(&transfer.old_owner_id, balance, undo);
+                    // Withdrawn before the deposit is checked, as the accounts may be the same.
+                    let balance =
+                        self.ft_deposited_balance(&transfer.new_owner_id, transfer.amount.0)?;
+                    self.set_ft_balance(&transfer.new_owner_id, balance, undo);
+                }
+            }
+            FtEvent::FtBurn(burns) => {
+                for burn in burns {
+                    let balance = self.ft_withdrawn_balance(&burn.owner_id, burn.amount.0)?;
+                    self.set_ft_balance(&burn.owner_id, balance, undo);
+                    // The balances of the accounts add up to the total supply.
+                    self.set_ft_total_supply(self.ft_total_supply - burn.amount.0, undo);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the balance of `account_id` after `amount` is deposited to it.
+    fn ft_deposited_balance(
+        &self,
+        account_id: &AccountId,
+        amount: Balance,
+    ) -> Result<Balance, ReplayError> {
+        self.ft_balance_of(account_id)
+            .checked_add(amount)
+            .ok_or_else(|| ReplayError::BalanceOverflow { account_id: account_id.clone() })
+    }
+
+    /// Returns the balance of `account_id` after `amount` is withdrawn from it.
+    fn ft_withdrawn_balance(
+        &self,
+        account_id: &AccountId,
+        amount: Balance,
+    ) -> Result<Balance, ReplayError> {
+        let balance = self.ft_balance_of(account_id);
+        if balance < amount {
+            return Err(ReplayError::InsufficientBalance {
+                account_id: account_id.clone(),
+                balance,
+                amount,
+            });
+        }
+        Ok(balance - amount)
+    }
+
+    fn set_ft_balance(&mut self, account_id: &AccountId, balance: Balance, undo: &mut Vec<Undo>) {
+        let previous = if balance == 0 {
+            self.ft_balances.remove(account_id)
+        } else {
+            self.ft_balances.insert(account_id.clone(), balance)
+        };
+        undo.push(Undo::FtBalance(account_id.clone(), previous));
+    }
+
+    fn set_ft_total_supply(&mut self, total_supply: Balance, undo: &mut Vec<Undo>) {
+        undo.push(Undo::FtTotalSupply(self.ft_total_supply));
+        self.ft_total_supply = total_supply;
+    }
+
+    fn apply_nft(&mut self, event: &NftEvent, undo: &mut Vec<Undo>) -> Result<(), ReplayError> {
+        match event {
+            NftEvent::NftMint(mints) => {
+                for mint in mints {
+                    for token_id in &mint.token_ids {
+                        if self.nft_owners.contains_key(token_id) {
+                            return Err(ReplayError::TokenAlreadyExists {
+                                token_id: token_id.clone(),
+                            });
+                        }
+                        self.set_nft_owner(token_id, Some(mint.owner_id.clone()), undo);
+                    }
+                }
+            }
+            NftEvent::NftTransfer(transfers) => {
+                for transfer in transfers {
+                    for token_id in &transfer.token_ids {
+                        self.nft_check_owner(token_id, &transfer.old_owner_id)?;
+                        self.set_nft_owner(token_id, Some(transfer.new_owner_id.clone()), undo);
+                    }
+                }
+            }
+            NftEvent::NftBurn(burns) => {
+                for burn in burns {
+                    for token_id in &burn.token_ids {
+                        self.nft_check_owner(token_id, &burn.owner_id)?;
+                        self.set_nft_owner(token_id, None, undo);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn nft_check_owner(&self, token_id: &str, owner_id: &AccountId) -> Result<(), ReplayError> {
+        match self.nft_owners.get(token_id) {
+            Some(actual_owner_id) if actual_owner_id == owner_id => Ok(()),
+            actual_owner_id => Err(ReplayError::NotTokenOwner {
+                token_id: token_id.to_string(),
+                owner_id: owner_id.clone(),
+                actual_owner_id: actual_owner_id.cloned(),
+            }),
+        }
+    }
+
+    fn set_nft_owner(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: Option<AccountId>,
+        undo: &mut Vec<Undo>,
+    ) {
+        let previous = match owner_id {
+            Some(owner_id) => self.nft_owners.insert(token_id.clone(), owner_id),
+            None => self.nft_owners.remove(token_id),
+        };
+        undo.push(Undo::NftOwner(token_id.clone(), previous));
+    }
+
+    /// Restores the state changed by the entries of an event, in reverse order.
+    fn rollback(&mut self, undo: Vec<Undo>) {
+        for change in undo.into_iter().rev() {
+            match change {
+                Undo::FtBalance(account_id, Some(balance)) => {
+                    self.ft_balances.insert(account_id, balance);
+                }
+                Undo::FtBalance(account_id, None) => {
+                    self.ft_balances.remove(&account_id);
+                }
+                Undo::FtTotalSupply(total_supply) => self.ft_total_supply = total_supply,
+                Undo::NftOwner(token_id, Some(owner_id)) => {
+                    self.nft_owners.insert(token_id, owner_id);
+                }
+                Undo::NftOwner(token_id, None) => {
+                    self.nft_owners.remove(&token_id);
+                }
+            }
+        }
+    }
+}
+
+/// Previous value of an entry of an [`EventReplay`] changed by an event, restored if a later entry
+/// of the event is inconsistent.
+enum Undo {
+    FtBalance(AccountId, Option<Balance>),
+    FtTotalSupply(Balance),
+    NftOwner(TokenId, Option<AccountId>),
+}
+
+/// Error of an [`EventReplay`], when a log is an invalid event or an event is inconsistent with the
+/// events replayed before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The log is an invalid event of the fungible or non-fungible token standards.
+    InvalidEvent(ParseEventError),
+    /// The fungible tokens withdrawn from an account are more than its balance.
+    InsufficientBalance { account_id: AccountId, balance: Balance, amount: Balance },
+    /// The balance of fungible tokens of an account overflows.
+    BalanceOverflow { account_id: AccountId },
+    /// The total supply of fungible tokens overflows.
+    TotalSupplyOverflow,
+    /// A non-fungible token is minted again.
+    TokenAlreadyExists { token_id: TokenId },
+    /// A non-fungible token is transferred or burned by an account which doesn't own it, or
+    /// doesn't exist.
+    NotTokenOwner { token_id: TokenId, owner_id: AccountId, actual_owner_id: Option<AccountId> },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEvent(err) => write!(f, "{}", err),
+            Self::InsufficientBalance { account_id, balance, amount } => write!(
+                f,
+                "the balance {} of {} is lower than the amount {}",
+                balance, account_id, amount
+            ),
+            Self::BalanceOverflow { account_id } => {
+                write!(f, "the balance of {} overflows", account_id)
+            }
+            Self::TotalSupplyOverflow => write!(f, "the total supply overflows"),
+            Self::TokenAlreadyExists { token_id } => {
+                write!(f, "the token {} already exists", token_id)
+            }
+            Self::NotTokenOwner { token_id, owner_id, actual_owner_id: Some(actual_owner_id) } => {
+                write!(
+                    f,
+                    "the token {} is owned by {}, not {}",
+                    token_id, actual_owner_id, owner_id
+                )
+            }
+            Self::NotTokenOwner { token_id, actual_owner_id: None, .. } => {
+                write!(f, "the token {} doesn't exist", token_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::events::{FtBurn, FtMint, FtTransfer};
+    use crate::indexer::{FtTransferData, NftBurnData, NftMintData};
+    use crate::non_fungible_token::events::{NftBurn, NftMint, NftTransfer};
+    use near_sdk::json_types::U128;
+    use near_sdk::test_utils;
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice".to_string())
+    }
+
+    #[test]
+    fn replay_ft_events() {
+        FtMint { owner_id: &alice(), amount: &U128(100), memo: None }.emit();
+        near_sdk::log!("not an event");
+        FtTransfer::emit_many(&[
+            FtTransfer {
+                old_owner_id: &alice(),
+                new_owner_id: &bob(),
+                amount: &U128(30),
+                memo: None,
+            },
+            FtTransfer {
+                old_owner_id: &bob(),
+                new_owner_id: &alice(),
+                amount: &U128(10),
+                memo: None,
+            },
+        ]);
+        FtBurn { owner_id: &bob(), amount: &U128(20), memo: None }.emit();
+
+        let replay = EventReplay::replay(test_utils::get_logs()).unwrap();
+        assert_eq!(replay.ft_balance_of(&alice()), 80);
+        assert_eq!(replay.ft_balance_of(&bob()), 0);
+        assert_eq!(replay.ft_total_supply, 80);
+        assert_eq!(replay.ft_balances.len(), 1);
+    }
+
+    #[test]
+    fn replay_nft_events() {
+        NftMint { owner_id: &alice(), token_ids: &["0", "1"], memo: None }.emit();
+        NftTransfer {
+            old_owner_id: &alice(),
+            new_owner_id: &bob(),
+            token_ids: &["1"],
+            authorized_id: None,
+            memo: None,
+        }
+        .emit();
+        NftBurn { owner_id: &alice(), token_ids: &["0"], authorized_id: None, memo: None }.emit();
+
+        let replay = EventReplay::replay(test_utils::get_logs()).unwrap();
+        assert_eq!(replay.nft_owner_of("0"), None);
+        assert_eq!(replay.nft_owner_of("1"), Some(&bob()));
+        assert_eq!(replay.nft_owners.len(), 1);
+    }
+
+    #[test]
+    fn inconsistent_events_are_not_applied() {
+        let mut replay = EventReplay::new();
+        FtMint { owner_id: &alice(), amount: &U128(10), memo: None }.emit();
+        NftMint { owner_id: &alice(), token_ids: &["0"], memo: None }.emit();
+        for log in test_utils::get_logs() {
+            replay.apply_log(&log).unwrap();
+        }
+        let before = replay.clone();
+
+        let transfer = StandardEvent::Nep141(FtEvent::FtTransfer(vec![
+            FtTransferData {
+                old_owner_id: alice(),
+                new_owner_id: bob(),
+                amount: U128(10),
+                memo: None,
+            },
+            FtTransferData {
+                old_owner_id: alice(),
+                new_owner_id: bob(),
+                amount: U128(1),
+                memo: None,
+            },
+        ]));
+        assert_eq!(
+            replay.apply(&transfer),
+            Err(ReplayError::InsufficientBalance { account_id: alice(), balance: 0, amount: 1 })
+        );
+        let burn = StandardEvent::Nep171(NftEvent::NftBurn(vec![NftBurnData {
+            owner_id: bob(),
+            token_ids: vec!["0".to_string()],
+            authorized_id: None,
+            memo: None,
+        }]));
+        assert_eq!(
+            replay.apply(&burn),
+            Err(ReplayError::NotTokenOwner {
+                token_id: "0".to_string(),
+                owner_id: bob(),
+                actual_owner_id: Some(alice()),
+            })
+        );
+        let mint = StandardEvent::Nep171(NftEvent::NftMint(vec![
+            NftMintData { owner_id: bob(), token_ids: vec!["1".to_string()], memo: None },
+            NftMintData { owner_id: bob(), token_ids: vec!["0".to_string()], memo: None },
+        ]));
+        assert_eq!(
+            replay.apply(&mint),
+            Err(ReplayError::TokenAlreadyExists { token_id: "0".to_string() })
+        );
+        assert_eq!(replay, before);
+    }
+}
@@ -2,10 +2,24 @@
 pub mod fungible_token;
 /// Non-fungible tokens as described in [by the spec](https://nomicon.io/Standards/NonFungibleToken/README.html).
 pub mod non_fungible_token;
+/// Multi tokens as described in [by the spec](https://github.com/near/NEPs/blob/master/neps/nep-0245.md).
+pub mod multi_token;
 /// Storage management deals with handling [state storage](https://docs.near.org/docs/concepts/storage-staking) on NEAR. This follows the [storage management standard](https://nomicon.io/Standards/StorageManagement.html).
 pub mod storage_management;
 /// This upgrade standard is a use case where a staging area exists for a WASM
 /// blob, allowing it to be stored for a period of time before deployed.
 pub mod upgrade;
+/// Ownership, roles and pausing of the methods of a contract. Available with the `unstable`
+/// feature.
+#[cfg(feature = "unstable")]
+pub mod access_control;
+/// Parsing and replay of the events of the token standards, for indexers.
+pub mod indexer;
+/// Vesting of fungible tokens according to cliff, linear or step schedules.
+pub mod vesting;
+/// Proposals of actions executed by a contract once confirmed by a threshold of its members.
+pub mod multisig;
 
 pub(crate) mod event;
+#[cfg(test)]
+pub(crate) mod test_utils;
@@ -0,0 +1,415 @@
+/// Common implementation of the approval management part of the [multi token standard](https://github.com/near/NEPs/blob/master/neps/nep-0245.md).
+/// Calls `mt_on_approve` on the contract/account that has just been approved when given a `msg`.
+use crate::multi_token::approval::MultiTokenApproval;
+use crate::multi_token::core::TokenApprovals;
+use crate::multi_token::token::{Approval, TokenId};
+use crate::multi_token::MultiToken;
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, require, AccountId, Gas, Promise, StorageTracker,
+};
+
+const GAS_FOR_MT_APPROVE: Gas = Gas(10_000_000_000_000);
+
+fn expect_token_found<T>(option: Option<T>) -> T {
+    option.unwrap_or_else(|| env::panic_str("Token not found"))
+}
+
+fn expect_approval<T>(option: Option<T>) -> T {
+    option.unwrap_or_else(|| env::panic_str("next_approval_id_by_id must be set for approval ext"))
+}
+
+#[ext_contract(ext_approval_receiver)]
+pub trait MultiTokenReceiver {
+    fn mt_on_approve(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        owner_id: AccountId,
+        approval_ids: Vec<u64>,
+        msg: String,
+    );
+}
+
+impl MultiToken {
+    fn approvals_mut(&mut self) -> &mut LookupMap<TokenId, TokenApprovals> {
+        self.approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| env::panic_str("MT does not support Approval Management"))
+    }
+
+    /// Removes the approvals of `owner_id` for `token_id` that `f` selects, releasing their
+    /// storage.
+    fn internal_revoke(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        f: impl FnOnce(&mut std::collections::HashMap<AccountId, Approval>),
+    ) {
+        expect_token_found(self.owner_by_id.get(token_id));
+        let approvals_by_id = self.approvals_mut();
+
+        // if token has no approvals, do nothing
+        if let Some(mut token_approvals) = approvals_by_id.get(token_id) {
+            if let Some(owner_approvals) = token_approvals.get_mut(owner_id) {
+                f(owner_approvals);
+                if owner_approvals.is_empty() {
+                    token_approvals.remove(owner_id);
+                }
+            }
+            // if this was the last approval, remove the whole HashMap to save space.
+            if token_approvals.is_empty() {
+                approvals_by_id.remove(token_id);
+            } else {
+                approvals_by_id.insert(token_id, &token_approvals);
+            }
+        }
+    }
+}
+
+impl MultiTokenApproval for MultiToken {
+    fn mt_approve(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        require!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR");
+        require!(token_ids.len() == amounts.len(), "The number of tokens and amounts must match");
+        let owner_id = env::predecessor_account_id();
+        require!(owner_id != account_id, "Cannot approve the owner of the tokens");
+
+        let mut tracker = StorageTracker::default();
+        let approval_ids = tracker.track(|| {
+            let mut approval_ids = Vec::with_capacity(token_ids.len());
+            for (token_id, amount) in token_ids.iter().zip(&amounts) {
+                expect_token_found(self.owner_by_id.get(token_id));
+                require!(
+                    self.internal_balance_of(token_id, &owner_id) >= amount.0,
+                    "Not enough balance to approve"
+                );
+
+                let next_approval_id_by_id = expect_approval(self.next_approval_id_by_id.as_mut());
+                let approval_id: u64 = next_approval_id_by_id.get(token_id).unwrap_or(1u64);
+                // increment next_approval_id for this token
+                next_approval_id_by_id.insert(token_id, &(approval_id + 1));
+
+                // update HashMap of approvals for this token
+                let approvals_by_id = self.approvals_mut();
+                let mut token_approvals = approvals_by_id.get(token_id).unwrap_or_default();
+                token_approvals
+                    .entry(owner_id.clone())
+                    .or_default()
+                    .insert(account_id.clone(), Approval { amount: *amount, approval_id });
+                approvals_by_id.insert(token_id, &token_approvals);
+
+                approval_ids.push(approval_id);
+            }
+            approval_ids
+        });
+        // Require that enough deposit was attached to pay for storage, and refund excess.
+        tracker.refund_deposit();
+
+        // if given `msg`, schedule call to `mt_on_approve` and return it. Else, return None.
+        msg.map(|msg| {
+            ext_approval_receiver::ext(account_id)
+                .with_static_gas(env::prepaid_gas() - GAS_FOR_MT_APPROVE)
+                .mt_on_approve(token_ids, amounts, owner_id, approval_ids, msg)
+        })
+    }
+
+    fn mt_revoke(&mut self, token_ids: Vec<TokenId>, account_id: AccountId) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| {
+            for token_id in &token_ids {
+                self.internal_revoke(token_id, &owner_id, |approvals| {
+                    approvals.remove(&account_id);
+                });
+            }
+        });
+        // The storage of the approvals was paid by their owner.
+        tracker.refund_released_deposit_to(owner_id);
+    }
+
+    fn mt_revoke_all(&mut self, token_ids: Vec<TokenId>) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| {
+            for token_id in &token_ids {
+                self.internal_revoke(token_id, &owner_id, |approvals| approvals.clear());
+            }
+        });
+        // The storage of the approvals was paid by their owner.
+        tracker.refund_released_deposit_to(owner_id);
+    }
+
+    fn mt_is_approved(
+        &self,
+        token_ids: Vec<TokenId>,
+        approved_account_id: AccountId,
+        amounts: Vec<U128>,
+        approval_ids: Option<Vec<u64>>,
+    ) -> bool {
+        require!(token_ids.len() == amounts.len(), "The number of tokens and amounts must match");
+        if let Some(approval_ids) = &approval_ids {
+            require!(
+                token_ids.len() == approval_ids.len(),
+                "The number of tokens and approval ids must match"
+            );
+        }
+
+        let approvals_by_id = if let Some(a) = self.approvals_by_id.as_ref() {
+            a
+        } else {
+            // contract does not support approval management
+            return false;
+        };
+
+        // approved by any of the owners of each token
+        token_ids.iter().zip(&amounts).enumerate().all(|(i, (token_id, amount))| {
+            expect_token_found(self.owner_by_id.get(token_id));
+            approvals_by_id.get(token_id).map_or(false, |token_approvals| {
+                token_approvals
+                    .values()
+                    .filter_map(|owner_approvals| owner_approvals.get(&approved_account_id))
+                    .any(|approval| {
+                        approval.amount.0 >= amount.0
+                            && approval_ids
+                                .as_ref()
+                                .map_or(true, |ids| ids[i] == approval.approval_id)
+                    })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::core::{MultiTokenCore, MultiTokenResolver};
+    use crate::test_utils::set_caller;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{serde_json, testing_env, Balance, PromiseResult, RuntimeFeesConfig, VMConfig};
+
+    const STORAGE_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
+    /// Creates the tokens, mints 100 of the token "sword" to `accounts(0)` and approves
+    /// `accounts(1)` to transfer 20 of them.
+    fn setup(context: &mut VMContextBuilder) -> MultiToken {
+        testing_env!(context.build());
+        set_caller(context, accounts(0), STORAGE_DEPOSIT);
+        let mut tokens = MultiToken::new(
+            b"t",
+            accounts(0),
+            Option::<Vec<u8>>::None,
+            Option::<Vec<u8>>::None,
+            Some(b"a"),
+        );
+        tokens.internal_mint("sword".to_string(), accounts(0), 100, None);
+
+        set_caller(context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(1), None);
+        tokens
+    }
+
+    fn is_approved(tokens: &MultiToken, account_id: AccountId, amount: u128) -> bool {
+        tokens.mt_is_approved(vec!["sword".to_string()], account_id, vec![U128(amount)], None)
+    }
+
+    /// Transfers the 20 approved swords of `accounts(0)` to `accounts(2)` as `accounts(1)`, with a
+    /// call of `mt_on_transfer`.
+    fn transfer_call_with_approval(context: &mut VMContextBuilder, tokens: &mut MultiToken) {
+        set_caller(context, accounts(1), 1);
+        tokens.mt_transfer_call(
+            accounts(2),
+            "sword".to_string(),
+            U128(20),
+            Some((accounts(0), 1)),
+            None,
+            "msg".to_string(),
+        );
+    }
+
+    /// Calls `mt_resolve_transfer` for the transfer of `transfer_call_with_approval`, where
+    /// `mt_on_transfer` returned `unused` swords.
+    fn resolve_with_unused(
+        context: &mut VMContextBuilder,
+        tokens: &mut MultiToken,
+        unused: u128,
+    ) -> Vec<U128> {
+        testing_env!(
+            context
+                .storage_usage(env::storage_usage())
+                .predecessor_account_id(accounts(0))
+                .attached_deposit(0)
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(serde_json::to_vec(&[U128(unused)]).unwrap())],
+        );
+        tokens.mt_resolve_transfer(
+            vec![accounts(0)],
+            accounts(2),
+            vec!["sword".to_string()],
+            vec![U128(20)],
+            Some(vec![Some((accounts(1), Approval { amount: U128(20), approval_id: 1 }))]),
+        )
+    }
+
+    #[test]
+    fn approve() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        assert!(tokens.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(20)],
+            Some(vec![1])
+        ));
+        assert!(!is_approved(&tokens, accounts(1), 21));
+        assert!(!is_approved(&tokens, accounts(2), 1));
+
+        // Approving again replaces the amount and the approval id.
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_approve(vec!["sword".to_string()], vec![U128(50)], accounts(1), None);
+        assert!(tokens.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(50)],
+            Some(vec![2])
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough balance to approve")]
+    fn approve_more_than_balance() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_approve(vec!["sword".to_string()], vec![U128(101)], accounts(2), None);
+    }
+
+    #[test]
+    fn transfer_with_approval() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        tokens.mt_transfer(
+            accounts(2),
+            "sword".to_string(),
+            U128(15),
+            Some((accounts(0), 1)),
+            None,
+        );
+
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(85));
+        assert_eq!(tokens.mt_balance_of(accounts(2), "sword".to_string()), U128(15));
+        assert!(is_approved(&tokens, accounts(1), 5));
+        assert!(!is_approved(&tokens, accounts(1), 6));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough approved amount")]
+    fn transfer_exceeding_approval() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        tokens.mt_transfer(
+            accounts(2),
+            "sword".to_string(),
+            U128(21),
+            Some((accounts(0), 1)),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is different from the given approval_id")]
+    fn transfer_with_wrong_approval_id() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        tokens.mt_transfer(accounts(2), "sword".to_string(), U128(1), Some((accounts(0), 2)), None);
+    }
+
+    #[test]
+    fn revoke() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_approve(vec!["sword".to_string()], vec![U128(20)], accounts(2), None);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_revoke(vec!["sword".to_string()], accounts(1));
+        assert!(!is_approved(&tokens, accounts(1), 1));
+        assert!(is_approved(&tokens, accounts(2), 1));
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_revoke_all(vec!["sword".to_string()]);
+        assert!(!is_approved(&tokens, accounts(2), 1));
+        assert!(tokens.approvals_by_id.as_ref().unwrap().get(&"sword".to_string()).is_none());
+    }
+
+    #[test]
+    fn resolve_transfer_restores_approval() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        transfer_call_with_approval(&mut context, &mut tokens);
+        assert!(!is_approved(&tokens, accounts(1), 1));
+
+        let used = resolve_with_unused(&mut context, &mut tokens, 5);
+
+        assert_eq!(used, vec![U128(15)]);
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(85));
+        assert!(is_approved(&tokens, accounts(1), 5));
+        assert!(!is_approved(&tokens, accounts(1), 6));
+    }
+
+    #[test]
+    fn resolve_transfer_does_not_restore_revoked_approval() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        transfer_call_with_approval(&mut context, &mut tokens);
+
+        // The owner revokes the approval while the transfer is in flight.
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_revoke(vec!["sword".to_string()], accounts(1));
+
+        let used = resolve_with_unused(&mut context, &mut tokens, 5);
+
+        assert_eq!(used, vec![U128(15)]);
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(85));
+        assert!(!is_approved(&tokens, accounts(1), 1));
+    }
+
+    #[test]
+    fn resolve_transfer_does_not_restore_replaced_approval() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        transfer_call_with_approval(&mut context, &mut tokens);
+
+        // The owner approves a new amount while the transfer is in flight.
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_approve(vec!["sword".to_string()], vec![U128(3)], accounts(1), None);
+
+        resolve_with_unused(&mut context, &mut tokens, 5);
+
+        assert!(tokens.mt_is_approved(
+            vec!["sword".to_string()],
+            accounts(1),
+            vec![U128(3)],
+            Some(vec![2])
+        ));
+        assert!(!is_approved(&tokens, accounts(1), 4));
+    }
+}
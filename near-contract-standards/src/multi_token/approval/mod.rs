@@ -0,0 +1,92 @@
+mod approval_impl;
+mod approval_receiver;
+
+pub use approval_impl::*;
+pub use approval_receiver::*;
+
+use crate::multi_token::token::TokenId;
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use near_sdk::Promise;
+
+/// Trait used when it's desired to have a multi token that has a traditional escrow or approval
+/// system. This allows Alice to allow Bob to take up to 10 of the tokens with the identifier
+/// "gold" but not others. Unlike approvals of non-fungible tokens, each approval is for an amount
+/// of the token, which is reduced as the approved account transfers it.
+///
+/// [approval management standard]: https://github.com/near/NEPs/blob/master/neps/nep-0245.md
+pub trait MultiTokenApproval {
+    /// Add an approved account for the given amounts of specific tokens.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of at least 1 yoctoⓃ for
+    ///   security purposes
+    /// * Contract MAY require caller to attach larger deposit, to cover cost of
+    ///   storing approver data
+    /// * Contract MUST panic if the caller does not own at least the given amounts of the tokens
+    /// * Contract MUST increment approval ID even if re-approving an account
+    /// * If successfully approved or if had already been approved, and if `msg` is
+    ///   present, contract MUST call `mt_on_approve` on `account_id`. See
+    ///   `mt_on_approve` description below for details.
+    ///
+    /// Arguments:
+    /// * `token_ids`: the tokens for which to add an approval
+    /// * `amounts`: the amount of each token to approve, replacing any previous approval
+    /// * `account_id`: the account to add to the approvals
+    /// * `msg`: optional string to be passed to `mt_on_approve`
+    ///
+    /// Returns void, if no `msg` given. Otherwise, returns promise call to
+    /// `mt_on_approve`, which can resolve with whatever it wants.
+    fn mt_approve(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise>;
+
+    /// Revoke an approved account for specific tokens of the caller.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security
+    ///   purposes
+    /// * If contract requires >1yN deposit on `mt_approve`, contract
+    ///   MUST refund associated storage deposit when owner revokes approval
+    ///
+    /// Arguments:
+    /// * `token_ids`: the tokens for which to revoke an approval
+    /// * `account_id`: the account to remove from the approvals
+    fn mt_revoke(&mut self, token_ids: Vec<TokenId>, account_id: AccountId);
+
+    /// Revoke all approved accounts for specific tokens of the caller.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security
+    ///   purposes
+    /// * If contract requires >1yN deposit on `mt_approve`, contract
+    ///   MUST refund all associated storage deposit when owner revokes approvals
+    ///
+    /// Arguments:
+    /// * `token_ids`: the tokens with approvals to revoke
+    fn mt_revoke_all(&mut self, token_ids: Vec<TokenId>);
+
+    /// Check if tokens are approved for transfer by a given account, optionally checking the
+    /// approval ids.
+    ///
+    /// Arguments:
+    /// * `token_ids`: the tokens for which to check an approval
+    /// * `approved_account_id`: the account to check the existence of in the approvals
+    /// * `amounts`: the amount of each token the account must at least be approved for
+    /// * `approval_ids`: optional approval ids to check against the current approval id of each
+    ///   token
+    ///
+    /// Returns `true` if, for each of the tokens, an owner approved `approved_account_id` for at
+    /// least the given amount and, if `approval_ids` given, with the given approval id.
+    fn mt_is_approved(
+        &self,
+        token_ids: Vec<TokenId>,
+        approved_account_id: AccountId,
+        amounts: Vec<U128>,
+        approval_ids: Option<Vec<u64>>,
+    ) -> bool;
+}
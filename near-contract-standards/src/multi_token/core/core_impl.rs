@@ -0,0 +1,967 @@
+use super::resolver::MultiTokenResolver;
+use crate::multi_token::core::MultiTokenCore;
+use crate::multi_token::events::{MtBurn, MtMint, MtTransfer};
+use crate::multi_token::metadata::TokenMetadata;
+use crate::multi_token::token::{Approval, Token, TokenId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, TreeMap, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, require, AccountId, Balance, BorshStorageKey, CryptoHash,
+    Gas, IntoStorageKey, PromiseOrValue, PromiseResult, StorageTracker,
+};
+use std::collections::HashMap;
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(5_000_000_000_000);
+const GAS_FOR_MT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
+
+#[ext_contract(ext_self)]
+trait MTResolver {
+    fn mt_resolve_transfer(
+        &mut self,
+        previous_owner_ids: Vec<AccountId>,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, Approval)>>>,
+    ) -> Vec<U128>;
+}
+
+#[ext_contract(ext_receiver)]
+pub trait MultiTokenReceiver {
+    /// Returns the number of unused tokens for each of the `token_ids`
+    fn mt_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_ids: Vec<AccountId>,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+}
+
+/// Approvals of a single token, by owner and then by approved account.
+pub type TokenApprovals = HashMap<AccountId, HashMap<AccountId, Approval>>;
+
+/// Implementation of the multi token standard.
+/// Allows to include NEP-245 compatible tokens to any contract.
+/// There are next traits that any contract may implement:
+///     - MultiTokenCore -- interface with mt_transfer methods. MultiToken provides methods for it.
+///     - MultiTokenApproval -- interface with mt_approve methods. MultiToken provides methods for it.
+///     - MultiTokenEnumeration -- interface for getting lists of tokens. MultiToken provides methods for it.
+///     - MultiTokenMetadataProvider -- return metadata for the contract, up to contract to implement.
+///
+/// For example usage, see examples/multi-token/mt/src/lib.rs.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MultiToken {
+    // owner of contract
+    pub owner_id: AccountId,
+
+    // always required, the account each token was minted to
+    pub owner_by_id: TreeMap<TokenId, AccountId>,
+
+    // always required, the total supply of each token
+    pub total_supply: LookupMap<TokenId, Balance>,
+
+    // always required, the balance of each token for each account
+    pub balances: LookupMap<(TokenId, AccountId), Balance>,
+
+    // required by metadata extension
+    pub token_metadata_by_id: Option<LookupMap<TokenId, TokenMetadata>>,
+
+    // required by enumeration extension
+    pub tokens_per_owner: Option<LookupMap<AccountId, UnorderedSet<TokenId>>>,
+
+    // required by approval extension
+    pub approvals_by_id: Option<LookupMap<TokenId, TokenApprovals>>,
+    pub next_approval_id_by_id: Option<LookupMap<TokenId, u64>>,
+}
+
+#[derive(BorshStorageKey, BorshSerialize)]
+pub enum StorageKey {
+    TokensPerOwner { account_hash: CryptoHash },
+}
+
+impl MultiToken {
+    pub fn new<Q, R, S, T>(
+        owner_by_id_prefix: Q,
+        owner_id: AccountId,
+        token_metadata_prefix: Option<R>,
+        enumeration_prefix: Option<S>,
+        approval_prefix: Option<T>,
+    ) -> Self
+    where
+        Q: IntoStorageKey,
+        R: IntoStorageKey,
+        S: IntoStorageKey,
+        T: IntoStorageKey,
+    {
+        let (approvals_by_id, next_approval_id_by_id) = if let Some(prefix) = approval_prefix {
+            let prefix: Vec<u8> = prefix.into_storage_key();
+            (
+                Some(LookupMap::new(prefix.clone())),
+                Some(LookupMap::new([prefix, "n".into()].concat())),
+            )
+        } else {
+            (None, None)
+        };
+        let prefix: Vec<u8> = owner_by_id_prefix.into_storage_key();
+
+        Self {
+            owner_id,
+            owner_by_id: TreeMap::new(prefix.clone()),
+            total_supply: LookupMap::new([prefix.clone(), "s".into()].concat()),
+            balances: LookupMap::new([prefix, "b".into()].concat()),
+            token_metadata_by_id: token_metadata_prefix.map(LookupMap::new),
+            tokens_per_owner: enumeration_prefix.map(LookupMap::new),
+            approvals_by_id,
+            next_approval_id_by_id,
+        }
+    }
+
+    /// Returns the balance of `account_id` for the given `token_id`, which is zero if the account
+    /// never owned the token.
+    pub fn internal_balance_of(&self, token_id: &TokenId, account_id: &AccountId) -> Balance {
+        self.balances.get(&(token_id.clone(), account_id.clone())).unwrap_or(0)
+    }
+
+    /// Adds `amount` of the token to the balance of `account_id` without any checks.
+    ///
+    /// A new balance allocates storage, which the caller is responsible for charging, e.g. with
+    /// [`StorageTracker`]. The storage of the balances created by transfers is paid by the
+    /// contract, as the standard requires transfers to attach exactly 1 yoctoⓃ.
+    pub fn internal_deposit(
+        &mut self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let balance = self.internal_balance_of(token_id, account_id);
+        let new_balance =
+            balance.checked_add(amount).unwrap_or_else(|| env::panic_str("Balance overflow"));
+        self.balances.insert(&(token_id.clone(), account_id.clone()), &new_balance);
+
+        // if using Enumeration standard, add the token to the owner's token list
+        if balance == 0 {
+            if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+                let mut owner_tokens = tokens_per_owner.get(account_id).unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::TokensPerOwner {
+                        account_hash: env::sha256_array(account_id.as_bytes()),
+                    })
+                });
+                owner_tokens.insert(token_id);
+                tokens_per_owner.insert(account_id, &owner_tokens);
+            }
+        }
+    }
+
+    /// Subtracts `amount` of the token from the balance of `account_id`, panicking if the balance
+    /// is not sufficient.
+    pub fn internal_withdraw(
+        &mut self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+        amount: Balance,
+    ) {
+        let balance = self.internal_balance_of(token_id, account_id);
+        let new_balance = balance
+            .checked_sub(amount)
+            .unwrap_or_else(|| env::panic_str("The account doesn't have enough balance"));
+        if new_balance > 0 {
+            self.balances.insert(&(token_id.clone(), account_id.clone()), &new_balance);
+            return;
+        }
+        self.balances.remove(&(token_id.clone(), account_id.clone()));
+
+        // if using Enumeration standard, remove the token from the owner's token list
+        if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+            if let Some(mut owner_tokens) = tokens_per_owner.get(account_id) {
+                owner_tokens.remove(token_id);
+                if owner_tokens.is_empty() {
+                    tokens_per_owner.remove(account_id);
+                } else {
+                    tokens_per_owner.insert(account_id, &owner_tokens);
+                }
+            }
+        }
+    }
+
+    /// Transfer `amount` of `token_id` to `receiver_id`, from `sender_id` or, if `approval` is
+    /// given, from the owner that approved `sender_id`. The used amount is deducted from the
+    /// approval.
+    ///
+    /// Does not emit the transfer event. Returns previous owner and the approval used, as it was
+    /// before the transfer.
+    pub fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        #[allow(clippy::ptr_arg)] token_id: &TokenId,
+        amount: Balance,
+        approval: Option<(AccountId, u64)>,
+    ) -> (AccountId, Option<(AccountId, Approval)>) {
+        require!(amount > 0, "The amount should be a positive number");
+        require!(self.total_supply.get(token_id).is_some(), "Token not found");
+
+        let (owner_id, used_approval) = match approval {
+            Some((owner_id, approval_id)) => {
+                let approval =
+                    self.internal_use_approval(token_id, &owner_id, sender_id, approval_id, amount);
+                (owner_id, Some((sender_id.clone(), approval)))
+            }
+            None => (sender_id.clone(), None),
+        };
+
+        require!(&owner_id != receiver_id, "Current and next owner must differ");
+
+        self.internal_withdraw(token_id, &owner_id, amount);
+        self.internal_deposit(token_id, receiver_id, amount);
+
+        (owner_id, used_approval)
+    }
+
+    /// Deducts `amount` from the approval of `account_id` to transfer `token_id` on behalf of
+    /// `owner_id`, checking that the approval exists, matches `approval_id` and is sufficient.
+    /// Returns the approval as it was before.
+    ///
+    /// An exhausted approval is kept with an amount of zero until it is revoked, so that the
+    /// tokens refunded by `mt_resolve_transfer` can be restored to it.
+    fn internal_use_approval(
+        &mut self,
+        #[allow(clippy::ptr_arg)] token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+        approval_id: u64,
+        amount: Balance,
+    ) -> Approval {
+        let approvals_by_id = self
+            .approvals_by_id
+            .as_mut()
+            .unwrap_or_else(|| env::panic_str("MT does not support Approval Management"));
+        let mut token_approvals = approvals_by_id.get(token_id).unwrap_or_default();
+        let owner_approvals = token_approvals
+            .get_mut(owner_id)
+            .unwrap_or_else(|| env::panic_str("Sender not approved"));
+        let approval = *owner_approvals
+            .get(account_id)
+            .unwrap_or_else(|| env::panic_str("Sender not approved"));
+
+        require!(
+            approval.approval_id == approval_id,
+            format!(
+                "The actual approval_id {} is different from the given approval_id {}",
+                approval.approval_id, approval_id
+            )
+        );
+        require!(approval.amount.0 >= amount, "Not enough approved amount");
+
+        let remaining = approval.amount.0 - amount;
+        owner_approvals
+            .insert(account_id.clone(), Approval { amount: remaining.into(), approval_id });
+        approvals_by_id.insert(token_id, &token_approvals);
+
+        approval
+    }
+
+    /// Transfer several tokens to `receiver_id`, see [`MultiToken::internal_transfer`], and emit
+    /// the transfer events.
+    ///
+    /// Returns the previous owners and the approvals used for each token.
+    #[allow(clippy::type_complexity)]
+    pub fn internal_batch_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_ids: &[TokenId],
+        amounts: &[Balance],
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+    ) -> (Vec<AccountId>, Vec<Option<(AccountId, Approval)>>) {
+        require!(!token_ids.is_empty(), "At least one token must be transferred");
+        require!(token_ids.len() == amounts.len(), "The number of tokens and amounts must match");
+        let approvals = approvals.unwrap_or_else(|| vec![None; token_ids.len()]);
+        require!(
+            token_ids.len() == approvals.len(),
+            "The number of tokens and approvals must match"
+        );
+
+        let mut previous_owner_ids = Vec::with_capacity(token_ids.len());
+        let mut used_approvals = Vec::with_capacity(token_ids.len());
+        for ((token_id, amount), approval) in token_ids.iter().zip(amounts).zip(approvals) {
+            let (owner_id, used_approval) =
+                self.internal_transfer(sender_id, receiver_id, token_id, *amount, approval);
+            previous_owner_ids.push(owner_id);
+            used_approvals.push(used_approval);
+        }
+
+        let amounts: Vec<String> = amounts.iter().map(|amount| amount.to_string()).collect();
+        let transfers: Vec<_> = token_ids
+            .iter()
+            .zip(&amounts)
+            .zip(previous_owner_ids.iter().zip(&used_approvals))
+            .map(|((token_id, amount), (owner_id, approval))| {
+                ([token_id.as_str()], [amount.as_str()], owner_id, approval)
+            })
+            .collect();
+        let events: Vec<_> = transfers
+            .iter()
+            .map(|(token_ids, amounts, owner_id, approval)| MtTransfer {
+                old_owner_id: owner_id,
+                new_owner_id: receiver_id,
+                token_ids,
+                amounts,
+                authorized_id: approval.as_ref().map(|(account_id, _)| account_id),
+                memo: memo.as_deref(),
+            })
+            .collect();
+        MtTransfer::emit_many(&events);
+
+        (previous_owner_ids, used_approvals)
+    }
+
+    /// Mint a new token without checking:
+    /// * Whether the caller id is equal to the `owner_id`
+    /// * Assumes there will be a refund to the predecessor after covering the storage costs
+    ///
+    /// Returns the newly minted token and emits the mint event
+    pub fn internal_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        amount: Balance,
+        token_metadata: Option<TokenMetadata>,
+    ) -> Token {
+        let token = self.internal_mint_with_refund(
+            token_id,
+            token_owner_id,
+            amount,
+            token_metadata,
+            Some(env::predecessor_account_id()),
+        );
+        MtMint {
+            owner_id: &token.owner_id,
+            token_ids: &[&token.token_id],
+            amounts: &[&token.supply.0.to_string()],
+            memo: None,
+        }
+        .emit();
+        token
+    }
+
+    /// Mint a new token without checking:
+    /// * Whether the caller id is equal to the `owner_id`
+    /// * `refund_id` will transfer the left over balance after storage costs are calculated to the provided account.
+    ///   Typically the account will be the owner. If `None`, will not refund. This is useful for delaying refunding
+    ///   until multiple tokens have been minted.
+    ///
+    /// Returns the newly minted token and does not emit the mint event. This allows minting multiple before emitting.
+    pub fn internal_mint_with_refund(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        amount: Balance,
+        token_metadata: Option<TokenMetadata>,
+        refund_id: Option<AccountId>,
+    ) -> Token {
+        let mut tracker = StorageTracker::default();
+        let token = tracker.track(|| {
+            if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
+                env::panic_str("Must provide metadata");
+            }
+            if self.owner_by_id.get(&token_id).is_some() {
+                env::panic_str("token_id must be unique");
+            }
+            require!(amount > 0, "The amount should be a positive number");
+
+            self.owner_by_id.insert(&token_id, &token_owner_id);
+            self.total_supply.insert(&token_id, &amount);
+            self.internal_deposit(&token_id, &token_owner_id, amount);
+
+            // Metadata extension: Save metadata, keep variable around to return later.
+            // Note that check above already panicked if metadata extension in use but no metadata
+            // provided to call.
+            self.token_metadata_by_id
+                .as_mut()
+                .and_then(|by_id| by_id.insert(&token_id, token_metadata.as_ref().unwrap()));
+
+            Token {
+                token_id,
+                owner_id: token_owner_id,
+                supply: amount.into(),
+                metadata: token_metadata,
+            }
+        });
+
+        // Return any extra attached deposit not used for storage
+        if let Some(id) = refund_id {
+            tracker.refund_deposit_to(id);
+        }
+
+        token
+    }
+
+    /// Burn `amount` of `token_id` owned by `account_id`, reducing its total supply, and emit the
+    /// burn event. Does not check whether the caller is allowed to burn the tokens.
+    ///
+    /// The token is removed along with its metadata and approvals when its supply reaches zero.
+    pub fn internal_burn(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+        amount: Balance,
+        memo: Option<String>,
+    ) {
+        require!(amount > 0, "The amount should be a positive number");
+        let supply =
+            self.total_supply.get(token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        self.internal_withdraw(token_id, account_id, amount);
+        if supply > amount {
+            self.total_supply.insert(token_id, &(supply - amount));
+        } else {
+            // Every balance of the token is zero and was removed by `internal_withdraw`, along
+            // with the token in the enumeration of its owners.
+            self.owner_by_id.remove(token_id);
+            self.total_supply.remove(token_id);
+            if let Some(token_metadata_by_id) = &mut self.token_metadata_by_id {
+                token_metadata_by_id.remove(token_id);
+            }
+            if let Some(approvals_by_id) = &mut self.approvals_by_id {
+                approvals_by_id.remove(token_id);
+            }
+            if let Some(next_approval_id_by_id) = &mut self.next_approval_id_by_id {
+                next_approval_id_by_id.remove(token_id);
+            }
+        }
+        MtBurn {
+            owner_id: account_id,
+            token_ids: &[token_id],
+            amounts: &[&amount.to_string()],
+            authorized_id: None,
+            memo: memo.as_deref(),
+        }
+        .emit();
+    }
+
+    /// Returns the token with the given `token_id`, if it exists.
+    pub(crate) fn internal_get_token(&self, token_id: TokenId) -> Option<Token> {
+        let owner_id = self.owner_by_id.get(&token_id)?;
+        let supply = self.total_supply.get(&token_id).unwrap_or_default();
+        let metadata = self.token_metadata_by_id.as_ref().and_then(|by_id| by_id.get(&token_id));
+        Some(Token { token_id, owner_id, supply: supply.into(), metadata })
+    }
+
+    fn internal_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        assert_one_yocto();
+        require!(env::prepaid_gas() > GAS_FOR_MT_TRANSFER_CALL, "More gas is required");
+        let sender_id = env::predecessor_account_id();
+        let raw_amounts: Vec<Balance> = amounts.iter().map(|amount| amount.0).collect();
+        let (previous_owner_ids, used_approvals) = self.internal_batch_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &raw_amounts,
+            approvals,
+            memo,
+        );
+        let used_approvals =
+            if used_approvals.iter().any(Option::is_some) { Some(used_approvals) } else { None };
+        // Initiating receiver's call and the callback
+        ext_receiver::ext(receiver_id.clone())
+            .with_static_gas(env::prepaid_gas() - GAS_FOR_MT_TRANSFER_CALL)
+            .mt_on_transfer(
+                sender_id,
+                previous_owner_ids.clone(),
+                token_ids.clone(),
+                amounts.clone(),
+                msg,
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .mt_resolve_transfer(
+                        previous_owner_ids,
+                        receiver_id,
+                        token_ids,
+                        amounts,
+                        used_approvals,
+                    ),
+            )
+            .into()
+    }
+}
+
+impl MultiTokenCore for MultiToken {
+    fn mt_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    ) {
+        self.mt_batch_transfer(
+            receiver_id,
+            vec![token_id],
+            vec![amount],
+            Some(vec![approval]),
+            memo,
+        )
+    }
+
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let amounts: Vec<Balance> = amounts.into_iter().map(|amount| amount.0).collect();
+        self.internal_batch_transfer(
+            &sender_id,
+            &receiver_id,
+            &token_ids,
+            &amounts,
+            approvals,
+            memo,
+        );
+    }
+
+    fn mt_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        self.internal_batch_transfer_call(
+            receiver_id,
+            vec![token_id],
+            vec![amount],
+            Some(vec![approval]),
+            memo,
+            msg,
+        )
+    }
+
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>> {
+        self.internal_batch_transfer_call(receiver_id, token_ids, amounts, approvals, memo, msg)
+    }
+
+    fn mt_token(&self, token_ids: Vec<TokenId>) -> Vec<Option<Token>> {
+        token_ids.into_iter().map(|token_id| self.internal_get_token(token_id)).collect()
+    }
+
+    fn mt_balance_of(&self, account_id: AccountId, token_id: TokenId) -> U128 {
+        self.internal_balance_of(&token_id, &account_id).into()
+    }
+
+    fn mt_batch_balance_of(&self, account_id: AccountId, token_ids: Vec<TokenId>) -> Vec<U128> {
+        token_ids
+            .iter()
+            .map(|token_id| self.internal_balance_of(token_id, &account_id).into())
+            .collect()
+    }
+
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128> {
+        self.total_supply.get(&token_id).map(U128)
+    }
+
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>> {
+        token_ids.iter().map(|token_id| self.total_supply.get(token_id).map(U128)).collect()
+    }
+}
+
+impl MultiTokenResolver for MultiToken {
+    /// Returns the amounts of each token that were successfully transferred to `receiver_id`.
+    fn mt_resolve_transfer(
+        &mut self,
+        previous_owner_ids: Vec<AccountId>,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, Approval)>>>,
+    ) -> Vec<U128> {
+        require!(
+            previous_owner_ids.len() == token_ids.len() && amounts.len() == token_ids.len(),
+            "The number of tokens, amounts and previous owners must match"
+        );
+
+        // Get the unused amount from the `mt_on_transfer` call result, all of the tokens are
+        // returned if the call failed or returned an invalid value.
+        let unused_amounts: Vec<Balance> = match env::promise_result(0) {
+            PromiseResult::NotReady => env::abort(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<Vec<U128>>(&value) {
+                    Ok(unused) if unused.len() == amounts.len() => unused
+                        .iter()
+                        .zip(&amounts)
+                        .map(|(unused, amount)| std::cmp::min(unused.0, amount.0))
+                        .collect(),
+                    _ => amounts.iter().map(|amount| amount.0).collect(),
+                }
+            }
+            PromiseResult::Failed => amounts.iter().map(|amount| amount.0).collect(),
+        };
+        let mut approvals = approvals.unwrap_or_default().into_iter();
+
+        let mut refunds = vec![];
+        let mut used_amounts = Vec::with_capacity(amounts.len());
+        for (((token_id, amount), unused_amount), previous_owner_id) in
+            token_ids.iter().zip(&amounts).zip(unused_amounts).zip(&previous_owner_ids)
+        {
+            let approval = approvals.next().flatten();
+            // The receiver may have already transferred or burned some of the tokens.
+            let receiver_balance = self.internal_balance_of(token_id, &receiver_id);
+            let refund_amount = std::cmp::min(unused_amount, receiver_balance);
+            used_amounts.push(U128(amount.0 - refund_amount));
+            if refund_amount == 0 {
+                continue;
+            }
+
+            self.internal_withdraw(token_id, &receiver_id, refund_amount);
+            self.internal_deposit(token_id, previous_owner_id, refund_amount);
+
+            // If using Approval Management extension, restore the returned amount to the
+            // approval that was used for the transfer, unless the owner revoked or replaced it
+            // in the meantime.
+            if let (Some((account_id, approval)), Some(approvals_by_id)) =
+                (approval, &mut self.approvals_by_id)
+            {
+                if let Some(mut token_approvals) = approvals_by_id.get(token_id) {
+                    let restored = token_approvals
+                        .get_mut(previous_owner_id)
+                        .and_then(|owner_approvals| owner_approvals.get_mut(&account_id))
+                        .filter(|restored| restored.approval_id == approval.approval_id);
+                    if let Some(restored) = restored {
+                        restored.amount = U128(restored.amount.0 + refund_amount);
+                        approvals_by_id.insert(token_id, &token_approvals);
+                    }
+                }
+            }
+
+            refunds.push((token_id, previous_owner_id, refund_amount.to_string()));
+        }
+
+        let refunds: Vec<_> = refunds
+            .iter()
+            .map(|(token_id, owner_id, amount)| ([token_id.as_str()], [amount.as_str()], owner_id))
+            .collect();
+        let events: Vec<_> = refunds
+            .iter()
+            .map(|(token_ids, amounts, owner_id)| MtTransfer {
+                old_owner_id: &receiver_id,
+                new_owner_id: owner_id,
+                token_ids,
+                amounts,
+                authorized_id: None,
+                memo: None,
+            })
+            .collect();
+        if !events.is_empty() {
+            MtTransfer::emit_many(&events);
+        }
+
+        used_amounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_token::enumeration::MultiTokenEnumeration;
+    use crate::test_utils::set_caller;
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::{serde_json, testing_env, RuntimeFeesConfig, VMConfig};
+
+    const STORAGE_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
+    /// Creates the tokens and mints 100 of the token "sword" to `accounts(0)`.
+    fn setup(context: &mut VMContextBuilder) -> MultiToken {
+        testing_env!(context.build());
+        set_caller(context, accounts(0), STORAGE_DEPOSIT);
+        let mut tokens = MultiToken::new(
+            b"t",
+            accounts(0),
+            Option::<Vec<u8>>::None,
+            Some(b"e"),
+            Option::<Vec<u8>>::None,
+        );
+        tokens.internal_mint("sword".to_string(), accounts(0), 100, None);
+        tokens
+    }
+
+    fn token_ids_of(tokens: &MultiToken, account_id: AccountId) -> Vec<TokenId> {
+        tokens
+            .mt_tokens_for_owner(account_id, None, None)
+            .into_iter()
+            .map(|token| token.token_id)
+            .collect()
+    }
+
+    /// Sets the result of `mt_on_transfer` for the call of `mt_resolve_transfer`.
+    fn set_resolve_result(context: &mut VMContextBuilder, result: PromiseResult) {
+        testing_env!(
+            context
+                .storage_usage(env::storage_usage())
+                .predecessor_account_id(accounts(0))
+                .attached_deposit(0)
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
+    fn unused(amounts: &[u128]) -> PromiseResult {
+        let amounts: Vec<U128> = amounts.iter().copied().map(U128).collect();
+        PromiseResult::Successful(serde_json::to_vec(&amounts).unwrap())
+    }
+
+    #[test]
+    fn transfer() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(30), None, None);
+
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(70));
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(30));
+        assert_eq!(tokens.mt_supply("sword".to_string()), Some(U128(100)));
+        assert_eq!(token_ids_of(&tokens, accounts(1)), vec!["sword".to_string()]);
+
+        // The storage of the new balance is paid by the contract.
+        assert!(get_created_receipts().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn transfer_with_storage_deposit() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(30), None, None);
+    }
+
+    #[test]
+    fn transfer_whole_balance() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(30), None, None);
+
+        // The balance of the sender is removed, without refunding its storage.
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(70), None, None);
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(100));
+        assert!(tokens.balances.get(&("sword".to_string(), accounts(0))).is_none());
+        assert!(token_ids_of(&tokens, accounts(0)).is_empty());
+        assert!(get_created_receipts().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn transfer_insufficient_balance() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(101), None, None);
+    }
+
+    #[test]
+    fn batch_transfer() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), STORAGE_DEPOSIT);
+        tokens.internal_mint("shield".to_string(), accounts(0), 1, None);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_batch_transfer(
+            accounts(1),
+            vec!["sword".to_string(), "shield".to_string()],
+            vec![U128(10), U128(1)],
+            None,
+            None,
+        );
+
+        assert_eq!(
+            tokens
+                .mt_batch_balance_of(accounts(1), vec!["sword".to_string(), "shield".to_string()]),
+            vec![U128(10), U128(1)]
+        );
+        assert_eq!(tokens.mt_balance_of(accounts(0), "shield".to_string()), U128(0));
+        assert_eq!(token_ids_of(&tokens, accounts(0)), vec!["sword".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of tokens and amounts must match")]
+    fn batch_transfer_mismatched_amounts() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_batch_transfer(
+            accounts(1),
+            vec!["sword".to_string()],
+            vec![U128(10), U128(1)],
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn resolve_transfer_refunds_unused() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer_call(
+            accounts(1),
+            "sword".to_string(),
+            U128(30),
+            None,
+            None,
+            "msg".to_string(),
+        );
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(30));
+
+        set_resolve_result(&mut context, unused(&[10]));
+        let used = tokens.mt_resolve_transfer(
+            vec![accounts(0)],
+            accounts(1),
+            vec!["sword".to_string()],
+            vec![U128(30)],
+            None,
+        );
+
+        assert_eq!(used, vec![U128(20)]);
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(80));
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(20));
+    }
+
+    #[test]
+    fn resolve_failed_transfer_refunds_all() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer_call(
+            accounts(1),
+            "sword".to_string(),
+            U128(30),
+            None,
+            None,
+            "msg".to_string(),
+        );
+
+        set_resolve_result(&mut context, PromiseResult::Failed);
+        let used = tokens.mt_resolve_transfer(
+            vec![accounts(0)],
+            accounts(1),
+            vec!["sword".to_string()],
+            vec![U128(30)],
+            None,
+        );
+
+        assert_eq!(used, vec![U128(0)]);
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(100));
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(0));
+        assert!(token_ids_of(&tokens, accounts(1)).is_empty());
+    }
+
+    #[test]
+    fn resolve_transfer_refunds_what_receiver_kept() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer_call(
+            accounts(1),
+            "sword".to_string(),
+            U128(30),
+            None,
+            None,
+            "msg".to_string(),
+        );
+        // The receiver already passed on 25 of the tokens.
+        set_caller(&mut context, accounts(1), 1);
+        tokens.mt_transfer(accounts(2), "sword".to_string(), U128(25), None, None);
+
+        set_resolve_result(&mut context, unused(&[30]));
+        let used = tokens.mt_resolve_transfer(
+            vec![accounts(0)],
+            accounts(1),
+            vec!["sword".to_string()],
+            vec![U128(30)],
+            None,
+        );
+
+        assert_eq!(used, vec![U128(25)]);
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(75));
+        assert_eq!(tokens.mt_balance_of(accounts(1), "sword".to_string()), U128(0));
+    }
+
+    #[test]
+    fn burn() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.internal_burn(&accounts(0), &"sword".to_string(), 40, None);
+
+        assert_eq!(tokens.mt_balance_of(accounts(0), "sword".to_string()), U128(60));
+        assert_eq!(tokens.mt_supply("sword".to_string()), Some(U128(60)));
+        assert!(tokens.mt_token(vec!["sword".to_string()])[0].is_some());
+    }
+
+    #[test]
+    fn burn_whole_supply() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+        set_caller(&mut context, accounts(0), 1);
+        tokens.mt_transfer(accounts(1), "sword".to_string(), U128(30), None, None);
+
+        set_caller(&mut context, accounts(0), 1);
+        tokens.internal_burn(&accounts(0), &"sword".to_string(), 70, None);
+        assert_eq!(tokens.mt_supply("sword".to_string()), Some(U128(30)));
+        assert!(token_ids_of(&tokens, accounts(0)).is_empty());
+
+        set_caller(&mut context, accounts(1), 1);
+        tokens.internal_burn(&accounts(1), &"sword".to_string(), 30, None);
+        assert_eq!(tokens.mt_supply("sword".to_string()), None);
+        assert_eq!(tokens.mt_token(vec!["sword".to_string()]), vec![None]);
+        assert!(tokens.owner_by_id.is_empty());
+        assert!(token_ids_of(&tokens, accounts(1)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough balance")]
+    fn burn_more_than_balance() {
+        let mut context = VMContextBuilder::new();
+        let mut tokens = setup(&mut context);
+
+        set_caller(&mut context, accounts(1), 1);
+        tokens.internal_burn(&accounts(1), &"sword".to_string(), 1, None);
+    }
+}
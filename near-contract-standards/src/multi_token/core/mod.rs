@@ -0,0 +1,140 @@
+mod core_impl;
+
+mod receiver;
+mod resolver;
+
+pub use self::core_impl::*;
+
+pub use self::receiver::*;
+pub use self::resolver::*;
+
+use crate::multi_token::token::{Token, TokenId};
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use near_sdk::PromiseOrValue;
+
+/// Used for all multi tokens. The specification for the [core multi token standard] lays out the
+/// reasoning for each method. It's important to check out
+/// [MultiTokenReceiver](crate::multi_token::core::MultiTokenReceiver) and
+/// [MultiTokenResolver](crate::multi_token::core::MultiTokenResolver) to understand how the
+/// cross-contract call work.
+///
+/// [core multi token standard]: <https://github.com/near/NEPs/blob/master/neps/nep-0245.md>
+pub trait MultiTokenCore {
+    /// Simple transfer. Transfer `amount` of a given `token_id` from current owner to
+    /// `receiver_id`.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security purposes
+    /// * Caller must have greater than or equal to the `amount` being requested
+    /// * Contract MUST panic if called by someone other than token owner or,
+    ///   if using Approval Management, one of the approved accounts
+    /// * `approval` is for use with Approval Management extension, see that document for
+    ///   full explanation.
+    ///
+    /// Arguments:
+    /// * `receiver_id`: the valid NEAR account receiving the token
+    /// * `token_id`: the token to transfer
+    /// * `amount`: the number of tokens to transfer, wrapped in quotes and treated
+    ///   like a string, although the number will be stored as an unsigned integer
+    ///   with 128 bits.
+    /// * `approval` (optional): a tuple of the owner of the token and the expected approval ID
+    ///   of the caller. A number smaller than 2^53, and therefore representable as JSON.
+    /// * `memo` (optional): for use cases that may benefit from indexing or
+    ///    providing information for a transfer
+    fn mt_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+    );
+
+    /// Simple batch transfer. Transfer given `token_ids` and `amounts` from current owner to
+    /// `receiver_id`.
+    ///
+    /// Requirements
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security purposes
+    /// * Contract MUST panic if called by someone other than token owner or,
+    ///   if using Approval Management, one of the approved accounts
+    /// * Contract MUST panic if the length of `token_ids` is not equal to `amounts`, or if
+    ///   `approvals` is given and its length is not equal to `token_ids`
+    ///
+    /// Arguments:
+    /// * `receiver_id`: the valid NEAR account receiving the tokens
+    /// * `token_ids`: the tokens to transfer
+    /// * `amounts`: the number of tokens to transfer, one per token
+    /// * `approvals` (optional): the owner and expected approval ID of each token, see
+    ///   [`MultiTokenCore::mt_transfer`]
+    /// * `memo` (optional): for use cases that may benefit from indexing or
+    ///    providing information for a transfer
+    fn mt_batch_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+    );
+
+    /// Transfer token and call a method on a receiver contract. A successful
+    /// workflow will end in a success execution outcome to the callback on the MT
+    /// contract at the method `mt_resolve_transfer`.
+    ///
+    /// Requirements:
+    /// * Caller of the method must attach a deposit of 1 yoctoⓃ for security
+    ///   purposes
+    /// * Contract MUST panic if called by someone other than token owner or,
+    ///   if using Approval Management, one of the approved accounts
+    /// * The receiving contract must implement `mt_on_transfer` according to the
+    ///   standard. If it does not, MT contract's `mt_resolve_transfer` MUST deal
+    ///   with the resulting failed cross-contract call and roll back the transfer.
+    /// * Contract MUST implement the behavior described in `mt_resolve_transfer`
+    ///
+    /// Arguments are the same as for [`MultiTokenCore::mt_transfer`], with:
+    /// * `msg`: specifies information needed by the receiving contract in
+    ///    order to properly handle the transfer. Can indicate both a function to
+    ///    call and the parameters to pass to that function.
+    ///
+    /// Returns the amount of the token that was used by the receiver.
+    fn mt_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+
+    /// Batch version of [`MultiTokenCore::mt_transfer_call`], transferring several tokens in a
+    /// single call to the receiver.
+    ///
+    /// Returns the amounts of each token that were used by the receiver.
+    fn mt_batch_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<Vec<U128>>;
+
+    /// Returns the tokens with the given `token_ids` or `null` for each token that doesn't exist.
+    fn mt_token(&self, token_ids: Vec<TokenId>) -> Vec<Option<Token>>;
+
+    /// Returns the balance of `account_id` for the given `token_id`.
+    fn mt_balance_of(&self, account_id: AccountId, token_id: TokenId) -> U128;
+
+    /// Returns the balances of `account_id` for each of the given `token_ids`.
+    fn mt_batch_balance_of(&self, account_id: AccountId, token_ids: Vec<TokenId>) -> Vec<U128>;
+
+    /// Returns the total supply of the given `token_id` or `null` if no such token.
+    fn mt_supply(&self, token_id: TokenId) -> Option<U128>;
+
+    /// Returns the total supply of each of the given `token_ids` or `null` for each token that
+    /// doesn't exist.
+    fn mt_batch_supply(&self, token_ids: Vec<TokenId>) -> Vec<Option<U128>>;
+}
@@ -0,0 +1,43 @@
+use crate::multi_token::token::{Approval, TokenId};
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+
+/// Used when MTs are transferred using `mt_transfer_call` or `mt_batch_transfer_call`. This is the
+/// method that's called after `mt_on_transfer`. This trait is implemented on the MT contract.
+pub trait MultiTokenResolver {
+    /// Finalize an `mt_transfer_call` or `mt_batch_transfer_call` chain of cross-contract calls.
+    ///
+    /// The `mt_transfer_call` process:
+    ///
+    /// 1. Sender calls `mt_transfer_call` on MT contract
+    /// 2. MT contract transfers tokens from sender to receiver
+    /// 3. MT contract calls `mt_on_transfer` on receiver contract
+    /// 4+. [receiver contract may make other cross-contract calls]
+    /// N. MT contract resolves promise chain with `mt_resolve_transfer`, and may
+    ///    transfer unused tokens back to their previous owners
+    ///
+    /// Requirements:
+    /// * Contract MUST forbid calls to this function by any account except self
+    /// * If promise chain failed, contract MUST revert the whole transfer
+    /// * If promise chain resolves with unused amounts, contract MUST return them to their
+    ///   previous owners, as far as the receiver still owns them
+    ///
+    /// Arguments:
+    /// * `previous_owner_ids`: the owners of each token prior to the call to `mt_transfer_call`
+    /// * `receiver_id`: the `receiver_id` argument given to `mt_transfer_call`
+    /// * `token_ids`: the `token_ids` argument given to `mt_transfer_call`
+    /// * `amounts`: the `amounts` argument given to `mt_transfer_call`
+    /// * `approvals`: if using Approval Management, contract MUST provide the approved account
+    ///   and the approval it used for each token, and restore the returned amount to it in
+    ///   case of revert.
+    ///
+    /// Returns the amounts of each token that were successfully transferred to `receiver_id`.
+    fn mt_resolve_transfer(
+        &mut self,
+        previous_owner_ids: Vec<AccountId>,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<U128>,
+        approvals: Option<Vec<Option<(AccountId, Approval)>>>,
+    ) -> Vec<U128>;
+}
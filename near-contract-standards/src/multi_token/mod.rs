@@ -0,0 +1,20 @@
+/// The approval management part of the [multi token standard](https://github.com/near/NEPs/blob/master/neps/nep-0245.md).
+pub mod approval;
+/// The core multi token standard. This can be though of as the base standard, with the others being extension standards.
+pub mod core;
+/// Trait for the MT enumeration part of the standard.
+/// This provides useful view-only methods returning tokens and tokens by owner.
+pub mod enumeration;
+/// Macros typically used by a contract wanting to take advantage of the multi
+/// token NEAR contract standard approach.
+mod macros;
+/// Metadata traits and implementation for the contract and the individual tokens.
+pub mod metadata;
+/// The Token struct for the multi token.
+mod token;
+pub use self::token::{Approval, Token, TokenId};
+
+pub use self::core::MultiToken;
+pub use macros::*;
+
+pub mod events;
@@ -0,0 +1,31 @@
+use crate::multi_token::metadata::TokenMetadata;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// Token IDs of multi tokens are strings on NEAR, the same as for non-fungible tokens.
+pub type TokenId = String;
+
+/// In this implementation, the Token struct takes the metadata extension standard as an optional
+/// field, as it is frequently used by multi token contracts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Token {
+    pub token_id: TokenId,
+    /// The account the token was minted to.
+    pub owner_id: AccountId,
+    /// Total amount of the token in circulation.
+    pub supply: U128,
+    pub metadata: Option<TokenMetadata>,
+}
+
+/// Approval of an account to transfer up to `amount` of a token on behalf of its owner.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, BorshDeserialize, BorshSerialize,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Approval {
+    pub amount: U128,
+    pub approval_id: u64,
+}
@@ -0,0 +1,146 @@
+//! Events of the multisig module, logged when a proposal is created, confirmed, executed or
+//! deleted.
+//!
+//! These events follow the events format (nep-297):
+//! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
+//!
+//! The events are [`ProposalCreated`], [`ProposalConfirmed`], [`ProposalExecuted`] and
+//! [`ProposalDeleted`]. They are logged by calling `.emit()` on them.
+
+use crate::event::NearEvent;
+use crate::multisig::ProposalId;
+use near_sdk::AccountId;
+use serde::Serialize;
+
+/// Data to log when a proposal is created. To log this event,
+/// call [`.emit()`](ProposalCreated::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct ProposalCreated<'a> {
+    pub proposal_id: ProposalId,
+    pub proposer_id: &'a AccountId,
+    pub receiver_id: &'a AccountId,
+}
+
+impl ProposalCreated<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_multisig_v1(MultisigEventKind::ProposalCreated(&[self])).emit()
+    }
+}
+
+/// Data to log when a member confirms a proposal. To log this event,
+/// call [`.emit()`](ProposalConfirmed::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct ProposalConfirmed<'a> {
+    pub proposal_id: ProposalId,
+    pub account_id: &'a AccountId,
+}
+
+impl ProposalConfirmed<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_multisig_v1(MultisigEventKind::ProposalConfirmed(&[self])).emit()
+    }
+}
+
+/// Data to log when a proposal is executed. To log this event,
+/// call [`.emit()`](ProposalExecuted::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct ProposalExecuted<'a> {
+    pub proposal_id: ProposalId,
+    pub account_id: &'a AccountId,
+}
+
+impl ProposalExecuted<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_multisig_v1(MultisigEventKind::ProposalExecuted(&[self])).emit()
+    }
+}
+
+/// Data to log when a proposal is deleted without being executed. To log this event,
+/// call [`.emit()`](ProposalDeleted::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct ProposalDeleted<'a> {
+    pub proposal_id: ProposalId,
+    pub account_id: &'a AccountId,
+}
+
+impl ProposalDeleted<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_multisig_v1(MultisigEventKind::ProposalDeleted(&[self])).emit()
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct MultisigEvent<'a> {
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: MultisigEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+enum MultisigEventKind<'a> {
+    ProposalCreated(&'a [ProposalCreated<'a>]),
+    ProposalConfirmed(&'a [ProposalConfirmed<'a>]),
+    ProposalExecuted(&'a [ProposalExecuted<'a>]),
+    ProposalDeleted(&'a [ProposalDeleted<'a>]),
+}
+
+fn new_multisig<'a>(version: &'static str, event_kind: MultisigEventKind<'a>) -> NearEvent<'a> {
+    NearEvent::Multisig(MultisigEvent { version, event_kind })
+}
+
+fn new_multisig_v1(event_kind: MultisigEventKind) -> NearEvent {
+    new_multisig("1.0.0", event_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils, AccountId};
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice".to_string())
+    }
+
+    #[test]
+    fn proposal_created() {
+        ProposalCreated { proposal_id: 0, proposer_id: &bob(), receiver_id: &alice() }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"multisig","version":"1.0.0","event":"proposal_created","data":[{"proposal_id":0,"proposer_id":"bob","receiver_id":"alice"}]}"#
+        );
+    }
+
+    #[test]
+    fn proposal_lifecycle() {
+        ProposalConfirmed { proposal_id: 1, account_id: &alice() }.emit();
+        ProposalExecuted { proposal_id: 1, account_id: &bob() }.emit();
+        ProposalDeleted { proposal_id: 2, account_id: &bob() }.emit();
+        assert_eq!(
+            test_utils::get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"multisig","version":"1.0.0","event":"proposal_confirmed","data":[{"proposal_id":1,"account_id":"alice"}]}"#,
+                r#"EVENT_JSON:{"standard":"multisig","version":"1.0.0","event":"proposal_executed","data":[{"proposal_id":1,"account_id":"bob"}]}"#,
+                r#"EVENT_JSON:{"standard":"multisig","version":"1.0.0","event":"proposal_deleted","data":[{"proposal_id":2,"account_id":"bob"}]}"#
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+/// The methods of a multisig, see [`MultisigCore`](crate::multisig::MultisigCore).
+/// Takes name of the Contract struct and the inner field for the
+/// [`Multisig`](crate::multisig::Multisig).
+#[macro_export]
+macro_rules! impl_multisig {
+    ($contract: ident, $multisig: ident) => {
+        use $crate::multisig::{MultisigCore, Proposal, ProposalAction, ProposalId};
+
+        #[near_bindgen]
+        impl MultisigCore for $contract {
+            #[payable]
+            fn multisig_propose(
+                &mut self,
+                receiver_id: AccountId,
+                actions: Vec<ProposalAction>,
+                description: String,
+            ) -> ProposalId {
+                self.$multisig.multisig_propose(receiver_id, actions, description)
+            }
+
+            #[payable]
+            fn multisig_confirm(&mut self, proposal_id: ProposalId) {
+                self.$multisig.multisig_confirm(proposal_id)
+            }
+
+            fn multisig_execute(&mut self, proposal_id: ProposalId) -> Promise {
+                self.$multisig.multisig_execute(proposal_id)
+            }
+
+            fn multisig_delete(&mut self, proposal_id: ProposalId) {
+                self.$multisig.multisig_delete(proposal_id)
+            }
+
+            fn multisig_proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+                self.$multisig.multisig_proposal(proposal_id)
+            }
+
+            fn multisig_proposals(
+                &self,
+                from_index: Option<u64>,
+                limit: Option<u64>,
+            ) -> Vec<Proposal> {
+                self.$multisig.multisig_proposals(from_index, limit)
+            }
+
+            fn multisig_members(&self) -> Vec<AccountId> {
+                self.$multisig.multisig_members()
+            }
+
+            fn multisig_threshold(&self) -> u32 {
+                self.$multisig.multisig_threshold()
+            }
+
+            #[private]
+            fn multisig_add_member(&mut self, account_id: AccountId) {
+                self.$multisig.internal_add_member(&account_id)
+            }
+
+            #[private]
+            fn multisig_remove_member(&mut self, account_id: AccountId) {
+                self.$multisig.internal_remove_member(&account_id)
+            }
+
+            #[private]
+            fn multisig_set_threshold(&mut self, threshold: u32) {
+                self.$multisig.internal_set_threshold(threshold)
+            }
+        }
+    };
+}
@@ -0,0 +1,84 @@
+pub mod events;
+mod macros;
+mod multisig_impl;
+mod proposal;
+
+pub use multisig_impl::*;
+pub use proposal::*;
+
+use near_sdk::{AccountId, Promise};
+
+/// Proposals of actions executed by a contract once confirmed by a threshold of its members, as
+/// used by DAOs. The actions of a proposal, such as transfers, function calls, access keys or
+/// deployments, are executed on a single receiver as a [`Promise`], in order.
+///
+/// A proposal expires after the proposal duration of the contract, after which it can't be
+/// confirmed or executed, and can be deleted by anyone. The confirmations of members which have
+/// been removed don't count.
+///
+/// The members and the threshold can only be changed by executing proposals calling the contract
+/// itself, with `multisig_add_member`, `multisig_remove_member` and `multisig_set_threshold`.
+///
+/// The proposer pays for the storage of a proposal, and each member confirming it for the storage
+/// of its confirmation. The storage deposits are refunded when the proposal is executed or
+/// deleted.
+pub trait MultisigCore {
+    /// Creates a proposal to execute `actions` on `receiver_id`, confirmed by the
+    /// `env::predecessor_account_id`, which must be a member. Returns the ID of the proposal.
+    ///
+    /// Requirements:
+    /// * Caller must attach enough deposit to cover the storage of the proposal. The remaining
+    ///   deposit is refunded.
+    ///
+    /// Arguments:
+    /// - `receiver_id` - the account ID on which the actions are executed.
+    /// - `actions` - the actions, such as `[{"type": "transfer", "amount": "1000"}]`.
+    /// - `description` - a description of the proposal in a free form.
+    fn multisig_propose(
+        &mut self,
+        receiver_id: AccountId,
+        actions: Vec<ProposalAction>,
+        description: String,
+    ) -> ProposalId;
+
+    /// Confirms the proposal `proposal_id` on behalf of the `env::predecessor_account_id`, which
+    /// must be a member. The proposal must not have expired. Caller must attach enough deposit to
+    /// cover the storage of the confirmation, and the remaining deposit is refunded.
+    fn multisig_confirm(&mut self, proposal_id: ProposalId);
+
+    /// Executes the proposal `proposal_id`, confirmed by the threshold of members, on behalf of
+    /// the `env::predecessor_account_id`, which must be a member. The proposal must not have
+    /// expired, and is removed.
+    ///
+    /// Returns the result of the last action of the proposal.
+    fn multisig_execute(&mut self, proposal_id: ProposalId) -> Promise;
+
+    /// Deletes the proposal `proposal_id` without executing it. Only the proposer can delete a
+    /// proposal before it expires, after which any account can delete it.
+    fn multisig_delete(&mut self, proposal_id: ProposalId);
+
+    /// Returns the proposal `proposal_id`, if it hasn't been executed or deleted.
+    fn multisig_proposal(&self, proposal_id: ProposalId) -> Option<Proposal>;
+
+    /// Returns the proposals which haven't been executed or deleted, starting at the index
+    /// `from_index` (0 by default) and up to `limit` of them. Executing or deleting a proposal
+    /// moves the last proposal to its index.
+    fn multisig_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Proposal>;
+
+    /// Returns the members of the multisig.
+    fn multisig_members(&self) -> Vec<AccountId>;
+
+    /// Returns the number of members required to confirm a proposal.
+    fn multisig_threshold(&self) -> u32;
+
+    /// Adds `account_id` to the members. Can only be called by the contract itself.
+    fn multisig_add_member(&mut self, account_id: AccountId);
+
+    /// Removes `account_id` from the members, which must leave at least the threshold of members.
+    /// Can only be called by the contract itself.
+    fn multisig_remove_member(&mut self, account_id: AccountId);
+
+    /// Sets the number of members required to confirm a proposal, between 1 and the number of
+    /// members. Can only be called by the contract itself.
+    fn multisig_set_threshold(&mut self, threshold: u32);
+}
@@ -0,0 +1,527 @@
+use crate::multisig::events::{
+    ProposalConfirmed, ProposalCreated, ProposalDeleted, ProposalExecuted,
+};
+use crate::multisig::{Proposal, ProposalAction, ProposalId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::json_types::U64;
+use near_sdk::{env, require, AccountId, Balance, IntoStorageKey, Promise, StorageTracker};
+
+/// Implementation of a multisig, see [`MultisigCore`](super::MultisigCore): members propose actions on
+/// an account, which are executed as a [`Promise`] once `threshold` members confirmed them,
+/// unless the proposal expired.
+///
+/// The members and the threshold are changed with the `internal_*` methods, which are exposed by
+/// the `impl_multisig` macro as private methods, so that they can only be changed by executing a
+/// proposal calling them on the contract itself.
+///
+/// The storage of a proposal is paid by its proposer, and the storage of each confirmation by the
+/// member confirming it. Both are refunded when the proposal is executed or deleted.
+///
+/// For example usage, see the `impl_multisig` macro.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Multisig {
+    pub members: UnorderedSet<AccountId>,
+
+    /// The number of members required to confirm a proposal.
+    pub threshold: u32,
+
+    /// The duration in nanoseconds after which a proposal expires.
+    pub proposal_duration: u64,
+
+    /// Proposal ID -> Proposal which hasn't been executed or deleted yet.
+    pub proposals: UnorderedMap<ProposalId, Proposal>,
+
+    /// The ID of the next proposal.
+    pub next_proposal_id: ProposalId,
+}
+
+impl Multisig {
+    pub fn new<S>(
+        prefix: S,
+        members: Vec<AccountId>,
+        threshold: u32,
+        proposal_duration: u64,
+    ) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        let mut this = Self {
+            members: UnorderedSet::new([prefix.as_slice(), b"m"].concat()),
+            threshold,
+            proposal_duration,
+            proposals: UnorderedMap::new([prefix.as_slice(), b"p"].concat()),
+            next_proposal_id: 0,
+        };
+        this.members.extend(members);
+        this.assert_valid_threshold();
+        this
+    }
+
+    fn assert_valid_threshold(&self) {
+        require!(
+            self.threshold > 0 && u64::from(self.threshold) <= self.members.len(),
+            "The threshold should be between 1 and the number of members"
+        );
+    }
+
+    pub fn assert_member(&self, account_id: &AccountId) {
+        require!(self.members.contains(account_id), "Only the members can call this method");
+    }
+
+    /// Returns the proposal `proposal_id`, panicking if it doesn't exist.
+    pub fn internal_unwrap_proposal(&self, proposal_id: ProposalId) -> Proposal {
+        self.proposals
+            .get(&proposal_id)
+            .unwrap_or_else(|| env::panic_str("The proposal doesn't exist"))
+    }
+
+    /// Returns the number of current members which confirmed `proposal`, so that the
+    /// confirmations of removed members don't count.
+    pub fn confirmation_count(&self, proposal: &Proposal) -> u32 {
+        proposal.confirmations.iter().filter(|account_id| self.members.contains(account_id)).count()
+            as u32
+    }
+
+    /// Whether `proposal` is confirmed by enough members to be executed.
+    pub fn is_approved(&self, proposal: &Proposal) -> bool {
+        self.confirmation_count(proposal) >= self.threshold
+    }
+
+    /// Returns the storage deposit paid by `account_id` to confirm a proposal, the cost of its
+    /// Borsh serialization in the confirmations of the proposal.
+    fn confirmation_storage_cost(account_id: &AccountId) -> Balance {
+        // The length prefix and the bytes of the account ID.
+        Balance::from(4 + account_id.as_str().len() as u64) * env::storage_byte_cost()
+    }
+
+    /// Removes `proposal`, refunding the storage of each confirmation to the
+    /// member which confirmed it, and the rest of the released storage to the proposer.
+    fn internal_remove_proposal(&mut self, proposal: &Proposal) {
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.proposals.remove(&proposal.id));
+        let mut released_deposit = tracker.released_deposit();
+        for account_id in &proposal.confirmations[1..] {
+            let refund =
+                std::cmp::min(Self::confirmation_storage_cost(account_id), released_deposit);
+            if refund > 0 {
+                Promise::new(account_id.clone()).transfer(refund);
+            }
+            released_deposit -= refund;
+        }
+        if released_deposit > 0 {
+            Promise::new(proposal.proposer_id.clone()).transfer(released_deposit);
+        }
+    }
+
+    /// Creates a proposal of `proposer_id` to execute `actions` on `receiver_id`, confirmed by
+    /// `proposer_id`. Requires the attached deposit to cover the storage of the proposal, and
+    /// refunds the rest of it to `proposer_id`. Returns the ID of the proposal.
+    pub fn internal_propose(
+        &mut self,
+        proposer_id: &AccountId,
+        receiver_id: AccountId,
+        actions: Vec<ProposalAction>,
+        description: String,
+    ) -> ProposalId {
+        self.assert_member(proposer_id);
+        require!(!actions.is_empty(), "The proposal should have at least one action");
+        let id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        let expires_at = env::block_timestamp().saturating_add(self.proposal_duration);
+        let proposal = Proposal {
+            id,
+            proposer_id: proposer_id.clone(),
+            description,
+            receiver_id,
+            actions,
+            confirmations: vec![proposer_id.clone()],
+            expires_at: U64(expires_at),
+        };
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.proposals.insert(&id, &proposal));
+        tracker.refund_deposit_to(proposer_id.clone());
+        ProposalCreated { proposal_id: id, proposer_id, receiver_id: &proposal.receiver_id }.emit();
+        id
+    }
+
+    /// Confirms the proposal `proposal_id` on behalf of the member `account_id`. Requires the
+    /// attached deposit to cover the storage of the confirmation, and refunds the rest of it to
+    /// `account_id`.
+    pub fn internal_confirm(&mut self, account_id: &AccountId, proposal_id: ProposalId) {
+        self.assert_member(account_id);
+        let mut proposal = self.internal_unwrap_proposal(proposal_id);
+        require!(!proposal.is_expired(env::block_timestamp()), "The proposal has expired");
+        require!(
+            !proposal.confirmations.contains(account_id),
+            "The proposal is already confirmed by this member"
+        );
+        proposal.confirmations.push(account_id.clone());
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.proposals.insert(&proposal_id, &proposal));
+        tracker.refund_deposit_to(account_id.clone());
+        ProposalConfirmed { proposal_id, account_id }.emit();
+    }
+
+    /// Removes the approved proposal `proposal_id`, refunding its storage, and returns the
+    /// [`Promise`] executing it, on behalf of the member `account_id`.
+    pub fn internal_execute(&mut self, account_id: &AccountId, proposal_id: ProposalId) -> Promise {
+        self.assert_member(account_id);
+        let proposal = self.internal_unwrap_proposal(proposal_id);
+        require!(!proposal.is_expired(env::block_timestamp()), "The proposal has expired");
+        require!(self.is_approved(&proposal), "The proposal isn't confirmed by enough members");
+        self.internal_remove_proposal(&proposal);
+        ProposalExecuted { proposal_id, account_id }.emit();
+        proposal.into_promise()
+    }
+
+    /// Deletes the proposal `proposal_id` on behalf of `account_id`, which must be its proposer
+    /// unless the proposal has expired, refunding its storage.
+    pub fn internal_delete(&mut self, account_id: &AccountId, proposal_id: ProposalId) {
+        let proposal = self.internal_unwrap_proposal(proposal_id);
+        require!(
+            &proposal.proposer_id == account_id || proposal.is_expired(env::block_timestamp()),
+            "Only the proposer can delete a proposal before it expires"
+        );
+        self.internal_remove_proposal(&proposal);
+        ProposalDeleted { proposal_id, account_id }.emit();
+    }
+
+    pub fn internal_add_member(&mut self, account_id: &AccountId) {
+        require!(self.members.insert(account_id), "The account is already a member");
+    }
+
+    pub fn internal_remove_member(&mut self, account_id: &AccountId) {
+        require!(self.members.remove(account_id), "The account is not a member");
+        self.assert_valid_threshold();
+    }
+
+    pub fn internal_set_threshold(&mut self, threshold: u32) {
+        self.threshold = threshold;
+        self.assert_valid_threshold();
+    }
+
+    pub fn multisig_propose(
+        &mut self,
+        receiver_id: AccountId,
+        actions: Vec<ProposalAction>,
+        description: String,
+    ) -> ProposalId {
+        let proposer_id = env::predecessor_account_id();
+        self.internal_propose(&proposer_id, receiver_id, actions, description)
+    }
+
+    pub fn multisig_confirm(&mut self, proposal_id: ProposalId) {
+        let account_id = env::predecessor_account_id();
+        self.internal_confirm(&account_id, proposal_id);
+    }
+
+    pub fn multisig_execute(&mut self, proposal_id: ProposalId) -> Promise {
+        let account_id = env::predecessor_account_id();
+        self.internal_execute(&account_id, proposal_id)
+    }
+
+    pub fn multisig_delete(&mut self, proposal_id: ProposalId) {
+        let account_id = env::predecessor_account_id();
+        self.internal_delete(&account_id, proposal_id);
+    }
+
+    pub fn multisig_proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+        self.proposals.get(&proposal_id)
+    }
+
+    pub fn multisig_proposals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Proposal> {
+        let limit = limit.unwrap_or(u64::MAX);
+        require!(limit != 0, "Cannot provide limit of 0.");
+        let values = self.proposals.values_as_vector();
+        let start_index = from_index.unwrap_or_default();
+        let end_index = start_index.saturating_add(limit).min(values.len());
+        (start_index..end_index).filter_map(|index| values.get(index)).collect()
+    }
+
+    pub fn multisig_members(&self) -> Vec<AccountId> {
+        self.members.to_vec()
+    }
+
+    pub fn multisig_threshold(&self) -> u32 {
+        self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{refunds_to, set_caller};
+    use near_sdk::json_types::{Base64VecU8, U128};
+    use near_sdk::mock::{Receipt, VmAction};
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::{testing_env, Gas};
+
+    /// The deposit attached to proposals and confirmations, which covers their storage.
+    const DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
+    /// Returns a multisig of `accounts(0)` to `accounts(2)` with a threshold of 2 and a proposal
+    /// duration of 1000, and a proposal of `accounts(0)` transferring 10 to `accounts(3)`.
+    fn setup(context: &mut VMContextBuilder) -> (Multisig, ProposalId) {
+        testing_env!(context.build());
+        let mut multisig =
+            Multisig::new(b"m", vec![accounts(0), accounts(1), accounts(2)], 2, 1000);
+        set_caller(context, accounts(0), DEPOSIT);
+        let proposal_id = multisig.multisig_propose(
+            accounts(3),
+            vec![ProposalAction::Transfer { amount: U128(10) }],
+            "Pay danny".to_string(),
+        );
+        (multisig, proposal_id)
+    }
+
+    #[test]
+    fn execute() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        let proposal_cost = DEPOSIT - refunds_to(accounts(0))[0];
+        set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        assert_eq!(multisig.confirmation_count(&multisig.internal_unwrap_proposal(proposal_id)), 2);
+        let confirmation_cost = DEPOSIT - refunds_to(accounts(1))[0];
+        assert_eq!(confirmation_cost, Multisig::confirmation_storage_cost(&accounts(1)));
+
+        // The storage of the proposal is refunded to the members which paid for it.
+        set_caller(&mut context, accounts(1), 0);
+        drop(multisig.multisig_execute(proposal_id));
+        assert!(multisig.multisig_proposal(proposal_id).is_none());
+        assert_eq!(
+            get_created_receipts(),
+            vec![
+                Receipt {
+                    receipt_indices: vec![],
+                    receiver_id: accounts(1),
+                    actions: vec![VmAction::Transfer { deposit: confirmation_cost }],
+                },
+                Receipt {
+                    receipt_indices: vec![],
+                    receiver_id: accounts(0),
+                    actions: vec![VmAction::Transfer { deposit: proposal_cost }],
+                },
+                Receipt {
+                    receipt_indices: vec![],
+                    receiver_id: accounts(3),
+                    actions: vec![VmAction::Transfer { deposit: 10 }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn propose_without_storage_deposit() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(0), 1);
+        multisig.multisig_propose(
+            accounts(3),
+            vec![ProposalAction::Transfer { amount: U128(10) }],
+            String::new(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn confirm_without_storage_deposit() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), 1);
+        multisig.multisig_confirm(proposal_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "The proposal isn't confirmed by enough members")]
+    fn execute_below_threshold() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(0), 0);
+        drop(multisig.multisig_execute(proposal_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "The proposal is already confirmed by this member")]
+    fn confirm_twice() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        multisig.multisig_confirm(proposal_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "The proposal has expired")]
+    fn execute_expired() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        set_caller(context.block_timestamp(1000), accounts(1), 0);
+        drop(multisig.multisig_execute(proposal_id));
+    }
+
+    #[test]
+    fn delete_expired() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        let proposal_cost = DEPOSIT - refunds_to(accounts(0))[0];
+
+        // Any account can delete an expired proposal, refunding its storage to the proposer.
+        set_caller(context.block_timestamp(1000), accounts(3), 0);
+        multisig.multisig_delete(proposal_id);
+        assert!(multisig.multisig_proposal(proposal_id).is_none());
+        assert_eq!(refunds_to(accounts(0)), vec![proposal_cost]);
+        assert!(refunds_to(accounts(3)).is_empty());
+    }
+
+    #[test]
+    fn proposals() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        for index in 0..3 {
+            set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+            multisig.multisig_propose(
+                accounts(3),
+                vec![ProposalAction::Transfer { amount: U128(index) }],
+                String::new(),
+            );
+        }
+        let ids = |proposals: Vec<Proposal>| -> Vec<ProposalId> {
+            proposals.into_iter().map(|proposal| proposal.id).collect()
+        };
+        assert_eq!(ids(multisig.multisig_proposals(None, None)), vec![0, 1, 2, 3]);
+        assert_eq!(ids(multisig.multisig_proposals(Some(1), Some(2))), vec![1, 2]);
+        assert_eq!(ids(multisig.multisig_proposals(Some(3), Some(2))), vec![3]);
+        assert!(multisig.multisig_proposals(Some(4), None).is_empty());
+
+        // The last proposal takes the index of a removed one.
+        multisig.multisig_delete(1);
+        assert_eq!(ids(multisig.multisig_proposals(None, None)), vec![0, 3, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposer can delete a proposal before it expires")]
+    fn delete_by_other_member() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), 0);
+        multisig.multisig_delete(proposal_id);
+    }
+
+    #[test]
+    fn removed_member_confirmation() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        multisig.internal_add_member(&accounts(3));
+        multisig.internal_remove_member(&accounts(1));
+
+        let proposal = multisig.internal_unwrap_proposal(proposal_id);
+        assert_eq!(proposal.confirmations, vec![accounts(0), accounts(1)]);
+        assert_eq!(multisig.confirmation_count(&proposal), 1);
+        assert!(!multisig.is_approved(&proposal));
+
+        set_caller(&mut context, accounts(3), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        drop(multisig.multisig_execute(proposal_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "The proposal isn't confirmed by enough members")]
+    fn execute_with_removed_member_confirmation() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, proposal_id) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(1), DEPOSIT);
+        multisig.multisig_confirm(proposal_id);
+        multisig.internal_remove_member(&accounts(1));
+        set_caller(&mut context, accounts(0), 0);
+        drop(multisig.multisig_execute(proposal_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "The threshold should be between 1 and the number of members")]
+    fn remove_member_below_threshold() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        multisig.internal_remove_member(&accounts(1));
+        multisig.internal_remove_member(&accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account is not a member")]
+    fn remove_non_member() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        multisig.internal_remove_member(&accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "The threshold should be between 1 and the number of members")]
+    fn set_zero_threshold() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        multisig.internal_set_threshold(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the members can call this method")]
+    fn propose_by_non_member() {
+        let mut context = VMContextBuilder::new();
+        let (mut multisig, _) = setup(&mut context);
+        set_caller(context.block_timestamp(10), accounts(3), DEPOSIT);
+        multisig.multisig_propose(
+            accounts(3),
+            vec![ProposalAction::Transfer { amount: U128(10) }],
+            String::new(),
+        );
+    }
+
+    #[test]
+    fn into_promise() {
+        testing_env!(VMContextBuilder::new().build());
+        let proposal = Proposal {
+            id: 0,
+            proposer_id: accounts(0),
+            description: String::new(),
+            receiver_id: accounts(3),
+            actions: vec![
+                ProposalAction::CreateAccount,
+                ProposalAction::Transfer { amount: U128(10) },
+                ProposalAction::FunctionCall {
+                    function_name: "new".to_string(),
+                    arguments: Base64VecU8(b"{}".to_vec()),
+                    amount: U128(1),
+                    gas: Gas(5),
+                },
+                ProposalAction::DeleteAccount { beneficiary_id: accounts(0) },
+            ],
+            confirmations: vec![accounts(0)],
+            expires_at: U64(1000),
+        };
+        drop(proposal.into_promise());
+        assert_eq!(
+            get_created_receipts(),
+            vec![Receipt {
+                receipt_indices: vec![],
+                receiver_id: accounts(3),
+                actions: vec![
+                    VmAction::CreateAccount,
+                    VmAction::Transfer { deposit: 10 },
+                    VmAction::FunctionCall {
+                        function_name: "new".to_string(),
+                        args: b"{}".to_vec(),
+                        gas: Gas(5),
+                        deposit: 1,
+                    },
+                    VmAction::DeleteAccount { beneficiary_id: accounts(0) },
+                ],
+            }]
+        );
+    }
+}
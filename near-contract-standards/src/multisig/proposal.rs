@@ -0,0 +1,177 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Gas, Promise, PromiseActionSpec, PromiseSpec, PublicKey, Timestamp};
+
+/// Identifier of a proposal, in the order of creation.
+pub type ProposalId = u64;
+
+/// An action of a proposal, which is added to the [`Promise`] executing the proposal. The actions
+/// mirror the methods of [`Promise`] adding actions, and convert into the [`PromiseActionSpec`] of
+/// the [`PromiseSpec`] executing the proposal.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalAction {
+    CreateAccount,
+    DeployContract {
+        code: Base64VecU8,
+    },
+    FunctionCall {
+        function_name: String,
+        arguments: Base64VecU8,
+        amount: U128,
+        gas: Gas,
+    },
+    /// A function call which also receives a share of the unused gas proportional to `weight`,
+    /// see [`Promise::function_call_weight`].
+    FunctionCallWeight {
+        function_name: String,
+        arguments: Base64VecU8,
+        amount: U128,
+        gas: Gas,
+        weight: u64,
+    },
+    Transfer {
+        amount: U128,
+    },
+    Stake {
+        amount: U128,
+        public_key: PublicKey,
+    },
+    AddFullAccessKey {
+        public_key: PublicKey,
+        #[serde(default)]
+        nonce: u64,
+    },
+    /// Adds an access key restricted to calling the comma separated `function_names` of
+    /// `receiver_id`, or any of its methods if `function_names` is empty.
+    AddAccessKey {
+        public_key: PublicKey,
+        allowance: U128,
+        receiver_id: AccountId,
+        function_names: String,
+        #[serde(default)]
+        nonce: u64,
+    },
+    DeleteKey {
+        public_key: PublicKey,
+    },
+    DeleteAccount {
+        beneficiary_id: AccountId,
+    },
+}
+
+impl ProposalAction {
+    /// Adds the action to `promise`.
+    pub fn add_to(self, promise: Promise) -> Promise {
+        PromiseActionSpec::from(self).add_to(promise)
+    }
+}
+
+impl From<ProposalAction> for PromiseActionSpec {
+    fn from(action: ProposalAction) -> Self {
+        match action {
+            ProposalAction::CreateAccount => Self::CreateAccount,
+            ProposalAction::DeployContract { code } => Self::DeployContract { code },
+            ProposalAction::FunctionCall { function_name, arguments, amount, gas } => {
+                Self::FunctionCall { function_name, arguments, amount, gas }
+            }
+            ProposalAction::FunctionCallWeight {
+                function_name,
+                arguments,
+                amount,
+                gas,
+                weight,
+            } => Self::FunctionCallWeight { function_name, arguments, amount, gas, weight },
+            ProposalAction::Transfer { amount } => Self::Transfer { amount },
+            ProposalAction::Stake { amount, public_key } => Self::Stake { amount, public_key },
+            ProposalAction::AddFullAccessKey { public_key, nonce } => {
+                Self::AddFullAccessKey { public_key, nonce }
+            }
+            ProposalAction::AddAccessKey {
+                public_key,
+                allowance,
+                receiver_id,
+                function_names,
+                nonce,
+            } => Self::AddAccessKey { public_key, allowance, receiver_id, function_names, nonce },
+            ProposalAction::DeleteKey { public_key } => Self::DeleteKey { public_key },
+            ProposalAction::DeleteAccount { beneficiary_id } => {
+                Self::DeleteAccount { beneficiary_id }
+            }
+        }
+    }
+}
+
+/// A proposal to execute `actions` on `receiver_id`, which is executed once confirmed by enough
+/// members before it expires.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub id: ProposalId,
+    pub proposer_id: AccountId,
+    pub description: String,
+    pub receiver_id: AccountId,
+    pub actions: Vec<ProposalAction>,
+    /// The members which confirmed the proposal, starting with the proposer.
+    pub confirmations: Vec<AccountId>,
+    /// The block timestamp in nanoseconds from which the proposal can't be confirmed or executed.
+    pub expires_at: U64,
+}
+
+impl Proposal {
+    /// Whether the proposal has expired at `timestamp`.
+    pub fn is_expired(&self, timestamp: Timestamp) -> bool {
+        timestamp >= self.expires_at.0
+    }
+
+    /// Returns the [`Promise`] executing the actions of the proposal on its receiver, in order.
+    pub fn into_promise(self) -> Promise {
+        self.actions
+            .into_iter()
+            .map(PromiseActionSpec::from)
+            .fold(PromiseSpec::new(self.receiver_id), PromiseSpec::add_action)
+            .into_promise()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde_json;
+
+    #[test]
+    fn action_json() {
+        let actions = vec![
+            ProposalAction::CreateAccount,
+            ProposalAction::Transfer { amount: U128(10) },
+            ProposalAction::FunctionCall {
+                function_name: "migrate".to_string(),
+                arguments: Base64VecU8(b"{}".to_vec()),
+                amount: U128(0),
+                gas: Gas(5_000_000_000_000),
+            },
+            ProposalAction::AddFullAccessKey {
+                public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap(),
+                nonce: 0,
+            },
+        ];
+        let json = r#"[{"type":"create_account"},{"type":"transfer","amount":"10"},{"type":"function_call","function_name":"migrate","arguments":"e30=","amount":"0","gas":"5000000000000"},{"type":"add_full_access_key","public_key":"ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp","nonce":0}]"#;
+        assert_eq!(serde_json::to_string(&actions).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Vec<ProposalAction>>(json).unwrap(), actions);
+
+        // The actions convert into the promise actions with the same representation.
+        let specs: Vec<PromiseActionSpec> = actions.into_iter().map(Into::into).collect();
+        assert_eq!(serde_json::to_string(&specs).unwrap(), json);
+    }
+
+    #[test]
+    fn action_json_default_nonce() {
+        let json = r#"{"type":"add_full_access_key","public_key":"ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"}"#;
+        assert!(matches!(
+            serde_json::from_str(json).unwrap(),
+            ProposalAction::AddFullAccessKey { nonce: 0, .. }
+        ));
+    }
+}
@@ -3,11 +3,12 @@
 use crate::non_fungible_token::approval::NonFungibleTokenApproval;
 use crate::non_fungible_token::token::TokenId;
 use crate::non_fungible_token::utils::{
-    assert_at_least_one_yocto, bytes_for_approved_account_id, refund_approved_account_ids,
-    refund_approved_account_ids_iter, refund_deposit,
+    assert_at_least_one_yocto, refund_approved_account_ids, refund_approved_account_ids_iter,
 };
 use crate::non_fungible_token::NonFungibleToken;
-use near_sdk::{assert_one_yocto, env, ext_contract, require, AccountId, Balance, Gas, Promise};
+use near_sdk::{
+    assert_one_yocto, env, ext_contract, require, AccountId, Balance, Gas, Promise, StorageTracker,
+};
 
 const GAS_FOR_NFT_APPROVE: Gas = Gas(10_000_000_000_000);
 const NO_DEPOSIT: Balance = 0;
@@ -49,23 +50,25 @@ impl NonFungibleTokenApproval for NonFungibleToken {
         require!(env::predecessor_account_id() == owner_id, "Predecessor must be token owner.");
 
         let next_approval_id_by_id = expect_approval(self.next_approval_id_by_id.as_mut());
-        // update HashMap of approvals for this token
-        let approved_account_ids = &mut approvals_by_id.get(&token_id).unwrap_or_default();
-        let approval_id: u64 = next_approval_id_by_id.get(&token_id).unwrap_or(1u64);
-        let old_approval_id = approved_account_ids.insert(account_id.clone(), approval_id);
-
-        // save updated approvals HashMap to contract's LookupMap
-        approvals_by_id.insert(&token_id, approved_account_ids);
-
-        // increment next_approval_id for this token
-        next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+        let mut tracker = StorageTracker::default();
+        let approval_id = tracker.track(|| {
+            // update HashMap of approvals for this token
+            let approved_account_ids = &mut approvals_by_id.get(&token_id).unwrap_or_default();
+            let approval_id: u64 = next_approval_id_by_id.get(&token_id).unwrap_or(1u64);
+            approved_account_ids.insert(account_id.clone(), approval_id);
+
+            // save updated approvals HashMap to contract's LookupMap
+            approvals_by_id.insert(&token_id, approved_account_ids);
+
+            // increment next_approval_id for this token
+            next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+            approval_id
+        });
 
         // If this approval replaced existing for same account, no storage was used.
         // Otherwise, require that enough deposit was attached to pay for storage, and refund
         // excess.
-        let storage_used =
-            if old_approval_id.is_none() { bytes_for_approved_account_id(&account_id) } else { 0 };
-        refund_deposit(storage_used);
+        tracker.refund_deposit();
 
         // if given `msg`, schedule call to `nft_on_approve` and return it. Else, return None.
         msg.map(|msg| {
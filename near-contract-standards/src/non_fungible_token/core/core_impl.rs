@@ -3,15 +3,13 @@ use crate::non_fungible_token::core::NonFungibleTokenCore;
 use crate::non_fungible_token::events::{NftMint, NftTransfer};
 use crate::non_fungible_token::metadata::TokenMetadata;
 use crate::non_fungible_token::token::{Token, TokenId};
-use crate::non_fungible_token::utils::{
-    hash_account_id, refund_approved_account_ids, refund_deposit_to_account,
-};
+use crate::non_fungible_token::utils::{hash_account_id, refund_approved_account_ids};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, TreeMap, UnorderedSet};
 use near_sdk::json_types::Base64VecU8;
 use near_sdk::{
     assert_one_yocto, env, ext_contract, require, AccountId, Balance, BorshStorageKey, CryptoHash,
-    Gas, IntoStorageKey, PromiseOrValue, PromiseResult, StorageUsage,
+    Gas, IntoStorageKey, PromiseOrValue, PromiseResult, StorageTracker, StorageUsage,
 };
 use std::collections::HashMap;
 
@@ -119,58 +117,60 @@ impl NonFungibleToken {
 
     // TODO: does this seem reasonable?
     fn measure_min_token_storage_cost(&mut self) {
-        let initial_storage_usage = env::storage_usage();
         // 64 Length because this is the max account id length
         let tmp_token_id = "a".repeat(64);
         let tmp_owner_id = AccountId::new_unchecked("a".repeat(64));
 
         // 1. set some dummy data
-        self.owner_by_id.insert(&tmp_token_id, &tmp_owner_id);
-        if let Some(token_metadata_by_id) = &mut self.token_metadata_by_id {
-            token_metadata_by_id.insert(
-                &tmp_token_id,
-                &TokenMetadata {
-                    title: Some("a".repeat(64)),
-                    description: Some("a".repeat(64)),
-                    media: Some("a".repeat(64)),
-                    media_hash: Some(Base64VecU8::from("a".repeat(64).as_bytes().to_vec())),
-                    copies: Some(1),
-                    issued_at: None,
-                    expires_at: None,
-                    starts_at: None,
-                    updated_at: None,
-                    extra: None,
-                    reference: None,
-                    reference_hash: None,
-                },
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| {
+            self.owner_by_id.insert(&tmp_token_id, &tmp_owner_id);
+            if let Some(token_metadata_by_id) = &mut self.token_metadata_by_id {
+                token_metadata_by_id.insert(
+                    &tmp_token_id,
+                    &TokenMetadata {
+                        title: Some("a".repeat(64)),
+                        description: Some("a".repeat(64)),
+                        media: Some("a".repeat(64)),
+                        media_hash: Some(Base64VecU8::from("a".repeat(64).as_bytes().to_vec())),
+                        copies: Some(1),
+                        issued_at: None,
+                        expires_at: None,
+                        starts_at: None,
+                        updated_at: None,
+                        extra: None,
+                        reference: None,
+                        reference_hash: None,
+                    },
+                );
+            }
+            if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+                let u = &mut UnorderedSet::new(StorageKey::TokensPerOwner {
+                    account_hash: env::sha256(tmp_owner_id.as_bytes()),
+                });
+                u.insert(&tmp_token_id);
+                tokens_per_owner.insert(&tmp_owner_id, u);
+            }
+            if let Some(approvals_by_id) = &mut self.approvals_by_id {
+                let mut approvals = HashMap::new();
+                approvals.insert(tmp_owner_id.clone(), 1u64);
+                approvals_by_id.insert(&tmp_token_id, &approvals);
+            }
+            if let Some(next_approval_id_by_id) = &mut self.next_approval_id_by_id {
+                next_approval_id_by_id.insert(&tmp_token_id, &1u64);
+            }
+            let u = UnorderedSet::new(
+                StorageKey::TokenPerOwnerInner { account_id_hash: hash_account_id(&tmp_owner_id) }
+                    .try_to_vec()
+                    .unwrap(),
             );
-        }
-        if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
-            let u = &mut UnorderedSet::new(StorageKey::TokensPerOwner {
-                account_hash: env::sha256(tmp_owner_id.as_bytes()),
-            });
-            u.insert(&tmp_token_id);
-            tokens_per_owner.insert(&tmp_owner_id, u);
-        }
-        if let Some(approvals_by_id) = &mut self.approvals_by_id {
-            let mut approvals = HashMap::new();
-            approvals.insert(tmp_owner_id.clone(), 1u64);
-            approvals_by_id.insert(&tmp_token_id, &approvals);
-        }
-        if let Some(next_approval_id_by_id) = &mut self.next_approval_id_by_id {
-            next_approval_id_by_id.insert(&tmp_token_id, &1u64);
-        }
-        let u = UnorderedSet::new(
-            StorageKey::TokenPerOwnerInner { account_id_hash: hash_account_id(&tmp_owner_id) }
-                .try_to_vec()
-                .unwrap(),
-        );
-        if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
-            tokens_per_owner.insert(&tmp_owner_id, &u);
-        }
+            if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+                tokens_per_owner.insert(&tmp_owner_id, &u);
+            }
+        });
 
         // 2. see how much space it took
-        self.extra_storage_in_bytes_per_token = env::storage_usage() - initial_storage_usage;
+        self.extra_storage_in_bytes_per_token = tracker.bytes_added();
 
         // 3. roll it all back
         if let Some(next_approval_id_by_id) = &mut self.next_approval_id_by_id {
@@ -357,50 +357,52 @@ impl NonFungibleToken {
         token_metadata: Option<TokenMetadata>,
         refund_id: Option<AccountId>,
     ) -> Token {
-        // Remember current storage usage if refund_id is Some
-        let initial_storage_usage = refund_id.map(|account_id| (account_id, env::storage_usage()));
-
-        if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
-            env::panic_str("Must provide metadata");
-        }
-        if self.owner_by_id.get(&token_id).is_some() {
-            env::panic_str("token_id must be unique");
-        }
-
-        let owner_id: AccountId = token_owner_id;
-
-        // Core behavior: every token must have an owner
-        self.owner_by_id.insert(&token_id, &owner_id);
-
-        // Metadata extension: Save metadata, keep variable around to return later.
-        // Note that check above already panicked if metadata extension in use but no metadata
-        // provided to call.
-        self.token_metadata_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.insert(&token_id, token_metadata.as_ref().unwrap()));
+        // Track the storage used by the token to charge for it if refund_id is Some
+        let mut tracker = StorageTracker::default();
+        let token = tracker.track(|| {
+            if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
+                env::panic_str("Must provide metadata");
+            }
+            if self.owner_by_id.get(&token_id).is_some() {
+                env::panic_str("token_id must be unique");
+            }
 
-        // Enumeration extension: Record tokens_per_owner for use with enumeration view methods.
-        if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
-            let mut token_ids = tokens_per_owner.get(&owner_id).unwrap_or_else(|| {
-                UnorderedSet::new(StorageKey::TokensPerOwner {
-                    account_hash: env::sha256(owner_id.as_bytes()),
-                })
-            });
-            token_ids.insert(&token_id);
-            tokens_per_owner.insert(&owner_id, &token_ids);
-        }
+            let owner_id: AccountId = token_owner_id;
+
+            // Core behavior: every token must have an owner
+            self.owner_by_id.insert(&token_id, &owner_id);
+
+            // Metadata extension: Save metadata, keep variable around to return later.
+            // Note that check above already panicked if metadata extension in use but no metadata
+            // provided to call.
+            self.token_metadata_by_id
+                .as_mut()
+                .and_then(|by_id| by_id.insert(&token_id, token_metadata.as_ref().unwrap()));
+
+            // Enumeration extension: Record tokens_per_owner for use with enumeration view methods.
+            if let Some(tokens_per_owner) = &mut self.tokens_per_owner {
+                let mut token_ids = tokens_per_owner.get(&owner_id).unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::TokensPerOwner {
+                        account_hash: env::sha256(owner_id.as_bytes()),
+                    })
+                });
+                token_ids.insert(&token_id);
+                tokens_per_owner.insert(&owner_id, &token_ids);
+            }
 
-        // Approval Management extension: return empty HashMap as part of Token
-        let approved_account_ids =
-            if self.approvals_by_id.is_some() { Some(HashMap::new()) } else { None };
+            // Approval Management extension: return empty HashMap as part of Token
+            let approved_account_ids =
+                if self.approvals_by_id.is_some() { Some(HashMap::new()) } else { None };
 
-        if let Some((id, storage_usage)) = initial_storage_usage {
-            refund_deposit_to_account(env::storage_usage() - storage_usage, id)
-        }
+            Token { token_id, owner_id, metadata: token_metadata, approved_account_ids }
+        });
 
         // Return any extra attached deposit not used for storage
+        if let Some(id) = refund_id {
+            tracker.refund_deposit_to(id);
+        }
 
-        Token { token_id, owner_id, metadata: token_metadata, approved_account_ids }
+        token
     }
 }
 
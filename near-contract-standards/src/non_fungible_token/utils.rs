@@ -26,6 +26,10 @@ pub fn refund_approved_account_ids(
     refund_approved_account_ids_iter(account_id, approved_account_ids.keys())
 }
 
+#[deprecated(
+    since = "4.0.0",
+    note = "Measure the storage with `near_sdk::StorageTracker` and use `refund_deposit_to`"
+)]
 pub fn refund_deposit_to_account(storage_used: u64, account_id: AccountId) {
     let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
     let attached_deposit = env::attached_deposit();
@@ -42,7 +46,12 @@ pub fn refund_deposit_to_account(storage_used: u64, account_id: AccountId) {
 }
 
 /// Assumes that the precedecessor will be refunded
+#[deprecated(
+    since = "4.0.0",
+    note = "Measure the storage with `near_sdk::StorageTracker` and use `refund_deposit`"
+)]
 pub fn refund_deposit(storage_used: u64) {
+    #[allow(deprecated)]
     refund_deposit_to_account(storage_used, env::predecessor_account_id())
 }
 
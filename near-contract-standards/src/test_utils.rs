@@ -0,0 +1,28 @@
+//! Helpers shared by the unit tests of the standards.
+
+use near_sdk::mock::VmAction;
+use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+use near_sdk::{env, testing_env, AccountId, Balance};
+
+/// Sets up the context of a call of `caller` attaching `deposit`, on top of `context`, and keeps
+/// the storage usage of the previous calls so that their storage can be tracked.
+pub(crate) fn set_caller(context: &mut VMContextBuilder, caller: AccountId, deposit: Balance) {
+    testing_env!(context
+        .storage_usage(env::storage_usage())
+        .predecessor_account_id(caller)
+        .attached_deposit(deposit)
+        .build());
+}
+
+/// Returns the amounts transferred to `account_id` since the last [`testing_env!`].
+pub(crate) fn refunds_to(account_id: AccountId) -> Vec<Balance> {
+    get_created_receipts()
+        .into_iter()
+        .filter(|receipt| receipt.receiver_id == account_id)
+        .flat_map(|receipt| receipt.actions)
+        .filter_map(|action| match action {
+            VmAction::Transfer { deposit } => Some(deposit),
+            _ => None,
+        })
+        .collect()
+}
@@ -1,6 +1,6 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::U64;
-use near_sdk::{env, require, AccountId, Duration, Promise, Timestamp};
+use near_sdk::{env, require, AccountId, Duration, Gas, GasWeight, Promise, Timestamp};
 
 type WrappedDuration = U64;
 
@@ -17,11 +17,21 @@ pub trait Upgradable {
     fn stage_code(&mut self, code: Vec<u8>, timestamp: Timestamp);
     fn deploy_code(&mut self) -> Promise;
 
-    /// Implement migration for the next version.
-    /// Should be `unimplemented` for a new contract.
-    /// TODO: consider adding version of the contract stored in the storage?
+    /// Migrates the state to the current version of the contract.
+    ///
+    /// By default, calls the `migrate_state` method that `near_bindgen` generates for contracts
+    /// which implement [`near_sdk::VersionedState`], with the unused gas. It applies the
+    /// migrations declared with `#[migrate(from = N)]` from the version stored in the storage.
+    /// Prefer [`Upgrade::deploy_code_with_migration`] to migrate in the same batch as the
+    /// deployment.
     fn migrate(&mut self) {
-        unimplemented!();
+        Promise::new(env::current_account_id()).function_call_weight(
+            "migrate_state".to_string(),
+            vec![],
+            0,
+            Gas(0),
+            GasWeight(1),
+        );
     }
 }
 
@@ -36,6 +46,13 @@ impl Upgrade {
     pub fn new(owner: AccountId, staging_duration: Duration) -> Self {
         Self { owner, staging_duration, staging_timestamp: 0 }
     }
+
+    /// Deploys the staged code and calls the `migrate_state` method of the new code in the same
+    /// batch, so the code is only deployed if the pending migrations of the
+    /// [`near_sdk::VersionedState`] succeed.
+    pub fn deploy_code_with_migration(&mut self, migrate_gas: Gas) -> Promise {
+        self.deploy_code().function_call("migrate_state".to_string(), vec![], 0, migrate_gas)
+    }
 }
 
 impl Ownable for Upgrade {
@@ -0,0 +1,117 @@
+//! Events of the vesting module, logged when tokens are granted with a vesting schedule or when a
+//! grant is revoked. The tokens moved by the grants are also logged as `ft_transfer` events.
+//!
+//! These events follow the events format (nep-297):
+//! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
+//!
+//! The events are [`VestingGranted`] and [`VestingRevoked`]. They are logged by calling `.emit()`
+//! on them.
+
+use crate::event::NearEvent;
+use crate::vesting::VestingSchedule;
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use serde::Serialize;
+
+/// Data to log when tokens are granted with a vesting schedule. To log this event,
+/// call [`.emit()`](VestingGranted::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct VestingGranted<'a> {
+    pub owner_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+    pub schedule: &'a VestingSchedule,
+    pub revocable: bool,
+}
+
+impl VestingGranted<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_vesting_v1(VestingEventKind::VestingGranted(&[self])).emit()
+    }
+}
+
+/// Data to log when a grant is revoked, with the `amount` of tokens which hadn't vested yet and
+/// were returned to the owner. To log this event, call [`.emit()`](VestingRevoked::emit).
+#[must_use]
+#[derive(Serialize, Debug, Clone)]
+pub struct VestingRevoked<'a> {
+    pub owner_id: &'a AccountId,
+    pub account_id: &'a AccountId,
+    pub amount: &'a U128,
+}
+
+impl VestingRevoked<'_> {
+    /// Logs the event to the host. This is required to ensure that the event is triggered
+    /// and to consume the event.
+    pub fn emit(self) {
+        new_vesting_v1(VestingEventKind::VestingRevoked(&[self])).emit()
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct VestingEvent<'a> {
+    version: &'static str,
+    #[serde(flatten)]
+    event_kind: VestingEventKind<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+enum VestingEventKind<'a> {
+    VestingGranted(&'a [VestingGranted<'a>]),
+    VestingRevoked(&'a [VestingRevoked<'a>]),
+}
+
+fn new_vesting<'a>(version: &'static str, event_kind: VestingEventKind<'a>) -> NearEvent<'a> {
+    NearEvent::Vesting(VestingEvent { version, event_kind })
+}
+
+fn new_vesting_v1(event_kind: VestingEventKind) -> NearEvent {
+    new_vesting("1.0.0", event_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U64;
+    use near_sdk::{test_utils, AccountId};
+
+    fn bob() -> AccountId {
+        AccountId::new_unchecked("bob".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice".to_string())
+    }
+
+    #[test]
+    fn vesting_granted() {
+        let schedule = VestingSchedule::Linear { start: U64(10), cliff: U64(20), end: U64(30) };
+        VestingGranted {
+            owner_id: &bob(),
+            account_id: &alice(),
+            amount: &U128(100),
+            schedule: &schedule,
+            revocable: true,
+        }
+        .emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"vesting","version":"1.0.0","event":"vesting_granted","data":[{"owner_id":"bob","account_id":"alice","amount":"100","schedule":{"type":"linear","start":"10","cliff":"20","end":"30"},"revocable":true}]}"#
+        );
+    }
+
+    #[test]
+    fn vesting_revoked() {
+        VestingRevoked { owner_id: &bob(), account_id: &alice(), amount: &U128(40) }.emit();
+        assert_eq!(
+            test_utils::get_logs()[0],
+            r#"EVENT_JSON:{"standard":"vesting","version":"1.0.0","event":"vesting_revoked","data":[{"owner_id":"bob","account_id":"alice","amount":"40"}]}"#
+        );
+    }
+}
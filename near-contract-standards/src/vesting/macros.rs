@@ -0,0 +1,47 @@
+/// The vesting extension of a fungible token, see
+/// [`FungibleTokenVesting`](crate::vesting::FungibleTokenVesting). The token must have its
+/// [`vesting`](crate::fungible_token::FungibleToken::vesting) field set, so that its methods can't
+/// withdraw the tokens which haven't vested yet, and the other extensions are implemented as
+/// usual, for example with `impl_fungible_token_core`.
+/// Takes name of the Contract struct and the inner field for the token.
+#[macro_export]
+macro_rules! impl_fungible_token_vesting {
+    ($contract: ident, $token: ident) => {
+        use $crate::vesting::{FungibleTokenVesting, VestingGrant, VestingSchedule};
+
+        #[near_bindgen]
+        impl FungibleTokenVesting for $contract {
+            #[payable]
+            fn ft_vesting_grant(
+                &mut self,
+                account_id: AccountId,
+                amount: U128,
+                schedule: VestingSchedule,
+                revocable: bool,
+            ) {
+                self.$token.ft_vesting_grant(account_id, amount, schedule, revocable)
+            }
+
+            #[payable]
+            fn ft_vesting_revoke(&mut self, account_id: AccountId) -> U128 {
+                self.$token.ft_vesting_revoke(account_id)
+            }
+
+            fn ft_vesting_release(&mut self, account_id: AccountId) {
+                self.$token.ft_vesting_release(account_id)
+            }
+
+            fn ft_vesting_of(&self, account_id: AccountId) -> Option<VestingGrant> {
+                self.$token.ft_vesting_of(account_id)
+            }
+
+            fn ft_locked_balance_of(&self, account_id: AccountId) -> U128 {
+                self.$token.ft_locked_balance_of(account_id)
+            }
+
+            fn ft_vesting_storage_cost(&self) -> U128 {
+                self.$token.ft_vesting_storage_cost()
+            }
+        }
+    };
+}
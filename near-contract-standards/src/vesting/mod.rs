@@ -0,0 +1,66 @@
+pub mod events;
+mod macros;
+mod schedule;
+mod vesting_impl;
+
+pub use schedule::*;
+pub use vesting_impl::*;
+
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+
+/// Optional extension of a fungible token granting tokens which vest over time, for example to
+/// the team or the investors of a token launch. The tokens of a grant are transferred to the
+/// balance of its account, which can only transfer the tokens vested according to the
+/// [`VestingSchedule`] of the grant at the current block timestamp.
+///
+/// Each account can have a single grant, which requires the owner of the grant to attach a
+/// storage deposit. The storage deposit is refunded to the owner when the grant is revoked, or
+/// when the grant is released after it has fully vested. Granting and revoking log a
+/// `vesting_granted` and a `vesting_revoked` event, along with the `ft_transfer` events of the
+/// tokens.
+pub trait FungibleTokenVesting {
+    /// Transfers positive `amount` of tokens of the `env::predecessor_account_id` to
+    /// `account_id`, which vest according to `schedule`.
+    ///
+    /// Requirements:
+    /// * Caller must attach the deposit returned by `ft_vesting_storage_cost`. The remaining
+    ///   deposit is refunded.
+    /// * `account_id` must be registered with the contract and must not have a grant already.
+    /// * The tokens of the caller which haven't vested yet can't be granted.
+    ///
+    /// Arguments:
+    /// - `account_id` - the account ID receiving the tokens.
+    /// - `amount` - the amount of tokens granted, in a decimal string representation.
+    /// - `schedule` - the schedule of the vesting, such as
+    ///   `{"type": "linear", "start": "1700000000000000000", "cliff": "1700000000000000000", "end": "1800000000000000000"}`.
+    /// - `revocable` - whether the caller can revoke the grant with `ft_vesting_revoke`.
+    fn ft_vesting_grant(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        schedule: VestingSchedule,
+        revocable: bool,
+    );
+
+    /// Revokes the revocable grant of `account_id` made by the `env::predecessor_account_id`,
+    /// transferring the tokens which haven't vested yet back to the caller, and refunding the
+    /// storage deposit of the grant. Exactly 1 yoctoNEAR must be attached.
+    ///
+    /// Returns the amount of tokens transferred back to the caller.
+    fn ft_vesting_revoke(&mut self, account_id: AccountId) -> U128;
+
+    /// Removes the grant of `account_id` once it has fully vested, refunding its storage deposit to
+    /// the owner of the grant. Can be called by any account.
+    fn ft_vesting_release(&mut self, account_id: AccountId);
+
+    /// Returns the grant of `account_id`, if any.
+    fn ft_vesting_of(&self, account_id: AccountId) -> Option<VestingGrant>;
+
+    /// Returns the amount of tokens of `account_id` which haven't vested yet and can't be
+    /// transferred.
+    fn ft_locked_balance_of(&self, account_id: AccountId) -> U128;
+
+    /// Returns the storage deposit to attach to `ft_vesting_grant`.
+    fn ft_vesting_storage_cost(&self) -> U128;
+}
@@ -0,0 +1,191 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{require, AccountId, Balance, Timestamp};
+
+/// Schedule of the vesting of tokens, with timestamps in nanoseconds as returned by
+/// [`env::block_timestamp`](near_sdk::env::block_timestamp).
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VestingSchedule {
+    /// All the tokens vest at `timestamp`.
+    Cliff { timestamp: U64 },
+    /// The tokens vest linearly from `start` to `end`, but none of them vest before `cliff`.
+    Linear { start: U64, cliff: U64, end: U64 },
+    /// The tokens vest in `steps` equal installments, one every `period` nanoseconds after
+    /// `start`, but none of them vest before `cliff`.
+    Step { start: U64, cliff: U64, period: U64, steps: u32 },
+}
+
+impl VestingSchedule {
+    /// Panics if the timestamps of the schedule are out of order or the schedule has no duration.
+    pub fn assert_valid(&self) {
+        match self {
+            Self::Cliff { .. } => {}
+            Self::Linear { start, cliff, end } => {
+                require!(start.0 < end.0, "The vesting should end after its start");
+                require!(
+                    start.0 <= cliff.0 && cliff.0 <= end.0,
+                    "The cliff should be between the start and the end of the vesting"
+                );
+            }
+            Self::Step { start, cliff, period, steps } => {
+                require!(period.0 > 0 && *steps > 0, "The vesting should have at least one step");
+                let end = period
+                    .0
+                    .checked_mul(u64::from(*steps))
+                    .and_then(|duration| duration.checked_add(start.0));
+                require!(end.is_some(), "The end of the vesting overflows");
+                require!(start.0 <= cliff.0, "The cliff should be after the start of the vesting");
+            }
+        }
+    }
+
+    /// Returns the amount of `total_amount` vested at `timestamp`.
+    pub fn vested_amount(&self, total_amount: Balance, timestamp: Timestamp) -> Balance {
+        match self {
+            Self::Cliff { timestamp: cliff } => {
+                if timestamp < cliff.0 {
+                    0
+                } else {
+                    total_amount
+                }
+            }
+            Self::Linear { start, cliff, end } => {
+                if timestamp < cliff.0 || timestamp <= start.0 {
+                    0
+                } else if timestamp >= end.0 {
+                    total_amount
+                } else {
+                    mul_div(total_amount, timestamp - start.0, end.0 - start.0)
+                }
+            }
+            Self::Step { start, cliff, period, steps } => {
+                if timestamp < cliff.0 || timestamp <= start.0 {
+                    return 0;
+                }
+                let steps = u64::from(*steps);
+                let vested_steps = std::cmp::min((timestamp - start.0) / period.0, steps);
+                mul_div(total_amount, vested_steps, steps)
+            }
+        }
+    }
+}
+
+/// Returns `amount * numerator / denominator` rounded down, for `numerator <= denominator`,
+/// without overflowing.
+fn mul_div(amount: Balance, numerator: u64, denominator: u64) -> Balance {
+    let (numerator, denominator) = (Balance::from(numerator), Balance::from(denominator));
+    // The remainder is lower than the denominator, so multiplying it by a `u64` can't overflow.
+    amount / denominator * numerator + amount % denominator * numerator / denominator
+}
+
+/// Tokens of an account vesting according to a schedule. The tokens are in the balance of the
+/// account, which can't transfer the tokens which haven't vested yet.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingGrant {
+    /// The account which granted the tokens, which can revoke the grant if it is revocable.
+    pub owner_id: AccountId,
+    /// The amount of tokens granted.
+    pub amount: U128,
+    pub schedule: VestingSchedule,
+    /// Whether the owner can take back the tokens which haven't vested yet.
+    pub revocable: bool,
+}
+
+impl VestingGrant {
+    /// Returns the amount of tokens vested at `timestamp`.
+    pub fn vested_amount(&self, timestamp: Timestamp) -> Balance {
+        self.schedule.vested_amount(self.amount.0, timestamp)
+    }
+
+    /// Returns the amount of tokens which haven't vested yet at `timestamp`.
+    pub fn locked_amount(&self, timestamp: Timestamp) -> Balance {
+        self.amount.0 - self.vested_amount(timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cliff_schedule() {
+        let schedule = VestingSchedule::Cliff { timestamp: U64(100) };
+        assert_eq!(schedule.vested_amount(1000, 99), 0);
+        assert_eq!(schedule.vested_amount(1000, 100), 1000);
+    }
+
+    #[test]
+    fn linear_schedule() {
+        let schedule = VestingSchedule::Linear { start: U64(100), cliff: U64(150), end: U64(300) };
+        schedule.assert_valid();
+        assert_eq!(schedule.vested_amount(1000, 0), 0);
+        assert_eq!(schedule.vested_amount(1000, 149), 0);
+        assert_eq!(schedule.vested_amount(1000, 150), 250);
+        assert_eq!(schedule.vested_amount(1000, 299), 995);
+        assert_eq!(schedule.vested_amount(1000, 300), 1000);
+        assert_eq!(schedule.vested_amount(1000, u64::MAX), 1000);
+    }
+
+    #[test]
+    fn linear_schedule_large_amounts() {
+        let schedule = VestingSchedule::Linear { start: U64(0), cliff: U64(0), end: U64(7) };
+        assert_eq!(schedule.vested_amount(u128::MAX, 2), u128::MAX / 7 * 2);
+        assert_eq!(schedule.vested_amount(u128::MAX, 3), u128::MAX / 7 * 3 + 1);
+        let schedule = VestingSchedule::Linear { start: U64(0), cliff: U64(0), end: U64(u64::MAX) };
+        assert_eq!(
+            schedule.vested_amount(u128::MAX, u64::MAX - 1),
+            u128::MAX - u128::MAX / u128::from(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn step_schedule() {
+        let schedule =
+            VestingSchedule::Step { start: U64(100), cliff: U64(200), period: U64(50), steps: 4 };
+        schedule.assert_valid();
+        assert_eq!(schedule.vested_amount(1000, 199), 0);
+        assert_eq!(schedule.vested_amount(1000, 200), 500);
+        assert_eq!(schedule.vested_amount(1000, 249), 500);
+        assert_eq!(schedule.vested_amount(1000, 250), 750);
+        assert_eq!(schedule.vested_amount(1000, 300), 1000);
+        assert_eq!(schedule.vested_amount(1000, 1000), 1000);
+
+        let schedule =
+            VestingSchedule::Step { start: U64(0), cliff: U64(0), period: U64(1), steps: 3 };
+        assert_eq!(schedule.vested_amount(10, 1), 3);
+        assert_eq!(schedule.vested_amount(10, 2), 6);
+        assert_eq!(schedule.vested_amount(10, 3), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "The cliff should be between the start and the end of the vesting")]
+    fn linear_schedule_cliff_after_end() {
+        VestingSchedule::Linear { start: U64(100), cliff: U64(400), end: U64(300) }.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "The end of the vesting overflows")]
+    fn step_schedule_overflow() {
+        VestingSchedule::Step { start: U64(100), cliff: U64(100), period: U64(u64::MAX), steps: 2 }
+            .assert_valid();
+    }
+
+    #[test]
+    fn grant_json() {
+        let grant = VestingGrant {
+            owner_id: "alice".parse().unwrap(),
+            amount: U128(1000),
+            schedule: VestingSchedule::Cliff { timestamp: U64(100) },
+            revocable: true,
+        };
+        let json = r#"{"owner_id":"alice","amount":"1000","schedule":{"type":"cliff","timestamp":"100"},"revocable":true}"#;
+        assert_eq!(near_sdk::serde_json::to_string(&grant).unwrap(), json);
+        assert_eq!(near_sdk::serde_json::from_str::<VestingGrant>(json).unwrap(), grant);
+        assert_eq!(grant.locked_amount(99), 1000);
+        assert_eq!(grant.locked_amount(100), 0);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::fungible_token::FungibleToken;
+use crate::vesting::events::{VestingGranted, VestingRevoked};
+use crate::vesting::{FungibleTokenVesting, VestingGrant, VestingSchedule};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{
+    assert_one_yocto, env, require, AccountId, Balance, IntoStorageKey, StorageTracker,
+    StorageUsage,
+};
+
+/// The grants of the vesting extension of a [`FungibleToken`], see [`FungibleTokenVesting`].
+///
+/// The tokens of a grant are in the balance of its account. The grants are stored in the
+/// [`FungibleToken::vesting`] field, so that [`FungibleToken::internal_withdraw`] checks the grant
+/// of the account and none of the methods of the token can withdraw the tokens which haven't
+/// vested yet.
+///
+/// For example usage, see the `impl_fungible_token_vesting` macro.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct VestingGrants {
+    /// Account ID -> Grant of the tokens vesting for the account.
+    pub grants: LookupMap<AccountId, VestingGrant>,
+
+    /// The storage size in bytes for one grant.
+    pub grant_storage_usage: StorageUsage,
+}
+
+impl VestingGrants {
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut this = Self { grants: LookupMap::new(prefix), grant_storage_usage: 0 };
+        this.measure_grant_storage_usage();
+        this
+    }
+
+    fn measure_grant_storage_usage(&mut self) {
+        let tmp_account_id = AccountId::new_unchecked("a".repeat(64));
+        // The largest schedule, so that the storage of any grant is covered.
+        let grant = VestingGrant {
+            owner_id: tmp_account_id.clone(),
+            amount: U128(0),
+            schedule: VestingSchedule::Step {
+                start: U64(0),
+                cliff: U64(0),
+                period: U64(0),
+                steps: 0,
+            },
+            revocable: false,
+        };
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| self.grants.insert(&tmp_account_id, &grant));
+        self.grant_storage_usage = tracker.bytes_added();
+        self.grants.remove(&tmp_account_id);
+    }
+
+    /// Returns the storage deposit required to add a new grant.
+    pub fn grant_storage_cost(&self) -> Balance {
+        Balance::from(self.grant_storage_usage) * env::storage_byte_cost()
+    }
+
+    /// Returns the amount of tokens of `account_id` which haven't vested yet.
+    pub fn internal_locked_balance_of(&self, account_id: &AccountId) -> Balance {
+        self.grants.get(account_id).map_or(0, |grant| grant.locked_amount(env::block_timestamp()))
+    }
+
+    /// Panics if `account_id` has more tokens which haven't vested yet than `new_balance`, its
+    /// balance after a withdrawal.
+    pub fn assert_vested_balance(&self, account_id: &AccountId, new_balance: Balance) {
+        require!(
+            new_balance >= self.internal_locked_balance_of(account_id),
+            "The account doesn't have enough vested balance"
+        );
+    }
+}
+
+impl FungibleToken {
+    fn vesting_grants(&self) -> &VestingGrants {
+        self.vesting.as_ref().unwrap_or_else(|| env::panic_str("FT does not support Vesting"))
+    }
+
+    fn vesting_grants_mut(&mut self) -> &mut VestingGrants {
+        self.vesting.as_mut().unwrap_or_else(|| env::panic_str("FT does not support Vesting"))
+    }
+
+    /// Transfers `amount` of the tokens of `owner_id` to `account_id`, vesting according to
+    /// `schedule`. Requires the attached deposit to cover the storage of the grant, and refunds
+    /// the rest of it to `owner_id`.
+    pub fn internal_vesting_grant(
+        &mut self,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+        amount: Balance,
+        schedule: VestingSchedule,
+        revocable: bool,
+    ) {
+        require!(owner_id != account_id, "The owner and the account should be different");
+        require!(amount > 0, "The amount should be a positive number");
+        schedule.assert_valid();
+        require!(
+            !self.vesting_grants().grants.contains_key(account_id),
+            "The account already has a grant"
+        );
+
+        self.internal_transfer(owner_id, account_id, amount, Some("vesting".to_string()));
+        let grant =
+            VestingGrant { owner_id: owner_id.clone(), amount: U128(amount), schedule, revocable };
+        let grants = &mut self.vesting_grants_mut().grants;
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| grants.insert(account_id, &grant));
+        tracker.refund_deposit_to(owner_id.clone());
+        VestingGranted {
+            owner_id,
+            account_id,
+            amount: &grant.amount,
+            schedule: &grant.schedule,
+            revocable,
+        }
+        .emit();
+    }
+
+    /// Revokes the grant of `account_id` on behalf of `owner_id`, transferring the tokens which
+    /// haven't vested yet back to `owner_id` and refunding the storage deposit of the grant, along
+    /// with the attached deposit. Returns the amount of tokens transferred back.
+    pub fn internal_vesting_revoke(
+        &mut self,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) -> Balance {
+        let grants = &mut self.vesting_grants_mut().grants;
+        let grant = grants
+            .get(account_id)
+            .unwrap_or_else(|| env::panic_str("The account doesn't have a grant"));
+        require!(&grant.owner_id == owner_id, "Only the owner of the grant can revoke it");
+        require!(grant.revocable, "The grant is not revocable");
+
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| grants.remove(account_id));
+        // The refunds of the tokens unused by the receivers of transfer calls aren't checked, so
+        // the locked tokens may have left the balance.
+        let amount = std::cmp::min(
+            grant.locked_amount(env::block_timestamp()),
+            self.internal_unwrap_balance_of(account_id),
+        );
+        if amount > 0 {
+            self.internal_transfer(
+                account_id,
+                owner_id,
+                amount,
+                Some("vesting revoked".to_string()),
+            );
+        }
+        tracker.refund_released_deposit_to(owner_id.clone());
+        tracker.refund_deposit_to(owner_id.clone());
+        VestingRevoked { owner_id, account_id, amount: &U128(amount) }.emit();
+        amount
+    }
+
+    /// Removes the grant of `account_id` once it has fully vested, refunding its storage deposit
+    /// to the owner of the grant.
+    pub fn internal_vesting_release(&mut self, account_id: &AccountId) {
+        let grants = &mut self.vesting_grants_mut().grants;
+        let grant = grants
+            .get(account_id)
+            .unwrap_or_else(|| env::panic_str("The account doesn't have a grant"));
+        require!(
+            grant.locked_amount(env::block_timestamp()) == 0,
+            "The grant hasn't fully vested yet"
+        );
+
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| grants.remove(account_id));
+        tracker.refund_released_deposit_to(grant.owner_id);
+    }
+}
+
+impl FungibleTokenVesting for FungibleToken {
+    fn ft_vesting_grant(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        schedule: VestingSchedule,
+        revocable: bool,
+    ) {
+        let owner_id = env::predecessor_account_id();
+        self.internal_vesting_grant(&owner_id, &account_id, amount.into(), schedule, revocable);
+    }
+
+    fn ft_vesting_revoke(&mut self, account_id: AccountId) -> U128 {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        self.internal_vesting_revoke(&owner_id, &account_id).into()
+    }
+
+    fn ft_vesting_release(&mut self, account_id: AccountId) {
+        self.internal_vesting_release(&account_id);
+    }
+
+    fn ft_vesting_of(&self, account_id: AccountId) -> Option<VestingGrant> {
+        self.vesting_grants().grants.get(&account_id)
+    }
+
+    fn ft_locked_balance_of(&self, account_id: AccountId) -> U128 {
+        self.vesting_grants().internal_locked_balance_of(&account_id).into()
+    }
+
+    fn ft_vesting_storage_cost(&self) -> U128 {
+        self.vesting_grants().grant_storage_cost().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungible_token::allowance::FungibleTokenAllowances;
+    use crate::fungible_token::batch::FungibleTokenBatchTransfer;
+    use crate::fungible_token::core::FungibleTokenCore;
+    use crate::storage_management::StorageManagement;
+    use crate::test_utils::{refunds_to, set_caller};
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    /// Registers `accounts(0)` to `accounts(3)` and mints 1000 tokens to `accounts(0)`, which
+    /// grants 100 of them to `accounts(1)`, vesting linearly from 0 to 1000.
+    fn setup(context: &mut VMContextBuilder, revocable: bool) -> FungibleToken {
+        testing_env!(context.build());
+        let mut token = FungibleToken::new(b"t");
+        token.vesting = Some(VestingGrants::new(b"v"));
+        for index in 0..4 {
+            token.internal_register_account(&accounts(index));
+        }
+        token.internal_deposit(&accounts(0), 1000);
+
+        set_caller(context, accounts(0), token.ft_vesting_storage_cost().0);
+        let schedule = VestingSchedule::Linear { start: U64(0), cliff: U64(0), end: U64(1000) };
+        token.ft_vesting_grant(accounts(1), U128(100), schedule, revocable);
+        token
+    }
+
+    #[test]
+    fn grant() {
+        let mut context = VMContextBuilder::new();
+        let token = setup(&mut context, true);
+        assert_eq!(token.ft_balance_of(accounts(0)), U128(900));
+        assert_eq!(token.ft_balance_of(accounts(1)), U128(100));
+        assert_eq!(token.ft_locked_balance_of(accounts(1)), U128(100));
+        assert_eq!(token.ft_vesting_of(accounts(1)).unwrap().owner_id, accounts(0));
+
+        // The storage cost is estimated for the longest account IDs and schedules.
+        let cost = token.ft_vesting_storage_cost().0;
+        assert!(matches!(refunds_to(accounts(0)).as_slice(), [refund] if *refund < cost));
+
+        set_caller(context.block_timestamp(500), accounts(0), 0);
+        assert_eq!(token.ft_locked_balance_of(accounts(1)), U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn grant_without_storage_deposit() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(&mut context, accounts(0), 1);
+        let schedule = VestingSchedule::Cliff { timestamp: U64(1000) };
+        token.ft_vesting_grant(accounts(2), U128(100), schedule, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account already has a grant")]
+    fn grant_twice() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(&mut context, accounts(0), token.ft_vesting_storage_cost().0);
+        let schedule = VestingSchedule::Cliff { timestamp: U64(1000) };
+        token.ft_vesting_grant(accounts(1), U128(100), schedule, false);
+    }
+
+    #[test]
+    fn transfer_vested_tokens() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(500), accounts(1), 1);
+        token.ft_transfer(accounts(2), U128(50), None);
+        assert_eq!(token.ft_balance_of(accounts(1)), U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough vested balance")]
+    fn transfer_locked_tokens() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(500), accounts(1), 1);
+        token.ft_transfer(accounts(2), U128(51), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough vested balance")]
+    fn batch_transfer_locked_tokens() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(500), accounts(1), 1);
+        token.ft_batch_transfer(vec![accounts(2), accounts(3)], vec![U128(30), U128(30)], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough vested balance")]
+    fn transfer_from_locked_tokens() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        let mut allowances = FungibleTokenAllowances::new(b"a");
+        set_caller(context.block_timestamp(500), accounts(1), allowances.allowance_storage_cost());
+        allowances.ft_approve(accounts(2), U128(100));
+
+        set_caller(&mut context, accounts(2), 1);
+        allowances.ft_transfer_from(&mut token, accounts(1), accounts(3), U128(60), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account doesn't have enough vested balance")]
+    fn force_unregister_with_locked_tokens() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(500), accounts(1), 1);
+        token.storage_unregister(Some(true));
+    }
+
+    #[test]
+    fn release_fully_vested_grant() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        let charged = token.ft_vesting_storage_cost().0 - refunds_to(accounts(0))[0];
+
+        // Transfers only check the grant, which stays until it is released.
+        set_caller(context.block_timestamp(1000), accounts(1), 1);
+        token.ft_transfer(accounts(2), U128(100), None);
+        assert_eq!(token.ft_balance_of(accounts(1)), U128(0));
+        assert!(token.ft_vesting_of(accounts(1)).is_some());
+        assert!(refunds_to(accounts(0)).is_empty());
+
+        // Any account can release the grant, refunding its storage to its owner.
+        set_caller(&mut context, accounts(2), 0);
+        token.ft_vesting_release(accounts(1));
+        assert_eq!(token.ft_vesting_of(accounts(1)), None);
+        assert_eq!(refunds_to(accounts(0)), vec![charged]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The grant hasn't fully vested yet")]
+    fn release_locked_grant() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(999), accounts(2), 0);
+        token.ft_vesting_release(accounts(1));
+    }
+
+    #[test]
+    fn transfer_without_vesting() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.build());
+        let mut token = FungibleToken::new(b"t");
+        token.internal_register_account(&accounts(0));
+        token.internal_register_account(&accounts(1));
+        token.internal_deposit(&accounts(0), 100);
+
+        set_caller(&mut context, accounts(0), 1);
+        token.ft_transfer(accounts(1), U128(100), None);
+        assert_eq!(token.ft_balance_of(accounts(1)), U128(100));
+    }
+
+    #[test]
+    fn revoke() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        let charged = token.ft_vesting_storage_cost().0 - refunds_to(accounts(0))[0];
+
+        set_caller(context.block_timestamp(500), accounts(0), 1);
+        assert_eq!(token.ft_vesting_revoke(accounts(1)), U128(50));
+        assert_eq!(token.ft_balance_of(accounts(0)), U128(950));
+        assert_eq!(token.ft_balance_of(accounts(1)), U128(50));
+        assert_eq!(token.ft_vesting_of(accounts(1)), None);
+        assert_eq!(refunds_to(accounts(0)), vec![charged]);
+
+        // The remaining tokens can be transferred.
+        set_caller(&mut context, accounts(1), 1);
+        token.ft_transfer(accounts(2), U128(50), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The grant is not revocable")]
+    fn revoke_irrevocable_grant() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, false);
+        set_caller(context.block_timestamp(500), accounts(0), 1);
+        token.ft_vesting_revoke(accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of the grant can revoke it")]
+    fn revoke_by_other_account() {
+        let mut context = VMContextBuilder::new();
+        let mut token = setup(&mut context, true);
+        set_caller(context.block_timestamp(500), accounts(2), 1);
+        token.ft_vesting_revoke(accounts(1));
+    }
+}
@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream as TokenStream2;
 
 use crate::core_impl::info_extractor::{
-    ArgInfo, AttrSigInfo, BindgenArgType, InputStructType, SerializerType,
+    join_ok_types, ArgInfo, AttrSigInfo, BindgenArgType, InputStructType, SerializerType,
 };
 use crate::core_impl::utils;
 use quote::quote;
@@ -251,17 +251,26 @@ impl AttrSigInfo {
 
     /// Create code that deserializes arguments that were decorated with `#[callback*]`
     pub fn callback_deserialization(&self) -> TokenStream2 {
+        // Index of the next promise result, `#[callback_join]` arguments read several of them.
+        let mut next_idx = 0u64;
         self.args
             .iter()
             .filter(|arg| {
                 matches!(
                     arg.bindgen_ty,
-                    BindgenArgType::CallbackArg | BindgenArgType::CallbackResultArg
+                    BindgenArgType::CallbackArg
+                        | BindgenArgType::CallbackResultArg
+                        | BindgenArgType::CallbackJoinArg
                 )
             })
-            .enumerate()
-            .fold(TokenStream2::new(), |acc, (idx, arg)| {
-                let idx = idx as u64;
+            .fold(TokenStream2::new(), |acc, arg| {
+                let idx = next_idx;
+                next_idx += match arg.bindgen_ty {
+                    BindgenArgType::CallbackJoinArg => {
+                        join_ok_types(&arg.ty).map_or(1, |ok_types| ok_types.len() as u64)
+                    }
+                    _ => 1,
+                };
                 let ArgInfo { mutability, ident, ty, bindgen_ty, serializer_ty, .. } = arg;
                 match &bindgen_ty {
                     BindgenArgType::CallbackArg => {
@@ -321,6 +330,23 @@ impl AttrSigInfo {
                             let #mutability #ident: #ty = #result;
                         }
                     }
+                    BindgenArgType::CallbackJoinArg => {
+                        let ok_types = join_ok_types(ty)
+                            .expect("#[callback_join] type is checked when extracting the argument");
+                        let read_result = match serializer_ty {
+                            SerializerType::JSON => quote! { near_sdk::__private::promise_result_json },
+                            SerializerType::Borsh => quote! { near_sdk::__private::promise_result_borsh },
+                        };
+                        let results = ok_types.iter().zip(idx..).map(|(ok_type, idx)| {
+                            quote! {
+                                #read_result::<#ok_type>(#idx)
+                            }
+                        });
+                        quote! {
+                            #acc
+                            let #mutability #ident: #ty = (#(#results,)*);
+                        }
+                    }
                     _ => unreachable!()
                 }
             })
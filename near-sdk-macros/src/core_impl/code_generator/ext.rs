@@ -0,0 +1,125 @@
+use crate::core_impl::info_extractor::{ArgInfo, AttrSigInfo};
+use crate::core_impl::TraitItemMethodInfo;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Generics, Ident, LitStr};
+
+/// Generate the builder struct that is used to make calls to a contract, along with the `ext`
+/// function creating it. When `generics` is provided, the `ext` function is an associated
+/// function of `ident`, otherwise it is a free function.
+///
+/// # Example
+/// ```ignore
+/// #[must_use]
+/// pub struct ContractExt {
+///     pub(crate) account_id: near_sdk::AccountId,
+///     pub(crate) deposit: near_sdk::Balance,
+///     pub(crate) static_gas: near_sdk::Gas,
+///     pub(crate) gas_weight: near_sdk::GasWeight,
+/// }
+/// impl ContractExt {
+///     pub fn with_attached_deposit(mut self, amount: near_sdk::Balance) -> Self { .. }
+///     pub fn with_static_gas(mut self, static_gas: near_sdk::Gas) -> Self { .. }
+///     pub fn with_unused_gas_weight(mut self, gas_weight: u64) -> Self { .. }
+/// }
+/// impl Contract {
+///     pub fn ext(account_id: near_sdk::AccountId) -> ContractExt { .. }
+/// }
+/// ```
+pub fn generate_ext_structs(ident: &Ident, generics: Option<&Generics>) -> TokenStream2 {
+    let name = format_ident!("{}Ext", ident);
+    let doc = LitStr::new(
+        &format!("API for calling the methods of `{}` through cross-contract calls.", ident),
+        Span::call_site(),
+    );
+    let ext_fn = quote! {
+        /// Creates a builder of cross-contract calls to the contract deployed on `account_id`.
+        /// Unless configured otherwise, calls attach no deposit, no static gas and the default
+        /// weight of the unused gas.
+        pub fn ext(account_id: near_sdk::AccountId) -> #name {
+            #name {
+                account_id,
+                deposit: 0,
+                static_gas: near_sdk::Gas(0),
+                gas_weight: near_sdk::GasWeight::default(),
+            }
+        }
+    };
+    let ext_fn = match generics {
+        Some(generics) => {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics #ident #ty_generics #where_clause {
+                    #ext_fn
+                }
+            }
+        }
+        None => ext_fn,
+    };
+    quote! {
+        #[doc = #doc]
+        #[must_use]
+        pub struct #name {
+            pub(crate) account_id: near_sdk::AccountId,
+            pub(crate) deposit: near_sdk::Balance,
+            pub(crate) static_gas: near_sdk::Gas,
+            pub(crate) gas_weight: near_sdk::GasWeight,
+        }
+
+        impl #name {
+            /// Amount of yoctoNEAR to attach to the call.
+            pub fn with_attached_deposit(mut self, amount: near_sdk::Balance) -> Self {
+                self.deposit = amount;
+                self
+            }
+
+            /// Gas reserved for the call, on top of its share of the unused gas.
+            pub fn with_static_gas(mut self, static_gas: near_sdk::Gas) -> Self {
+                self.static_gas = static_gas;
+                self
+            }
+
+            /// Weight of the unused gas assigned to the call, relative to the other calls
+            /// scheduled by the current execution. A weight of `0` limits the call to its static
+            /// gas.
+            pub fn with_unused_gas_weight(mut self, gas_weight: u64) -> Self {
+                self.gas_weight = near_sdk::GasWeight(gas_weight);
+                self
+            }
+        }
+
+        #ext_fn
+    }
+}
+
+/// Generate a method of the builder struct that schedules a call of the contract method described
+/// by `attr_sig_info` and returns the resulting `Promise`.
+pub fn generate_ext_function(attr_sig_info: &AttrSigInfo) -> TokenStream2 {
+    let ident = &attr_sig_info.ident;
+    let ident_str = ident.to_string();
+    let mut args = TokenStream2::new();
+    for arg in attr_sig_info.input_args() {
+        let ArgInfo { ident, ty, .. } = arg;
+        args.extend(quote! { #ident: #ty, });
+    }
+    // Methods can be conditionally compiled, so the builder method should be as well.
+    let attrs = attr_sig_info
+        .non_bindgen_attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg") || attr.path.is_ident("doc"));
+    let serialize =
+        TraitItemMethodInfo::generate_serialier(attr_sig_info, &attr_sig_info.input_serializer);
+    quote! {
+        #(#attrs)*
+        pub fn #ident(self, #args) -> near_sdk::Promise {
+            #serialize
+            near_sdk::Promise::new(self.account_id).function_call_weight(
+                #ident_str.to_string(),
+                args,
+                self.deposit,
+                self.static_gas,
+                self.gas_weight,
+            )
+        }
+    }
+}
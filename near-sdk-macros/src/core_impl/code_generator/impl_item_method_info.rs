@@ -1,5 +1,5 @@
 use crate::core_impl::info_extractor::{
-    AttrSigInfo, ImplItemMethodInfo, InputStructType, MethodType, SerializerType,
+    AttrSigInfo, ImplItemMethodInfo, InputStructType, MethodType, OnlyAttr, SerializerType,
 };
 use crate::core_impl::utils;
 use proc_macro2::TokenStream as TokenStream2;
@@ -10,9 +10,11 @@ use syn::{ReturnType, Signature};
 impl ImplItemMethodInfo {
     /// Generate wrapper method for the given method of the contract.
     pub fn method_wrapper(&self) -> TokenStream2 {
-        let ImplItemMethodInfo { attr_signature_info, struct_type, .. } = self;
-        // Args provided by `env::input()`.
-        let has_input_args = attr_signature_info.input_args().next().is_some();
+        let ImplItemMethodInfo { attr_signature_info, .. } = self;
+        let statements = match self.wrapper_statements() {
+            Ok(statements) => statements,
+            Err(err) => return err.to_compile_error(),
+        };
 
         let panic_hook = quote! {
             near_sdk::env::setup_panic_hook();
@@ -23,6 +25,84 @@ impl ImplItemMethodInfo {
         let input_struct2 =
             attr_signature_info.input_struct2(&self.struct_type, &attr_signature_info.method_type);
 
+        let AttrSigInfo { non_bindgen_attrs, ident, method_type, .. } = attr_signature_info;
+
+        let output_type = match &self.attr_signature_info.returns {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_token, type_) => {
+                if matches!(method_type, &MethodType::Init) {
+                    quote!(())
+                } else {
+                    quote!(#type_)
+                }
+            }
+        };
+        let ident_str = ident.to_string();
+        let method = quote! {
+            pub const NAME: &'static str = #ident_str;
+            pub type Output = #output_type;
+            impl near_sdk::utils::Method for Input {
+                const NAME: &'static str = NAME;
+                type Input = Self;
+                type Output = Output;
+            }
+        };
+
+        let non_bindgen_attrs = non_bindgen_attrs.iter().fold(TokenStream2::new(), |acc, value| {
+            quote! {
+                #acc
+                #value
+            }
+        });
+        quote! {
+            #non_bindgen_attrs
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn #ident() {
+                use #ident::Input;
+
+                #panic_hook
+                #statements
+            }
+            pub mod #ident {
+                use super::*;
+
+                #input_struct
+                #input_struct2
+                #method
+            }
+        }
+    }
+
+    /// Generate the arm of the `dispatch` function used by the mocked runtime of the unit tests,
+    /// which executes the method the same way as its wrapper, without the panic hook.
+    pub fn mock_dispatch_arm(&self) -> TokenStream2 {
+        let statements = match self.wrapper_statements() {
+            // The error is already reported by the wrapper.
+            Ok(statements) => statements,
+            Err(_) => return TokenStream2::new(),
+        };
+        let AttrSigInfo { non_bindgen_attrs, ident, .. } = &self.attr_signature_info;
+        let cfg_attrs = non_bindgen_attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+        let ident_str = ident.to_string();
+        quote! {
+            #(#cfg_attrs)*
+            #ident_str => {
+                use super::#ident::Input;
+
+                #statements
+                true
+            }
+        }
+    }
+
+    /// Generate the statements of the wrapper which read the input, the callback results and the
+    /// state, call the method and write the result and the state.
+    fn wrapper_statements(&self) -> syn::Result<TokenStream2> {
+        let ImplItemMethodInfo { attr_signature_info, struct_type, .. } = self;
+        // Args provided by `env::input()`.
+        let has_input_args = attr_signature_info.input_args().next().is_some();
+
         let arg_parsing = if has_input_args {
             let decomposition = attr_signature_info.decomposition_pattern();
             let serializer_invocation = match attr_signature_info.input_serializer {
@@ -49,7 +129,6 @@ impl ImplItemMethodInfo {
 
         let arg_list = attr_signature_info.arg_list();
         let AttrSigInfo {
-            non_bindgen_attrs,
             ident,
             receiver,
             returns,
@@ -58,30 +137,12 @@ impl ImplItemMethodInfo {
             is_payable,
             is_private,
             is_handles_result,
+            only,
+            is_when_not_paused,
+            is_when_paused,
             ..
         } = attr_signature_info;
 
-        let output_type = match &self.attr_signature_info.returns {
-            ReturnType::Default => quote!(()),
-            ReturnType::Type(_token, type_) => {
-                if matches!(method_type, &MethodType::Init) {
-                    quote!(())
-                } else {
-                    quote!(#type_)
-                }
-            }
-        };
-        let ident_str = ident.to_string();
-        let method = quote! {
-            pub const NAME: &'static str = #ident_str;
-            pub type Output = #output_type;
-            impl near_sdk::utils::Method for Input {
-                const NAME: &'static str = NAME;
-                type Input = Self;
-                type Output = Output;
-            }
-        };
-
         let deposit_check = if *is_payable || matches!(method_type, &MethodType::View) {
             // No check if the method is payable or a view method
             quote! {}
@@ -105,23 +166,54 @@ impl ImplItemMethodInfo {
             quote! {}
         };
         let body = if matches!(method_type, &MethodType::Init) {
-            match init_method_wrapper(self, true) {
-                Ok(wrapper) => wrapper,
-                Err(err) => return err.to_compile_error(),
-            }
+            init_method_wrapper(self, true)?
         } else if matches!(method_type, &MethodType::InitIgnoreState) {
-            match init_method_wrapper(self, false) {
-                Ok(wrapper) => wrapper,
-                Err(err) => return err.to_compile_error(),
-            }
+            init_method_wrapper(self, false)?
         } else {
             let contract_deser;
             let method_invocation;
             let contract_ser;
             if let Some(receiver) = receiver {
                 let mutability = &receiver.mutability;
+                let pause_check = if *is_when_not_paused {
+                    quote! {
+                        contract.assert_not_paused();
+                    }
+                } else if *is_when_paused {
+                    quote! {
+                        contract.assert_paused();
+                    }
+                } else {
+                    quote! {}
+                };
+                let only_check = match only {
+                    Some(OnlyAttr::Owner) => quote! {
+                        contract.assert_owner();
+                    },
+                    Some(OnlyAttr::Role(role)) => quote! {
+                        contract.assert_role(#role);
+                    },
+                    None => quote! {},
+                };
+                let state_read = if matches!(method_type, &MethodType::Regular) {
+                    // The state created with `Default` is written by the method, along with its
+                    // schema version if it is versioned.
+                    quote! {
+                        near_sdk::env::state_read().unwrap_or_else(|| {
+                            use near_sdk::__private::{WriteUnversionedState as _, WriteVersionedState as _};
+                            (&near_sdk::__private::StateVersionWriter::<#struct_type>::new()).write_default_state_version();
+                            Default::default()
+                        })
+                    }
+                } else {
+                    quote! {
+                        near_sdk::env::state_read().unwrap_or_default()
+                    }
+                };
                 contract_deser = quote! {
-                    let #mutability contract: #struct_type = near_sdk::env::state_read().unwrap_or_default();
+                    let #mutability contract: #struct_type = #state_read;
+                    #pause_check
+                    #only_check
                 };
                 method_invocation = quote! {
                     contract.#ident(#arg_list)
@@ -171,22 +263,20 @@ impl ImplItemMethodInfo {
                     }
                 }
                 ReturnType::Type(_, return_type) if *is_handles_result => {
-                    return syn::Error::new(
+                    return Err(syn::Error::new(
                         return_type.span(),
                         "Method marked with #[handle_result] should return Result<T, E>.",
-                    )
-                    .to_compile_error();
+                    ));
                 }
                 ReturnType::Type(_, return_type) if utils::type_is_result(return_type) => {
-                    return syn::Error::new(
+                    return Err(syn::Error::new(
                         return_type.span(),
                         "Serializing Result<T, E> has been deprecated. Consider marking your method \
                         with #[handle_result] if the second generic represents a panicable error or \
                         replacing Result with another two type sum enum otherwise. If you really want \
                         to keep the legacy behavior, mark the method with #[handle_result] and make \
                         it return Result<Result<T, E>, near_sdk::Abort>.",
-                    )
-                    .to_compile_error();
+                    ));
                 }
                 ReturnType::Type(_, _) => {
                     let value_ser = match result_serializer {
@@ -207,37 +297,15 @@ impl ImplItemMethodInfo {
                 }
             }
         };
-        let non_bindgen_attrs = non_bindgen_attrs.iter().fold(TokenStream2::new(), |acc, value| {
-            quote! {
-                #acc
-                #value
-            }
-        });
-        quote! {
-            #non_bindgen_attrs
-            #[cfg(target_arch = "wasm32")]
-            #[no_mangle]
-            pub extern "C" fn #ident() {
-                use #ident::Input;
-
-                #panic_hook
-                #is_private_check
-                #deposit_check
-                #arg_parsing
-                #callback_deser
-                #callback_vec_deser
-                #body
-            }
-            pub mod #ident {
-                use super::*;
-
-                #input_struct
-                #input_struct2
-                #method
-            }
-        }
+        Ok(quote! {
+            #is_private_check
+            #deposit_check
+            #arg_parsing
+            #callback_deser
+            #callback_vec_deser
+            #body
+        })
     }
-
     pub fn marshal_method(&self) -> TokenStream2 {
         let ImplItemMethodInfo { attr_signature_info, .. } = self;
         let has_input_args = attr_signature_info.input_args().next().is_some();
@@ -313,6 +381,12 @@ fn init_method_wrapper(
     } else {
         quote! {}
     };
+    let version_write = quote! {
+        {
+            use near_sdk::__private::{WriteUnversionedState as _, WriteVersionedState as _};
+            (&near_sdk::__private::StateVersionWriter::<#struct_type>::new()).write_state_version();
+        }
+    };
     match returns {
         ReturnType::Default => {
             Err(syn::Error::new(ident.span(), "Init methods must return the contract state"))
@@ -324,7 +398,10 @@ fn init_method_wrapper(
                 #state_check
                 let result = #struct_type::#ident(#arg_list);
                 match result {
-                    Ok(contract) => near_sdk::env::state_write(&contract),
+                    Ok(contract) => {
+                        near_sdk::env::state_write(&contract);
+                        #version_write
+                    }
                     Err(err) => near_sdk::FunctionError::panic(&err)
                 }
             })
@@ -337,6 +414,7 @@ fn init_method_wrapper(
             #state_check
             let contract = #struct_type::#ident(#arg_list);
             near_sdk::env::state_write(&contract);
+            #version_write
         }),
     }
 }
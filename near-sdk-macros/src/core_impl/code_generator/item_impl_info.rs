@@ -1,6 +1,8 @@
-use crate::ItemImplInfo;
+use super::generate_ext_function;
+use crate::{ItemImplInfo, MigrationInfo};
 use proc_macro2::TokenStream as TokenStream2;
-use syn::Ident;
+use syn::spanned::Spanned;
+use syn::{Ident, Type};
 
 impl ItemImplInfo {
     /// Generate the code that wraps
@@ -25,17 +27,117 @@ impl ItemImplInfo {
             reexports.extend(reexport)
         }
         let underscore_trait = &self.underscore_trait;
+        let mock_dispatch =
+            if cfg!(feature = "mock-runtime") { self.mock_dispatch() } else { TokenStream2::new() };
         let reexports = quote::quote! {
             #[allow(non_snake_case)]
             pub mod #underscore_trait {
                 use super::*;
                 #reexports
+                #mock_dispatch
             }
         };
 
+        let migrate_state = self.migrate_state_wrapper();
+
         quote::quote! {
             #res
             #reexports
+            #migrate_state
+        }
+    }
+
+    /// Generate the `dispatch` function which calls the exported methods of this `impl` section
+    /// by name in the mocked runtime of the unit tests, see `near_sdk::test_utils::MockRuntime`.
+    fn mock_dispatch(&self) -> TokenStream2 {
+        let arms: Vec<_> = self
+            .methods
+            .iter()
+            .filter(|method| method.is_public || self.is_trait_impl)
+            .map(|method| method.mock_dispatch_arm())
+            .collect();
+        let body = if arms.is_empty() {
+            quote::quote! { false }
+        } else {
+            quote::quote! {
+                match method_name {
+                    #(#arms)*
+                    _ => false,
+                }
+            }
+        };
+        quote::quote! {
+            #[cfg(not(target_arch = "wasm32"))]
+            #[allow(unused_variables)]
+            pub fn dispatch(method_name: &str) -> bool {
+                #body
+            }
+        }
+    }
+
+    /// Generate the private `migrate_state` method that applies the migrations declared with
+    /// `#[migrate(from = N)]` in this `impl` section.
+    fn migrate_state_wrapper(&self) -> TokenStream2 {
+        if self.migrations.is_empty() {
+            return TokenStream2::new();
+        }
+        let ty = &self.ty;
+        let steps = self.migrations.iter().map(|MigrationInfo { from, ident }| {
+            quote::quote! {
+                #from => near_sdk::env::state_write(
+                    &<#ty>::#ident(near_sdk::__private::read_previous_state())
+                ),
+            }
+        });
+        quote::quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn migrate_state() {
+                near_sdk::env::setup_panic_hook();
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
+                    near_sdk::env::panic_str("Method migrate_state is private");
+                }
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic_str("Method migrate_state doesn't accept deposit");
+                }
+                near_sdk::__private::migrate_state::<#ty>(|from| {
+                    match from {
+                        #(#steps)*
+                        _ => return false,
+                    }
+                    true
+                });
+            }
+        }
+    }
+
+    /// Generate the methods of the cross-contract call builder created by `Contract::ext`, one for
+    /// each exported method. The builder itself is declared by `#[near_bindgen]` on the struct, so
+    /// the impl fails to compile if the struct isn't marked with it.
+    pub fn generate_ext_wrapper_code(&self) -> TokenStream2 {
+        let ident = match &self.ty {
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                &type_path.path.segments.last().expect("Type path can't be empty").ident
+            }
+            _ => {
+                return syn::Error::new(
+                    self.ty.span(),
+                    "near_bindgen can only be used on impl sections of named types.",
+                )
+                .to_compile_error()
+            }
+        };
+        let ext_name = quote::format_ident!("{}Ext", ident, span = ident.span());
+        let mut methods = TokenStream2::new();
+        for method in &self.methods {
+            if method.is_public || self.is_trait_impl {
+                methods.extend(generate_ext_function(&method.attr_signature_info));
+            }
+        }
+        quote::quote! {
+            impl #ext_name {
+                #methods
+            }
         }
     }
 
@@ -1,19 +1,28 @@
+use super::{generate_ext_function, generate_ext_structs};
 use crate::core_impl::info_extractor::ItemTraitInfo;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
+use quote::{format_ident, quote};
 
 impl ItemTraitInfo {
     /// Generate code that wrapps external calls.
     pub fn wrapped_module(&self) -> TokenStream2 {
         let mut result = TokenStream2::new();
+        let mut ext_methods = TokenStream2::new();
         for method in &self.methods {
             result.extend(method.method_wrapper());
+            ext_methods.extend(generate_ext_function(&method.attr_sig_info));
         }
         let mod_name = &self.mod_name;
+        let ext_structs = generate_ext_structs(&self.original.ident, None);
+        let ext_name = format_ident!("{}Ext", self.original.ident);
         quote! {
            pub mod #mod_name {
                 use super::*;
                 use near_sdk::{Gas, Balance, AccountId, Promise};
+                #ext_structs
+                impl #ext_name {
+                    #ext_methods
+                }
                 #result
             }
         }
@@ -13,5 +13,8 @@ pub use trait_item_method_info::*;
 mod item_impl_info;
 pub use item_impl_info::*;
 
+mod ext;
+pub use ext::*;
+
 mod item_struct_info;
 pub use item_struct_info::*;
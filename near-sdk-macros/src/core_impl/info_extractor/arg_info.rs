@@ -1,5 +1,6 @@
 use crate::core_impl::info_extractor::serializer_attr::SerializerAttr;
 use crate::core_impl::info_extractor::SerializerType;
+use crate::core_impl::utils;
 use quote::ToTokens;
 use syn::{spanned::Spanned, Attribute, Error, Ident, Pat, PatType, Token, Type};
 
@@ -12,6 +13,9 @@ pub enum BindgenArgType {
     CallbackResultArg,
     /// An argument that we read from all `env::promise_result()`.
     CallbackArgVec,
+    /// A tuple of `Result`s that we read from consecutive `env::promise_result()`, one per
+    /// element, which handles the error of each of them.
+    CallbackJoinArg,
 }
 
 /// A single argument of a function after it was processed by the bindgen.
@@ -81,6 +85,9 @@ impl ArgInfo {
                 "callback_vec" => {
                     bindgen_ty = BindgenArgType::CallbackArgVec;
                 }
+                "callback_join" => {
+                    bindgen_ty = BindgenArgType::CallbackJoinArg;
+                }
                 "serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
                     serializer_ty = serializer.serializer_type;
@@ -98,8 +105,17 @@ impl ArgInfo {
                 && attr_str != "serializer"
                 && attr_str != "callback_result"
                 && attr_str != "callback_unwrap"
+                && attr_str != "callback_join"
         });
 
+        if matches!(bindgen_ty, BindgenArgType::CallbackJoinArg) && join_ok_types(&ty).is_none() {
+            return Err(Error::new(
+                original.span(),
+                "Function parameters marked with #[callback_join] should have type \
+                (Result<T1, PromiseError>, Result<T2, PromiseError>, ...)",
+            ));
+        }
+
         Ok(Self {
             non_bindgen_attrs,
             ident,
@@ -114,3 +130,16 @@ impl ArgInfo {
         })
     }
 }
+
+/// Extracts the Ok types of the elements of a `#[callback_join]` tuple.
+///
+/// For example, given `(Result<u8, PromiseError>, Result<String, PromiseError>)` type it will
+/// return `u8` and `String` types.
+pub(crate) fn join_ok_types(ty: &Type) -> Option<Vec<&Type>> {
+    match ty {
+        Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+            tuple.elems.iter().map(utils::extract_ok_type).collect()
+        }
+        _ => None,
+    }
+}
@@ -1,4 +1,6 @@
-use super::{ArgInfo, BindgenArgType, InitAttr, MethodType, SerializerAttr, SerializerType};
+use super::{
+    ArgInfo, BindgenArgType, InitAttr, MethodType, OnlyAttr, SerializerAttr, SerializerType,
+};
 use proc_macro2::Span;
 use quote::ToTokens;
 use syn::spanned::Spanned;
@@ -20,6 +22,12 @@ pub struct AttrSigInfo {
     pub is_private: bool,
     /// Whether method returns Result type where only Ok type is serialized
     pub is_handles_result: bool,
+    /// Accounts allowed to call the method, checked on the contract state.
+    pub only: Option<OnlyAttr>,
+    /// Whether method can only be called while the contract is not paused.
+    pub is_when_not_paused: bool,
+    /// Whether method can only be called while the contract is paused.
+    pub is_when_paused: bool,
     /// The serializer that we use for `env::input()`.
     pub input_serializer: SerializerType,
     /// The serializer that we use for the return type.
@@ -64,6 +72,9 @@ impl AttrSigInfo {
         let mut is_payable = false;
         let mut is_private = false;
         let mut is_handles_result = false;
+        let mut only = None;
+        let mut is_when_not_paused = false;
+        let mut is_when_paused = false;
         // By the default we serialize the result with JSON.
         let mut result_serializer = SerializerType::JSON;
 
@@ -93,6 +104,15 @@ impl AttrSigInfo {
                 "handle_result" => {
                     is_handles_result = true;
                 }
+                "only" => {
+                    only = Some(syn::parse2(attr.tokens.clone())?);
+                }
+                "when_not_paused" => {
+                    is_when_not_paused = true;
+                }
+                "when_paused" => {
+                    is_when_paused = true;
+                }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
                 }
@@ -122,6 +142,21 @@ impl AttrSigInfo {
             }
         };
 
+        if only.is_some() || is_when_not_paused || is_when_paused {
+            if receiver.is_none() {
+                return Err(Error::new(
+                    original_sig.span(),
+                    "Methods marked with #[only], #[when_not_paused] or #[when_paused] must take `self`",
+                ));
+            }
+            if is_when_not_paused && is_when_paused {
+                return Err(Error::new(
+                    original_sig.span(),
+                    "Method can't be marked with both #[when_not_paused] and #[when_paused]",
+                ));
+            }
+        }
+
         if let Some(payable_attr) = payable_attr {
             if matches!(method_type, MethodType::View) {
                 return Err(Error::new(
@@ -143,6 +178,9 @@ impl AttrSigInfo {
             is_payable,
             is_private,
             is_handles_result,
+            only,
+            is_when_not_paused,
+            is_when_paused,
             result_serializer,
             receiver,
             returns,
@@ -1,6 +1,7 @@
-use crate::ImplItemMethodInfo;
+use crate::{ImplItemMethodInfo, MigrateAttr};
+use quote::ToTokens;
 use syn::spanned::Spanned;
-use syn::{Error, Ident, ImplItem, ItemImpl, Type};
+use syn::{Error, FnArg, Ident, ImplItem, ImplItemMethod, ItemImpl, ReturnType, Type};
 
 /// Information extracted from `impl` section.
 pub struct ItemImplInfo {
@@ -11,6 +12,16 @@ pub struct ItemImplInfo {
     pub ty: Type,
     /// Info extracted for each method.
     pub methods: Vec<ImplItemMethodInfo>,
+    /// Methods marked with `#[migrate(from = N)]`, which are not exported.
+    pub migrations: Vec<MigrationInfo>,
+}
+
+/// Information extracted from a method marked with `#[migrate(from = N)]`.
+pub struct MigrationInfo {
+    /// The state version the migration is applied to.
+    pub from: u32,
+    /// The name of the method.
+    pub ident: Ident,
 }
 
 impl ItemImplInfo {
@@ -35,12 +46,63 @@ impl ItemImplInfo {
         };
 
         let mut methods = vec![];
+        let mut migrations: Vec<MigrationInfo> = vec![];
         for subitem in &mut original.items {
             if let ImplItem::Method(m) = subitem {
+                if let Some(migration) = MigrationInfo::new(m)? {
+                    if is_trait_impl {
+                        return Err(Error::new(
+                            m.sig.span(),
+                            "Migrations can only be declared in the inherent impl of the contract.",
+                        ));
+                    }
+                    if migrations.iter().any(|other| other.from == migration.from) {
+                        return Err(Error::new(
+                            m.sig.span(),
+                            format!(
+                                "Migration from state version {} is declared twice.",
+                                migration.from
+                            ),
+                        ));
+                    }
+                    migrations.push(migration);
+                    continue;
+                }
                 let method_info = ImplItemMethodInfo::new(m, ty.clone())?;
                 methods.push(method_info);
             }
         }
-        Ok(Self { is_trait_impl, underscore_trait, ty, methods })
+        Ok(Self { is_trait_impl, underscore_trait, ty, methods, migrations })
+    }
+}
+
+impl MigrationInfo {
+    /// Extracts the migration from the method and removes the `#[migrate]` attribute, returns
+    /// `None` if the method is not a migration.
+    fn new(original: &mut ImplItemMethod) -> syn::Result<Option<Self>> {
+        let position = match original
+            .attrs
+            .iter()
+            .position(|attr| attr.path.to_token_stream().to_string() == "migrate")
+        {
+            Some(position) => position,
+            None => return Ok(None),
+        };
+        let attr = original.attrs.remove(position);
+        let MigrateAttr { from } = syn::parse2(attr.tokens)?;
+
+        let sig = &original.sig;
+        let has_single_arg =
+            sig.inputs.len() == 1 && matches!(sig.inputs.first(), Some(FnArg::Typed(_)));
+        if !has_single_arg || matches!(sig.output, ReturnType::Default) {
+            return Err(Error::new(
+                sig.span(),
+                "Migrations must take the previous state as the only argument and return the next state.",
+            ));
+        }
+        if !sig.generics.params.is_empty() {
+            return Err(Error::new(sig.generics.span(), "Migrations can't have type parameters."));
+        }
+        Ok(Some(Self { from, ident: sig.ident.clone() }))
     }
 }
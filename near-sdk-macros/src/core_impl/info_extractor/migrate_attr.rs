@@ -0,0 +1,27 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::token::Paren;
+use syn::{Error, LitInt, Token};
+
+/// Arguments of `#[migrate(from = N)]`.
+pub struct MigrateAttr {
+    /// The state version the migration is applied to.
+    pub from: u32,
+}
+
+impl Parse for MigrateAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !input.peek(Paren) {
+            return Err(Error::new(input.span(), "Expected `#[migrate(from = N)]`."));
+        }
+        let content;
+        let _paren_token = syn::parenthesized!(content in input);
+        let ident: Ident = content.parse()?;
+        if ident != "from" {
+            return Err(Error::new(ident.span(), "Unsupported migrate attribute."));
+        }
+        content.parse::<Token![=]>()?;
+        let from: LitInt = content.parse()?;
+        Ok(Self { from: from.base10_parse()? })
+    }
+}
@@ -2,6 +2,7 @@ mod serializer_attr;
 pub use serializer_attr::SerializerAttr;
 
 mod arg_info;
+pub(crate) use arg_info::join_ok_types;
 pub use arg_info::{ArgInfo, BindgenArgType};
 
 mod attr_sig_info;
@@ -21,7 +22,13 @@ mod item_impl_info;
 mod init_attr;
 pub use init_attr::InitAttr;
 
-pub use item_impl_info::ItemImplInfo;
+mod only_attr;
+pub use only_attr::OnlyAttr;
+
+mod migrate_attr;
+pub use migrate_attr::MigrateAttr;
+
+pub use item_impl_info::{ItemImplInfo, MigrationInfo};
 
 /// Type of serialization we use.
 #[derive(PartialEq, Eq)]
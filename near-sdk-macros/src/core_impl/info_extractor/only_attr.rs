@@ -0,0 +1,34 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Error, LitStr, Token};
+
+/// Accounts allowed to call a method marked with `#[only(owner)]` or `#[only(role = "...")]`.
+pub enum OnlyAttr {
+    /// The owner of the contract, checked with `assert_owner`.
+    Owner,
+    /// The members of the role, checked with `assert_role`.
+    Role(LitStr),
+}
+
+impl Parse for OnlyAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        let _paren_token = parenthesized!(content in input);
+        let ident: Ident = content.parse()?;
+        let only = match ident.to_string().as_str() {
+            "owner" => OnlyAttr::Owner,
+            "role" => {
+                content.parse::<Token![=]>()?;
+                OnlyAttr::Role(content.parse()?)
+            }
+            _ => return Err(Error::new(ident.span(), "Unsupported only attribute.")),
+        };
+        if !content.is_empty() {
+            return Err(Error::new(
+                content.span(),
+                "Expected `#[only(owner)]` or `#[only(role = \"...\")]`.",
+            ));
+        }
+        Ok(only)
+    }
+}
@@ -1,8 +1,10 @@
+use crate::core_impl::info_extractor::join_ok_types;
+use crate::core_impl::utils;
 use crate::{BindgenArgType, ImplItemMethodInfo, InputStructType, MethodType, SerializerType};
 
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::ReturnType;
+use quote::{quote, ToTokens};
+use syn::{ReturnType, Type};
 
 impl ImplItemMethodInfo {
     /// Generates metadata struct for this method.
@@ -67,11 +69,15 @@ impl ImplItemMethodInfo {
             .attr_signature_info
             .args
             .iter()
-            .filter(|arg| matches!(arg.bindgen_ty, BindgenArgType::CallbackArg))
-            .map(|arg| {
-                let ty = &arg.ty;
+            .flat_map(|arg| match arg.bindgen_ty {
+                BindgenArgType::CallbackArg => vec![&arg.ty],
+                // Each element of the join is a separate callback.
+                BindgenArgType::CallbackJoinArg => join_ok_types(&arg.ty).unwrap_or_default(),
+                _ => vec![],
+            })
+            .map(|ty| {
                 quote! {
-                    #ty::schema_container()
+                    <#ty>::schema_container()
                 }
             })
             .collect();
@@ -119,4 +125,123 @@ impl ImplItemMethodInfo {
              }
         }
     }
+
+    /// Generates ABI struct for this method, the JSON counterpart of [`Self::metadata_struct`].
+    ///
+    /// # Example:
+    /// The following method:
+    /// ```ignore
+    /// #[payable]
+    /// #[handle_result]
+    /// pub fn f3(&mut self, arg0: FancyStruct, arg1: u64) -> Result<IsOk, Error> { }
+    /// ```
+    /// will produce this struct:
+    /// ```ignore
+    /// near_sdk::__private::AbiFunction {
+    ///     name: "f3".to_string(),
+    ///     is_view: false,
+    ///     is_init: false,
+    ///     is_payable: true,
+    ///     is_private: false,
+    ///     params: vec![
+    ///         near_sdk::__private::AbiParameter::new("arg0", near_sdk::__private::AbiType::json::<FancyStruct>(&mut generator)),
+    ///         near_sdk::__private::AbiParameter::new("arg1", near_sdk::__private::AbiType::json::<u64>(&mut generator))
+    ///     ],
+    ///     callbacks: vec![],
+    ///     callbacks_vec: None,
+    ///     result: Some(near_sdk::__private::AbiType::json::<IsOk>(&mut generator)),
+    ///     error: Some("Error".to_string())
+    /// }
+    /// ```
+    /// where `generator` is the `SchemaGenerator` collecting the definitions of the whole contract.
+    pub fn abi_struct(&self) -> TokenStream2 {
+        let info = &self.attr_signature_info;
+        let method_name_str = info.ident.to_string();
+        let is_view = matches!(&info.method_type, &MethodType::View);
+        let is_init = matches!(&info.method_type, &MethodType::Init | &MethodType::InitIgnoreState);
+        let is_payable = info.is_payable;
+        let is_private = info.is_private;
+        let params: Vec<_> = info
+            .input_args()
+            .map(|arg| {
+                let name = arg.ident.to_string();
+                let typ = abi_type(&arg.ty, &info.input_serializer);
+                quote! {
+                    near_sdk::__private::AbiParameter::new(#name, #typ)
+                }
+            })
+            .collect();
+        let callbacks: Vec<_> = info
+            .args
+            .iter()
+            .flat_map(|arg| {
+                let types = match arg.bindgen_ty {
+                    BindgenArgType::CallbackArg => vec![&arg.ty],
+                    BindgenArgType::CallbackResultArg => {
+                        utils::extract_ok_type(&arg.ty).into_iter().collect()
+                    }
+                    BindgenArgType::CallbackJoinArg => join_ok_types(&arg.ty).unwrap_or_default(),
+                    _ => vec![],
+                };
+                types.into_iter().map(move |ty| abi_type(ty, &arg.serializer_ty))
+            })
+            .collect();
+        let callbacks_vec = match info
+            .args
+            .iter()
+            .rfind(|arg| matches!(arg.bindgen_ty, BindgenArgType::CallbackArgVec))
+        {
+            None => quote! { None },
+            Some(arg) => {
+                let typ = abi_type(&arg.ty, &arg.serializer_ty);
+                quote! { Some(#typ) }
+            }
+        };
+        let (result, error) = match &info.returns {
+            ReturnType::Default => (quote! { None }, quote! { None }),
+            ReturnType::Type(_, ty) if info.is_handles_result => {
+                let ok_ty = utils::extract_ok_type(ty).unwrap_or(ty);
+                let typ = abi_type(ok_ty, &info.result_serializer);
+                let error = match utils::extract_err_type(ty) {
+                    Some(err_ty) => {
+                        let err_name = err_ty.to_token_stream().to_string().replace(' ', "");
+                        quote! { Some(#err_name.to_string()) }
+                    }
+                    None => quote! { None },
+                };
+                (quote! { Some(#typ) }, error)
+            }
+            ReturnType::Type(_, ty) => {
+                let typ = abi_type(ty, &info.result_serializer);
+                (quote! { Some(#typ) }, quote! { None })
+            }
+        };
+
+        quote! {
+             near_sdk::__private::AbiFunction {
+                 name: #method_name_str.to_string(),
+                 is_view: #is_view,
+                 is_init: #is_init,
+                 is_payable: #is_payable,
+                 is_private: #is_private,
+                 params: vec![#(#params),*],
+                 callbacks: vec![#(#callbacks),*],
+                 callbacks_vec: #callbacks_vec,
+                 result: #result,
+                 error: #error
+             }
+        }
+    }
+}
+
+/// Generates the `AbiType` of a value of type `ty` serialized with `serializer_type`.
+fn abi_type(ty: &Type, serializer_type: &SerializerType) -> TokenStream2 {
+    match serializer_type {
+        SerializerType::JSON => quote! {
+            near_sdk::__private::AbiType::json::<#ty>(&mut generator)
+        },
+        SerializerType::Borsh => quote! {
+            near_sdk::__private::AbiType::borsh::<#ty>()
+        },
+    }
 }
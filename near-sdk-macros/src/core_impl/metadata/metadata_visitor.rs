@@ -65,6 +65,30 @@ impl MetadataVisitor {
             }
         })
     }
+
+    /// Generates the native `contract_abi()` function returning the JSON ABI of the exported
+    /// methods. Unlike the metadata, it is not part of the wasm binary.
+    pub fn generate_abi_method(&self) -> syn::Result<TokenStream2> {
+        if !self.errors.is_empty() {
+            return Err(self.errors[0].clone());
+        }
+        let functions: Vec<TokenStream2> = self
+            .impl_item_infos
+            .iter()
+            .flat_map(|i| i.methods.iter().filter(move |m| m.is_public || i.is_trait_impl))
+            .map(|m| m.abi_struct())
+            .collect();
+        Ok(quote! {
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn contract_abi() -> near_sdk::__private::ContractAbi {
+                let mut generator = near_sdk::schemars::gen::SchemaGenerator::default();
+                let functions = vec![
+                    #(#functions),*
+                ];
+                near_sdk::__private::ContractAbi::new(functions, generator.take_definitions())
+            }
+        })
+    }
 }
 
 #[rustfmt::skip]
@@ -156,4 +180,75 @@ mod tests {
         );
         assert_eq!(expected.to_string(), actual.to_string());
     }
+    #[test]
+    fn abi_several_methods() {
+        let code = quote! {
+            #[near_bindgen]
+            impl Hello {
+                pub fn f1(&self) { }
+                fn not_exported(&self) { }
+                #[payable]
+                #[handle_result]
+                pub fn f2(&mut self, #[serializer(borsh)] arg0: FancyStruct) -> Result<IsOk, Error> { }
+                #[private]
+                pub fn on_f2(&mut self, #[callback_result] res: Result<u64, PromiseError>) -> Vec<u8> { }
+            }
+        };
+
+        let file: syn::File = syn::parse2(code).unwrap();
+
+        let mut visitor = MetadataVisitor::new();
+        visitor.visit_file(&file);
+
+        let actual = visitor.generate_abi_method().unwrap();
+        let expected = quote!(
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn contract_abi() -> near_sdk::__private::ContractAbi {
+                let mut generator = near_sdk::schemars::gen::SchemaGenerator::default();
+                let functions = vec![
+                    near_sdk::__private::AbiFunction {
+                        name: "f1".to_string(),
+                        is_view: true,
+                        is_init: false,
+                        is_payable: false,
+                        is_private: false,
+                        params: vec![],
+                        callbacks: vec![],
+                        callbacks_vec: None,
+                        result: None,
+                        error: None
+                    },
+                    near_sdk::__private::AbiFunction {
+                        name: "f2".to_string(),
+                        is_view: false,
+                        is_init: false,
+                        is_payable: true,
+                        is_private: false,
+                        params: vec![near_sdk::__private::AbiParameter::new(
+                            "arg0",
+                            near_sdk::__private::AbiType::borsh::<FancyStruct>()
+                        )],
+                        callbacks: vec![],
+                        callbacks_vec: None,
+                        result: Some(near_sdk::__private::AbiType::json::<IsOk>(&mut generator)),
+                        error: Some("Error".to_string())
+                    },
+                    near_sdk::__private::AbiFunction {
+                        name: "on_f2".to_string(),
+                        is_view: false,
+                        is_init: false,
+                        is_payable: false,
+                        is_private: true,
+                        params: vec![],
+                        callbacks: vec![near_sdk::__private::AbiType::json::<u64>(&mut generator)],
+                        callbacks_vec: None,
+                        result: Some(near_sdk::__private::AbiType::json::<Vec<u8> >(&mut generator)),
+                        error: None
+                    }
+                ];
+                near_sdk::__private::ContractAbi::new(functions, generator.take_definitions())
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
 }
@@ -39,3 +39,24 @@ pub(crate) fn extract_ok_type(ty: &Type) -> Option<&Type> {
         _ => None,
     }
 }
+
+/// Extracts the Err type from a `Result` type.
+///
+/// For example, given `Result<String, u8>` type it will return `u8` type.
+pub(crate) fn extract_err_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() && path_is_result(&type_path.path) => {
+            let type_params = &type_path.path.segments.first()?.arguments;
+            // The Err type is the second angle-bracketed param ("<_, u8>"):
+            let generic_arg = match type_params {
+                PathArguments::AngleBracketed(params) => Some(params.args.iter().nth(1)?),
+                _ => None,
+            }?;
+            match generic_arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
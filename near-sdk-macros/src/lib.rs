@@ -46,9 +46,11 @@ use syn::{File, ItemEnum, ItemImpl, ItemStruct, ItemTrait};
 pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
         let struct_proxy = generate_proxy_struct(&input);
+        let ext_gen = generate_ext_structs(&input.ident, Some(&input.generics));
         TokenStream::from(quote! {
             #input
             #struct_proxy
+            #ext_gen
         })
     } else if let Ok(mut input) = syn::parse::<ItemImpl>(item) {
         let item_impl_info = match ItemImplInfo::new(&mut input) {
@@ -60,10 +62,12 @@ pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
         let generated_code = item_impl_info.wrapper_code();
         // Add helper type for simulation testing only if not wasm32
         let marshalled_code = item_impl_info.marshall_code();
+        let ext_generated_code = item_impl_info.generate_ext_wrapper_code();
         TokenStream::from(quote! {
             #marshalled_code
             #input
             #generated_code
+            #ext_generated_code
         })
     } else {
         TokenStream::from(
@@ -80,16 +84,25 @@ pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// Each of these static methods takes positional arguments defined by the Trait,
 /// then the receiver_id, the attached deposit and the amount of gas and returns a new Promise.
 ///
+/// The module also contains an `ext` function that creates a builder of calls to the contract.
+/// The deposit, static gas and weight of the unused gas are configured on the builder, and
+/// calling one of the methods of the Trait on it returns a new Promise. Methods of contracts
+/// marked with `#[near_bindgen]` can be called the same way through `Contract::ext`.
+///
 /// # Examples
 ///
 /// ```ignore
-/// use near_sdk::ext_contract;
+/// use near_sdk::{ext_contract, Gas};
 ///
 /// #[ext_contract(ext_calculator)]
 /// trait Calculator {
 ///     fn mult(&self, a: u64, b: u64) -> u128;
 ///     fn sum(&self, a: u128, b: u128) -> u128;
 /// }
+///
+/// let promise = ext_calculator::ext("calculator.near".parse().unwrap())
+///     .with_static_gas(Gas(5_000_000_000_000))
+///     .mult(2, 3);
 /// ```
 #[proc_macro_attribute]
 pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -161,6 +174,10 @@ pub fn init(_attr: TokenStream, item: TokenStream) -> TokenStream {
 }
 
 /// `metadata` generates the metadata method and should be placed at the very end of the `lib.rs` file.
+///
+/// With the `abi` feature of `near-sdk` enabled it also generates a native `contract_abi()`
+/// function that returns the JSON ABI of the exported methods. Types of JSON arguments and return
+/// values are then required to implement `schemars::JsonSchema`.
 // TODO: Once Rust allows inner attributes and custom procedural macros for modules we should switch this
 // to be `#![metadata]` attribute at the top of the contract file instead. https://github.com/rust-lang/rust/issues/54727
 #[proc_macro]
@@ -172,9 +189,18 @@ pub fn metadata(item: TokenStream) -> TokenStream {
             Ok(x) => x,
             Err(err) => return TokenStream::from(err.to_compile_error()),
         };
+        let abi = if cfg!(feature = "abi") {
+            match visitor.generate_abi_method() {
+                Ok(x) => x,
+                Err(err) => return TokenStream::from(err.to_compile_error()),
+            }
+        } else {
+            proc_macro2::TokenStream::new()
+        };
         TokenStream::from(quote! {
             #input
             #generated
+            #abi
         })
     } else {
         TokenStream::from(
@@ -216,12 +242,47 @@ pub fn derive_no_default(item: TokenStream) -> TokenStream {
 /// `BorshStorageKey` generates implementation for `BorshIntoStorageKey` trait.
 /// It allows the type to be passed as a unique prefix for persistent collections.
 /// The type should also implement or derive `BorshSerialize` trait.
-#[proc_macro_derive(BorshStorageKey)]
+///
+/// The enum variants with fields marked with `#[nested]` generate the prefixes of collections
+/// nested in other collections: the fields are hashed instead of being serialized as they are,
+/// in the same way as `IntoStorageKey::nest`, so that all the prefixes of the variant have the
+/// same length.
+///
+/// ```ignore
+/// #[derive(BorshSerialize, BorshStorageKey)]
+/// enum StorageKey {
+///     TokensPerOwner,
+///     #[nested]
+///     OwnerTokens { owner_id: AccountId },
+/// }
+///
+/// let tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>> =
+///     LookupMap::new(StorageKey::TokensPerOwner);
+/// let owner_tokens: UnorderedSet<TokenId> =
+///     UnorderedSet::new(StorageKey::OwnerTokens { owner_id: owner_id.clone() });
+/// ```
+#[proc_macro_derive(BorshStorageKey, attributes(nested))]
 pub fn borsh_storage_key(item: TokenStream) -> TokenStream {
-    let name = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
-        input.ident
+    let (name, nested) = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        let mut nested = Vec::new();
+        for variant in &input.variants {
+            if !variant.attrs.iter().any(|attr| attr.path.is_ident("nested")) {
+                continue;
+            }
+            if variant.fields.is_empty() {
+                return TokenStream::from(
+                    syn::Error::new(
+                        variant.ident.span(),
+                        "Only variants with fields can be marked with `#[nested]`.",
+                    )
+                    .to_compile_error(),
+                );
+            }
+            nested.push(variant.ident.clone());
+        }
+        (input.ident, nested)
     } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
-        input.ident
+        (input.ident, Vec::new())
     } else {
         return TokenStream::from(
             syn::Error::new(
@@ -231,8 +292,70 @@ pub fn borsh_storage_key(item: TokenStream) -> TokenStream {
             .to_compile_error(),
         );
     };
+    if nested.is_empty() {
+        return TokenStream::from(quote! {
+            impl near_sdk::__private::BorshIntoStorageKey for #name {}
+        });
+    }
+    let nested = nested.iter().map(|variant| quote! { #name::#variant { .. } });
+    // The first byte of a serialized enum is the variant index, which is kept as it is.
+    TokenStream::from(quote! {
+        impl near_sdk::IntoStorageKey for #name {
+            fn into_storage_key(self) -> Vec<u8> {
+                let bytes = near_sdk::borsh::BorshSerialize::try_to_vec(&self)
+                    .unwrap_or_else(|_| near_sdk::env::abort());
+                match self {
+                    #(#nested)|* => {
+                        near_sdk::__private::nested_storage_key(bytes[..1].to_vec(), &bytes[1..])
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => bytes,
+                }
+            }
+        }
+    })
+}
+
+/// `VersionedState` generates implementation for `near_sdk::VersionedState` trait with the schema
+/// version given by the `#[state_version(N)]` attribute.
+/// The version is stored by the `#[init]` methods and is used by the `migrate_state` method that
+/// `near_bindgen` generates for the migrations declared with `#[migrate(from = N)]`.
+#[proc_macro_derive(VersionedState, attributes(state_version))]
+pub fn versioned_state(item: TokenStream) -> TokenStream {
+    let (name, attrs) = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        (input.ident, input.attrs)
+    } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
+        (input.ident, input.attrs)
+    } else {
+        return TokenStream::from(
+            syn::Error::new(
+                Span::call_site(),
+                "VersionedState can only be used as a derive on enums or structs.",
+            )
+            .to_compile_error(),
+        );
+    };
+    let version = match attrs.iter().find(|attr| attr.path.is_ident("state_version")) {
+        Some(attr) => {
+            match attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse::<u32>()) {
+                Ok(version) => version,
+                Err(err) => return TokenStream::from(err.to_compile_error()),
+            }
+        }
+        None => {
+            return TokenStream::from(
+                syn::Error::new(
+                    name.span(),
+                    "VersionedState requires the version as `#[state_version(N)]`.",
+                )
+                .to_compile_error(),
+            )
+        }
+    };
     TokenStream::from(quote! {
-        impl near_sdk::__private::BorshIntoStorageKey for #name {}
+        impl near_sdk::VersionedState for #name {
+            const STATE_VERSION: u32 = #version;
+        }
     })
 }
 
@@ -262,3 +385,131 @@ pub fn function_error(item: TokenStream) -> TokenStream {
         }
     })
 }
+
+/// `near_event` implements `near_sdk::NearEvent` for a struct or an enum, so that it is logged as
+/// an event of the given standard in the [NEP-297](https://nomicon.io/Standards/EventsFormat)
+/// format, and adds an `emit` method logging it. It also derives `serde::Serialize` for the type.
+///
+/// A struct is the `data` of an event, named by the `event` argument, which defaults to the name
+/// of the struct in snake case. The variants of an enum are the events of the standard, named
+/// after the variants in snake case, with the content of the variants as their `data`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[near_event(standard = "nep171", version = "1.0.0")]
+/// pub enum NftEvent {
+///     NftMint(Vec<NftMintData>),
+///     NftBurn(Vec<NftBurnData>),
+/// }
+///
+/// #[near_event(standard = "nep171", version = "1.0.0", event = "nft_mint")]
+/// pub struct NftMint(Vec<NftMintData>);
+///
+/// NftEvent::NftBurn(burns).emit();
+/// NftMint(mints).emit();
+/// ```
+#[proc_macro_attribute]
+pub fn near_event(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
+    let (mut standard, mut version, mut event) = (None, None, None);
+    for arg in args {
+        let name_value = match arg {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) => name_value,
+            arg => {
+                return TokenStream::from(
+                    syn::Error::new_spanned(arg, "Expected `name = \"value\"`.").to_compile_error(),
+                )
+            }
+        };
+        let value = match &name_value.lit {
+            syn::Lit::Str(value) => value.clone(),
+            lit => {
+                return TokenStream::from(
+                    syn::Error::new_spanned(lit, "Expected a string literal.").to_compile_error(),
+                )
+            }
+        };
+        let slot = if name_value.path.is_ident("standard") {
+            &mut standard
+        } else if name_value.path.is_ident("version") {
+            &mut version
+        } else if name_value.path.is_ident("event") {
+            &mut event
+        } else {
+            return TokenStream::from(
+                syn::Error::new_spanned(
+                    name_value.path,
+                    "Unknown argument, expected `standard`, `version` or `event`.",
+                )
+                .to_compile_error(),
+            );
+        };
+        *slot = Some(value);
+    }
+    let (standard, version) = match (standard, version) {
+        (Some(standard), Some(version)) => (standard, version),
+        _ => {
+            return TokenStream::from(
+                syn::Error::new(
+                    Span::call_site(),
+                    "near_event requires `standard = \"...\"` and `version = \"...\"`.",
+                )
+                .to_compile_error(),
+            )
+        }
+    };
+
+    let (item, name, generics, event) = if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        if let Some(event) = event {
+            return TokenStream::from(
+                syn::Error::new_spanned(
+                    event,
+                    "The events of an enum are named after its variants, `event` is only \
+                     allowed on structs.",
+                )
+                .to_compile_error(),
+            );
+        }
+        let item = quote! {
+            #[derive(near_sdk::serde::Serialize)]
+            #[serde(crate = "near_sdk::serde")]
+            #[serde(tag = "event", content = "data", rename_all = "snake_case")]
+            #input
+        };
+        (item, input.ident, input.generics, quote! { None })
+    } else if let Ok(input) = syn::parse::<ItemStruct>(item) {
+        let event = event.map(|event| event.value()).unwrap_or_else(|| {
+            use inflector::Inflector;
+            input.ident.to_string().to_snake_case()
+        });
+        let item = quote! {
+            #[derive(near_sdk::serde::Serialize)]
+            #[serde(crate = "near_sdk::serde")]
+            #input
+        };
+        (item, input.ident, input.generics, quote! { Some(#event) })
+    } else {
+        return TokenStream::from(
+            syn::Error::new(Span::call_site(), "near_event can only be used on enums or structs.")
+                .to_compile_error(),
+        );
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    TokenStream::from(quote! {
+        #item
+
+        impl #impl_generics near_sdk::NearEvent for #name #ty_generics #where_clause {
+            const STANDARD: &'static str = #standard;
+            const VERSION: &'static str = #version;
+            const EVENT: Option<&'static str> = #event;
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Logs the event through `near_sdk::env::log_str`.
+            pub fn emit(&self) {
+                near_sdk::NearEvent::emit(self)
+            }
+        }
+    })
+}
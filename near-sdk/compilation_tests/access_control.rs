@@ -0,0 +1,62 @@
+//! Methods restricted with `#[only]`, `#[when_not_paused]` and `#[when_paused]`.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen, require, AccountId, PanicOnDefault};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+struct Vault {
+    owner_id: AccountId,
+    guardian_id: AccountId,
+    paused: bool,
+    balance: u128,
+}
+
+impl Vault {
+    fn assert_owner(&self) {
+        require!(env::predecessor_account_id() == self.owner_id, "Not the owner");
+    }
+
+    fn assert_role(&self, role: &str) {
+        require!(role == "guardian" && env::predecessor_account_id() == self.guardian_id);
+    }
+
+    fn assert_not_paused(&self) {
+        require!(!self.paused);
+    }
+
+    fn assert_paused(&self) {
+        require!(self.paused);
+    }
+}
+
+#[near_bindgen]
+impl Vault {
+    #[init]
+    pub fn new(owner_id: AccountId, guardian_id: AccountId) -> Self {
+        Self { owner_id, guardian_id, paused: false, balance: 0 }
+    }
+
+    #[when_not_paused]
+    pub fn deposit(&mut self, amount: u128) {
+        self.balance += amount;
+    }
+
+    #[only(owner)]
+    #[when_paused]
+    pub fn drain(&mut self) -> u128 {
+        std::mem::take(&mut self.balance)
+    }
+
+    #[only(role = "guardian")]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    #[only(owner)]
+    pub fn balance(&self) -> u128 {
+        self.balance
+    }
+}
+
+fn main() {}
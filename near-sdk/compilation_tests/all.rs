@@ -20,4 +20,11 @@ fn compilation_tests() {
     t.compile_fail("compilation_tests/payable_view.rs");
     t.pass("compilation_tests/borsh_storage_key.rs");
     t.pass("compilation_tests/function_error.rs");
+    t.pass("compilation_tests/callback_join.rs");
+    t.pass("compilation_tests/ext_builder.rs");
+    t.compile_fail("compilation_tests/ext_builder_missing_struct.rs");
+    t.pass("compilation_tests/versioned_state.rs");
+    t.pass("compilation_tests/access_control.rs");
+    t.pass("compilation_tests/near_event.rs");
+    t.compile_fail("compilation_tests/only_static.rs");
 }
@@ -1,8 +1,8 @@
 //! Testing BorshStorageKey macro.
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
-use near_sdk::{near_bindgen, BorshStorageKey};
+use near_sdk::collections::{LookupMap, UnorderedSet};
+use near_sdk::{near_bindgen, BorshStorageKey, IntoStorageKey};
 
 #[derive(BorshStorageKey, BorshSerialize)]
 struct StorageKeyStruct {
@@ -15,11 +15,23 @@ enum StorageKeyEnum {
     SubAccounts { account_id: String },
 }
 
+#[derive(BorshStorageKey, BorshSerialize)]
+enum NestedStorageKeyEnum {
+    TokensPerOwner,
+    #[nested]
+    OwnerTokens {
+        owner_id: String,
+    },
+    #[nested]
+    Approvals(String, u64),
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
 struct Contract {
     map1: LookupMap<u64, u64>,
     map2: LookupMap<String, String>,
+    map3: LookupMap<String, UnorderedSet<String>>,
 }
 
 impl Default for Contract {
@@ -27,6 +39,7 @@ impl Default for Contract {
         Self {
             map1: LookupMap::new(StorageKeyStruct { key: "bla".to_string() }),
             map2: LookupMap::new(StorageKeyEnum::Accounts),
+            map3: LookupMap::new(NestedStorageKeyEnum::TokensPerOwner),
         }
     }
 }
@@ -34,4 +47,13 @@ impl Default for Contract {
 #[near_bindgen]
 impl Contract {}
 
-fn main() {}
+fn main() {
+    let owner_id = "alice".to_string();
+    let tokens: UnorderedSet<String> =
+        UnorderedSet::new(NestedStorageKeyEnum::OwnerTokens { owner_id: owner_id.clone() });
+    let approvals: UnorderedSet<String> =
+        UnorderedSet::new(NestedStorageKeyEnum::Approvals(owner_id.clone(), 0));
+    let nested: UnorderedSet<String> =
+        UnorderedSet::new(NestedStorageKeyEnum::TokensPerOwner.nest(&owner_id));
+    let _ = (tokens, approvals, nested);
+}
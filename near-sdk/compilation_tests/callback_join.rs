@@ -0,0 +1,47 @@
+//! Callback receiving the results of a typed promise join.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{env, ext_contract, near_bindgen, Gas, Promise, PromiseError};
+
+#[ext_contract(ext)]
+pub trait Ext {
+    fn get_number(&self) -> u8;
+    fn get_name(&self) -> String;
+    fn on_all(&mut self);
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Joiner {
+    failures: u32,
+}
+
+#[near_bindgen]
+impl Joiner {
+    pub fn query(&self) -> Promise {
+        let gas = Gas(5_000_000_000_000);
+        ext::get_number(env::current_account_id(), 0, gas)
+            .typed::<u8>()
+            .and(ext::get_name(env::current_account_id(), 0, gas).typed::<String>())
+            .and(Promise::new(env::current_account_id()).transfer(1).typed::<()>())
+            .then(ext::on_all(env::current_account_id(), 0, gas))
+    }
+
+    #[private]
+    pub fn on_all(
+        &mut self,
+        #[callback_join] results: (
+            Result<u8, PromiseError>,
+            Result<String, PromiseError>,
+            Result<(), PromiseError>,
+        ),
+    ) {
+        let (number, name, transfer) = results;
+        self.failures += [number.is_err(), name.is_err(), transfer.is_err()]
+            .iter()
+            .filter(|failed| **failed)
+            .count() as u32;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+//! Cross-contract calls through the builders generated by `#[ext_contract]` and `#[near_bindgen]`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, near_bindgen, AccountId, Gas, Promise};
+
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_balance_of(&self, account_id: AccountId) -> U128;
+    #[result_serializer(borsh)]
+    fn raw(&self, #[serializer(borsh)] data: Vec<u8>) -> Vec<u8>;
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Payer {
+    token: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl Payer {
+    #[init]
+    pub fn new(token: AccountId) -> Self {
+        Self { token: Some(token) }
+    }
+
+    #[payable]
+    pub fn pay(&mut self, receiver_id: AccountId, amount: U128) -> Promise {
+        let token = self.token.clone().unwrap();
+        ext_ft::ext(token.clone())
+            .with_attached_deposit(1)
+            .with_static_gas(Gas(5_000_000_000_000))
+            .ft_transfer(receiver_id, amount, None)
+            .then(ext_ft::ext(token).with_unused_gas_weight(0).raw(vec![1, 2, 3]))
+            .then(Self::ext(env::current_account_id()).on_paid(amount))
+    }
+
+    #[private]
+    pub fn on_paid(&mut self, amount: U128) -> U128 {
+        amount
+    }
+
+    pub fn deploy(&self, account_id: AccountId, token: AccountId) -> Promise {
+        Promise::new(account_id.clone())
+            .create_account()
+            .then(Self::ext(account_id).with_static_gas(Gas(5_000_000_000_000)).new(token))
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+//! Impl section of a struct which isn't marked with `#[near_bindgen]`, so the `FooContract` proxy
+//! and the `FooExt` call builder the methods are added to are not declared.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Foo {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Foo {
+    pub fn inc(&mut self, by: u32) {
+        self.value += by;
+    }
+}
+
+fn main() {}
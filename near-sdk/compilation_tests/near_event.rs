@@ -0,0 +1,52 @@
+//! Events of a custom standard declared with `near_event`.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use near_sdk::{near_bindgen, near_event, AccountId, PanicOnDefault};
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Greeting<'a> {
+    pub account_id: &'a AccountId,
+    pub message: &'a str,
+}
+
+#[near_event(standard = "greeting", version = "1.0.0")]
+pub enum GreetingEvent<'a> {
+    Greet(Vec<Greeting<'a>>),
+    Clear { account_id: &'a AccountId },
+}
+
+#[near_event(standard = "greeting", version = "1.0.0", event = "greet")]
+pub struct Greet<'a>(pub Greeting<'a>);
+
+#[near_event(standard = "greeting", version = "1.0.0")]
+pub struct Cleared {
+    pub count: u32,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+struct Greeter {
+    count: u32,
+}
+
+#[near_bindgen]
+impl Greeter {
+    pub fn greet(&mut self, message: String) {
+        let account_id = near_sdk::env::predecessor_account_id();
+        let greeting = Greeting { account_id: &account_id, message: &message };
+        Greet(greeting).emit();
+        GreetingEvent::Greet(vec![Greeting { account_id: &account_id, message: &message }]).emit();
+        self.count += 1;
+    }
+
+    pub fn clear(&mut self) {
+        let account_id = near_sdk::env::predecessor_account_id();
+        GreetingEvent::Clear { account_id: &account_id }.emit();
+        Cleared { count: self.count }.emit();
+        self.count = 0;
+    }
+}
+
+fn main() {}
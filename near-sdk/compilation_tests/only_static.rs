@@ -0,0 +1,20 @@
+//! Methods restricted with `#[only]` need the contract state.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Vault {
+    balance: u128,
+}
+
+#[near_bindgen]
+impl Vault {
+    #[only(owner)]
+    pub fn version() -> u32 {
+        1
+    }
+}
+
+fn main() {}
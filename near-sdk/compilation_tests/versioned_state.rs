@@ -0,0 +1,46 @@
+//! Smart contract with versioned state and a chain of migrations.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{near_bindgen, PanicOnDefault, VersionedState};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct IncrementerV1 {
+    value: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct IncrementerV2 {
+    value: u32,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault, VersionedState)]
+#[state_version(3)]
+struct Incrementer {
+    value: u32,
+    step: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[init]
+    pub fn new(step: u32) -> Self {
+        Self { value: 0, step }
+    }
+
+    pub fn inc(&mut self) {
+        self.value += self.step;
+    }
+
+    #[migrate(from = 1)]
+    fn migrate_v1(old: IncrementerV1) -> IncrementerV2 {
+        IncrementerV2 { value: old.value.into() }
+    }
+
+    #[migrate(from = 2)]
+    fn migrate_v2(old: IncrementerV2) -> Self {
+        Self { value: old.value, step: 1 }
+    }
+}
+
+fn main() {}
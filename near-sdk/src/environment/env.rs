@@ -10,7 +10,8 @@ use std::{convert::TryFrom, mem::MaybeUninit};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::mock::MockedBlockchain;
 use crate::types::{
-    AccountId, Balance, BlockHeight, Gas, PromiseIndex, PromiseResult, PublicKey, StorageUsage,
+    AccountId, Balance, BlockHeight, Gas, GasWeight, PromiseIndex, PromiseResult, PublicKey,
+    StorageUsage,
 };
 use near_sys as sys;
 
@@ -375,6 +376,66 @@ pub fn ecrecover(
     }
 }
 
+/// Computes `\sum_i scalar_i * point_i` on the alt_bn128 curve.
+///
+/// Panics if any point is not on the curve or any scalar is not in the field.
+///
+/// # Examples
+///
+/// ```
+/// # near_sdk::test_utils::test_env::setup_free();
+/// use near_sdk::alt_bn128::{Fq, Fr, G1Point};
+/// use near_sdk::env;
+///
+/// let generator = G1Point { x: Fq::from(1), y: Fq::from(2) };
+/// let doubled = env::alt_bn128_g1_multiexp(&[(generator, Fr::from(2))]);
+/// assert_eq!(doubled, env::alt_bn128_g1_sum(&[(false, generator), (false, generator)]));
+/// ```
+#[cfg(feature = "unstable")]
+pub fn alt_bn128_g1_multiexp(
+    elements: &[(crate::alt_bn128::G1Point, crate::alt_bn128::Fr)],
+) -> crate::alt_bn128::G1Point {
+    let input = crate::alt_bn128::encode_g1_multiexp_input(elements);
+    //* SAFETY: alt_bn128_g1_multiexp syscall will always generate a 64 byte point inside of the
+    //*         atomic op register so the read will have a sufficient buffer of 64, and can
+    //*         transmute from uninit because all bytes are filled.
+    let res = unsafe {
+        sys::alt_bn128_g1_multiexp(input.len() as _, input.as_ptr() as _, ATOMIC_OP_REGISTER);
+        read_register_fixed_64(ATOMIC_OP_REGISTER)
+    };
+    crate::alt_bn128::G1Point::from_le_bytes(&res)
+}
+
+/// Computes `\sum_i (-1)^{negative_i} * point_i` on the alt_bn128 curve.
+///
+/// Panics if any point is not on the curve.
+#[cfg(feature = "unstable")]
+pub fn alt_bn128_g1_sum(
+    elements: &[(bool, crate::alt_bn128::G1Point)],
+) -> crate::alt_bn128::G1Point {
+    let input = crate::alt_bn128::encode_g1_sum_input(elements);
+    //* SAFETY: alt_bn128_g1_sum syscall will always generate a 64 byte point inside of the
+    //*         atomic op register so the read will have a sufficient buffer of 64, and can
+    //*         transmute from uninit because all bytes are filled.
+    let res = unsafe {
+        sys::alt_bn128_g1_sum(input.len() as _, input.as_ptr() as _, ATOMIC_OP_REGISTER);
+        read_register_fixed_64(ATOMIC_OP_REGISTER)
+    };
+    crate::alt_bn128::G1Point::from_le_bytes(&res)
+}
+
+/// Checks that `\prod_i e(g1_i, g2_i)` equals one, where `e` is the optimal Ate pairing on the
+/// alt_bn128 curve. This is the building block of Groth16 proof verification.
+///
+/// Panics if any point is not on the curve or not in the correct subgroup.
+#[cfg(feature = "unstable")]
+pub fn alt_bn128_pairing_check(
+    elements: &[(crate::alt_bn128::G1Point, crate::alt_bn128::G2Point)],
+) -> bool {
+    let input = crate::alt_bn128::encode_pairing_check_input(elements);
+    unsafe { sys::alt_bn128_pairing_check(input.len() as _, input.as_ptr() as _) == 1 }
+}
+
 // ################
 // # Promises API #
 // ################
@@ -483,6 +544,32 @@ pub fn promise_batch_action_function_call(
     }
 }
 
+/// Appends a function call action to the batch of actions, like
+/// [`promise_batch_action_function_call`], but with a [`GasWeight`]. Besides the static `gas`,
+/// the call receives a share of the gas that is left unused when the current execution finishes,
+/// proportional to `weight` relative to the weights of all other function calls it schedules.
+pub fn promise_batch_action_function_call_weight(
+    promise_index: PromiseIndex,
+    function_name: &str,
+    arguments: &[u8],
+    amount: Balance,
+    gas: Gas,
+    weight: GasWeight,
+) {
+    unsafe {
+        sys::promise_batch_action_function_call_weight(
+            promise_index,
+            function_name.len() as _,
+            function_name.as_ptr() as _,
+            arguments.len() as _,
+            arguments.as_ptr() as _,
+            &amount as *const Balance as _,
+            gas.0,
+            weight.0,
+        )
+    }
+}
+
 pub fn promise_batch_action_transfer(promise_index: PromiseIndex, amount: Balance) {
     unsafe { sys::promise_batch_action_transfer(promise_index, &amount as *const Balance as _) }
 }
@@ -964,6 +1051,56 @@ mod tests {
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_alt_bn128() {
+        use crate::alt_bn128::{Fq, Fq2, Fr, G1Point, G2Point};
+        use crate::test_utils::test_env;
+
+        fn fq(be_hex: &str) -> Fq {
+            let mut bytes = [0u8; 32];
+            hex::decode_to_slice(be_hex, &mut bytes).unwrap();
+            Fq::from_be_bytes(bytes)
+        }
+
+        test_env::setup_free();
+
+        let g1 = G1Point { x: Fq::from(1), y: Fq::from(2) };
+        let neg_g1 = G1Point {
+            x: Fq::from(1),
+            y: fq("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"),
+        };
+        let doubled_g1 = G1Point {
+            x: fq("030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"),
+            y: fq("15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"),
+        };
+        let g2 = G2Point {
+            x: Fq2 {
+                re: fq("1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"),
+                im: fq("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"),
+            },
+            y: Fq2 {
+                re: fq("12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"),
+                im: fq("090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"),
+            },
+        };
+
+        assert_eq!(super::alt_bn128_g1_multiexp(&[(g1, Fr::from(2))]), doubled_g1);
+        assert_eq!(
+            super::alt_bn128_g1_multiexp(&[(g1, Fr::from(1)), (neg_g1, Fr::from(1))]),
+            G1Point::ZERO
+        );
+
+        assert_eq!(super::alt_bn128_g1_sum(&[(false, g1), (false, g1)]), doubled_g1);
+        assert!(super::alt_bn128_g1_sum(&[(false, g1), (true, g1)]).is_zero());
+        assert_eq!(super::alt_bn128_g1_sum(&[(true, g1)]), neg_g1);
+
+        assert!(super::alt_bn128_pairing_check(&[(g1, g2), (neg_g1, g2)]));
+        assert!(!super::alt_bn128_pairing_check(&[(g1, g2), (g1, g2)]));
+        assert!(super::alt_bn128_pairing_check(&[]));
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[test]
     fn signer_public_key() {
@@ -1,6 +1,6 @@
 use super::{Receipt, VmAction};
 use crate::{
-    types::{Balance, Gas},
+    types::{Balance, Gas, GasWeight},
     PublicKey,
 };
 use near_vm_logic::types::AccountId as VmAccountId;
@@ -16,6 +16,9 @@ pub(crate) struct SdkExternal {
     pub fake_trie: HashMap<Vec<u8>, Vec<u8>>,
     pub receipts: Vec<Receipt>,
     pub validators: HashMap<String, Balance>,
+    /// Weight of the unused gas of the next function call action, which is a
+    /// [`VmAction::FunctionCallWeight`] if set.
+    pub next_function_call_weight: Option<GasWeight>,
 }
 
 pub struct MockedValuePtr {
@@ -108,16 +111,25 @@ impl External for SdkExternal {
         attached_deposit: u128,
         prepaid_gas: u64,
     ) -> Result<()> {
-        self.receipts.get_mut(receipt_index as usize).unwrap().actions.push(
-            VmAction::FunctionCall {
-                function_name: String::from_utf8(function_name)
-                    // * Unwrap here is fine because this is only used in mocks
-                    .expect("method name must be utf8 bytes"),
+        // * Unwrap here is fine because this is only used in mocks
+        let function_name =
+            String::from_utf8(function_name).expect("method name must be utf8 bytes");
+        let action = match self.next_function_call_weight.take() {
+            Some(weight) => VmAction::FunctionCallWeight {
+                function_name,
                 args: arguments,
                 deposit: attached_deposit,
                 gas: Gas(prepaid_gas),
+                weight,
             },
-        );
+            None => VmAction::FunctionCall {
+                function_name,
+                args: arguments,
+                deposit: attached_deposit,
+                gas: Gas(prepaid_gas),
+            },
+        };
+        self.receipts.get_mut(receipt_index as usize).unwrap().actions.push(action);
         Ok(())
     }
 
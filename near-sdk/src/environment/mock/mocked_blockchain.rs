@@ -1,6 +1,6 @@
 use super::{Receipt, SdkExternal};
-use crate::test_utils::VMContextBuilder;
-use crate::types::{Balance, PromiseResult};
+use crate::test_utils::{GasProfile, VMContextBuilder};
+use crate::types::{Balance, Gas, PromiseResult};
 use crate::RuntimeFeesConfig;
 use crate::VMContext;
 use near_vm_logic::mocks::mock_memory::MockedMemory;
@@ -16,6 +16,10 @@ use std::collections::HashMap;
 /// message.
 pub struct MockedBlockchain {
     logic: RefCell<VMLogic<'static>>,
+    /// The context the blockchain was created with, kept to take snapshots of the blockchain.
+    context: VMContext,
+    /// Gas burnt by the host functions called on the blockchain.
+    gas_profile: GasProfile,
     // We keep ownership over logic fixture so that references in `VMLogic` are valid.
     #[allow(dead_code)]
     logic_fixture: LogicFixture,
@@ -55,7 +59,7 @@ impl MockedBlockchain {
         memory_opt: Option<Box<dyn MemoryLike>>,
     ) -> Self {
         let mut ext = Box::new(SdkExternal::new());
-        let context = sdk_context_to_vm_context(context);
+        let vm_context = sdk_context_to_vm_context(context.clone());
         ext.fake_trie = storage;
         ext.validators = validators;
         let memory = memory_opt.unwrap_or_else(|| Box::new(MockedMemory {}));
@@ -68,7 +72,7 @@ impl MockedBlockchain {
         let logic = unsafe {
             VMLogic::new_with_protocol_version(
                 &mut *(logic_fixture.ext.as_mut() as *mut dyn External),
-                context,
+                vm_context,
                 &*(logic_fixture.config.as_mut() as *const VMConfig),
                 &*(logic_fixture.fees_config.as_mut() as *const RuntimeFeesConfig),
                 &*(logic_fixture.promise_results.as_ref().as_slice() as *const [VmPromiseResult]),
@@ -78,13 +82,33 @@ impl MockedBlockchain {
         };
 
         let logic = RefCell::new(logic);
-        Self { logic, logic_fixture }
+        Self { logic, context, gas_profile: GasProfile::default(), logic_fixture }
     }
 
     pub fn take_storage(&mut self) -> HashMap<Vec<u8>, Vec<u8>> {
         std::mem::take(&mut self.logic_fixture.ext.fake_trie)
     }
 
+    pub(crate) fn storage(&self) -> &HashMap<Vec<u8>, Vec<u8>> {
+        &self.logic_fixture.ext.fake_trie
+    }
+
+    pub(crate) fn context(&self) -> &VMContext {
+        &self.context
+    }
+
+    pub(crate) fn config(&self) -> &VMConfig {
+        &self.logic_fixture.config
+    }
+
+    pub(crate) fn fees_config(&self) -> &RuntimeFeesConfig {
+        &self.logic_fixture.fees_config
+    }
+
+    pub(crate) fn validators(&self) -> &HashMap<String, Balance> {
+        &self.logic_fixture.ext.validators
+    }
+
     pub fn created_receipts(&self) -> &Vec<Receipt> {
         &self.logic_fixture.ext.receipts
     }
@@ -99,6 +123,20 @@ impl MockedBlockchain {
     pub fn logs(&self) -> Vec<String> {
         self.logic.borrow().clone_outcome().logs
     }
+
+    pub(crate) fn burnt_gas(&self) -> Gas {
+        Gas(self.logic.borrow().clone_outcome().burnt_gas)
+    }
+
+    pub(crate) fn gas_profile(&self) -> GasProfile {
+        let mut profile = self.gas_profile.clone();
+        profile.total = self.burnt_gas();
+        profile
+    }
+
+    pub(crate) fn gas_profile_mut(&mut self) -> &mut GasProfile {
+        &mut self.gas_profile
+    }
 }
 
 fn sdk_context_to_vm_context(context: VMContext) -> near_vm_logic::VMContext {
@@ -128,6 +166,8 @@ fn sdk_context_to_vm_context(context: VMContext) -> near_vm_logic::VMContext {
 
 #[cfg(not(target_arch = "wasm32"))]
 mod mock_chain {
+    use crate::test_utils::HostFunctionCategory;
+    use crate::{Gas, GasWeight};
     use near_vm_logic::{VMLogic, VMLogicError};
 
     fn with_mock_interface<F, R>(f: F) -> R
@@ -137,6 +177,32 @@ mod mock_chain {
         crate::mock::with_mocked_blockchain(|b| f(&mut b.logic.borrow_mut()).unwrap())
     }
 
+    /// Calls the host function and records the gas it burnt in the gas profile, under `category`
+    /// with the size of its input.
+    fn with_profiled_interface<F, R>(category: HostFunctionCategory, bytes: u64, f: F) -> R
+    where
+        F: FnOnce(&mut VMLogic) -> Result<R, VMLogicError>,
+    {
+        crate::mock::with_mocked_blockchain(|b| {
+            let mut logic = b.logic.borrow_mut();
+            let burnt_before = logic.clone_outcome().burnt_gas;
+            let result = f(&mut logic);
+            let burnt = logic.clone_outcome().burnt_gas.saturating_sub(burnt_before);
+            drop(logic);
+            b.gas_profile.record_host_function(category, bytes, Gas(burnt));
+            result.unwrap()
+        })
+    }
+
+    /// Length of the input of a host function, which is `u64::MAX` for null-terminated strings.
+    fn input_len(len: u64) -> u64 {
+        if len == u64::MAX {
+            0
+        } else {
+            len
+        }
+    }
+
     #[no_mangle]
     extern "C" fn read_register(register_id: u64, ptr: u64) {
         with_mock_interface(|b| b.read_register(register_id, ptr))
@@ -207,19 +273,27 @@ mod mock_chain {
     }
     #[no_mangle]
     extern "C" fn sha256(value_len: u64, value_ptr: u64, register_id: u64) {
-        with_mock_interface(|b| b.sha256(value_len, value_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::Hashing, value_len, |b| {
+            b.sha256(value_len, value_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn keccak256(value_len: u64, value_ptr: u64, register_id: u64) {
-        with_mock_interface(|b| b.keccak256(value_len, value_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::Hashing, value_len, |b| {
+            b.keccak256(value_len, value_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn keccak512(value_len: u64, value_ptr: u64, register_id: u64) {
-        with_mock_interface(|b| b.keccak512(value_len, value_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::Hashing, value_len, |b| {
+            b.keccak512(value_len, value_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn ripemd160(value_len: u64, value_ptr: u64, register_id: u64) {
-        with_mock_interface(|b| b.ripemd160(value_len, value_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::Hashing, value_len, |b| {
+            b.ripemd160(value_len, value_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn ecrecover(
@@ -235,9 +309,26 @@ mod mock_chain {
             b.ecrecover(hash_len, hash_ptr, sig_len, sig_ptr, v, malleability_flag, register_id)
         })
     }
+    #[cfg(feature = "unstable")]
+    #[no_mangle]
+    extern "C" fn alt_bn128_g1_multiexp(value_len: u64, value_ptr: u64, register_id: u64) {
+        with_mock_interface(|b| b.alt_bn128_g1_multiexp(value_len, value_ptr, register_id))
+    }
+    #[cfg(feature = "unstable")]
+    #[no_mangle]
+    extern "C" fn alt_bn128_g1_sum(value_len: u64, value_ptr: u64, register_id: u64) {
+        with_mock_interface(|b| b.alt_bn128_g1_sum(value_len, value_ptr, register_id))
+    }
+    #[cfg(feature = "unstable")]
+    #[no_mangle]
+    extern "C" fn alt_bn128_pairing_check(value_len: u64, value_ptr: u64) -> u64 {
+        with_mock_interface(|b| b.alt_bn128_pairing_check(value_len, value_ptr))
+    }
     #[no_mangle]
     extern "C" fn value_return(value_len: u64, value_ptr: u64) {
-        with_mock_interface(|b| b.value_return(value_len, value_ptr))
+        with_profiled_interface(HostFunctionCategory::ValueReturn, value_len, |b| {
+            b.value_return(value_len, value_ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn panic() -> ! {
@@ -251,11 +342,13 @@ mod mock_chain {
     }
     #[no_mangle]
     extern "C" fn log_utf8(len: u64, ptr: u64) {
-        with_mock_interface(|b| b.log_utf8(len, ptr))
+        with_profiled_interface(HostFunctionCategory::Log, input_len(len), |b| b.log_utf8(len, ptr))
     }
     #[no_mangle]
     extern "C" fn log_utf16(len: u64, ptr: u64) {
-        with_mock_interface(|b| b.log_utf16(len, ptr))
+        with_profiled_interface(HostFunctionCategory::Log, input_len(len), |b| {
+            b.log_utf16(len, ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_create(
@@ -268,18 +361,22 @@ mod mock_chain {
         amount_ptr: u64,
         gas: u64,
     ) -> u64 {
-        with_mock_interface(|b| {
-            b.promise_create(
-                account_id_len,
-                account_id_ptr,
-                function_name_len,
-                function_name_ptr,
-                arguments_len,
-                arguments_ptr,
-                amount_ptr,
-                gas,
-            )
-        })
+        with_profiled_interface(
+            HostFunctionCategory::Promise,
+            account_id_len + function_name_len + arguments_len,
+            |b| {
+                b.promise_create(
+                    account_id_len,
+                    account_id_ptr,
+                    function_name_len,
+                    function_name_ptr,
+                    arguments_len,
+                    arguments_ptr,
+                    amount_ptr,
+                    gas,
+                )
+            },
+        )
     }
     #[no_mangle]
     extern "C" fn promise_then(
@@ -293,27 +390,35 @@ mod mock_chain {
         amount_ptr: u64,
         gas: u64,
     ) -> u64 {
-        with_mock_interface(|b| {
-            b.promise_then(
-                promise_index,
-                account_id_len,
-                account_id_ptr,
-                function_name_len,
-                function_name_ptr,
-                arguments_len,
-                arguments_ptr,
-                amount_ptr,
-                gas,
-            )
-        })
+        with_profiled_interface(
+            HostFunctionCategory::Promise,
+            account_id_len + function_name_len + arguments_len,
+            |b| {
+                b.promise_then(
+                    promise_index,
+                    account_id_len,
+                    account_id_ptr,
+                    function_name_len,
+                    function_name_ptr,
+                    arguments_len,
+                    arguments_ptr,
+                    amount_ptr,
+                    gas,
+                )
+            },
+        )
     }
     #[no_mangle]
     extern "C" fn promise_and(promise_idx_ptr: u64, promise_idx_count: u64) -> u64 {
-        with_mock_interface(|b| b.promise_and(promise_idx_ptr, promise_idx_count))
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| {
+            b.promise_and(promise_idx_ptr, promise_idx_count)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_batch_create(account_id_len: u64, account_id_ptr: u64) -> u64 {
-        with_mock_interface(|b| b.promise_batch_create(account_id_len, account_id_ptr))
+        with_profiled_interface(HostFunctionCategory::Promise, account_id_len, |b| {
+            b.promise_batch_create(account_id_len, account_id_ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_batch_then(
@@ -321,11 +426,15 @@ mod mock_chain {
         account_id_len: u64,
         account_id_ptr: u64,
     ) -> u64 {
-        with_mock_interface(|b| b.promise_batch_then(promise_index, account_id_len, account_id_ptr))
+        with_profiled_interface(HostFunctionCategory::Promise, account_id_len, |b| {
+            b.promise_batch_then(promise_index, account_id_len, account_id_ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_batch_action_create_account(promise_index: u64) {
-        with_mock_interface(|b| b.promise_batch_action_create_account(promise_index))
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| {
+            b.promise_batch_action_create_account(promise_index)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_batch_action_deploy_contract(
@@ -333,7 +442,7 @@ mod mock_chain {
         code_len: u64,
         code_ptr: u64,
     ) {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::Promise, code_len, |b| {
             b.promise_batch_action_deploy_contract(promise_index, code_len, code_ptr)
         })
     }
@@ -347,21 +456,59 @@ mod mock_chain {
         amount_ptr: u64,
         gas: u64,
     ) {
-        with_mock_interface(|b| {
-            b.promise_batch_action_function_call(
-                promise_index,
-                function_name_len,
-                function_name_ptr,
-                arguments_len,
-                arguments_ptr,
-                amount_ptr,
-                gas,
-            )
-        })
+        with_profiled_interface(
+            HostFunctionCategory::Promise,
+            function_name_len + arguments_len,
+            |b| {
+                b.promise_batch_action_function_call(
+                    promise_index,
+                    function_name_len,
+                    function_name_ptr,
+                    arguments_len,
+                    arguments_ptr,
+                    amount_ptr,
+                    gas,
+                )
+            },
+        )
+    }
+    #[no_mangle]
+    extern "C" fn promise_batch_action_function_call_weight(
+        promise_index: u64,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        amount_ptr: u64,
+        gas: u64,
+        weight: u64,
+    ) {
+        // `VMLogic` doesn't support weights, the action is recorded as a
+        // `VmAction::FunctionCallWeight` so that `MockRuntime` can distribute the unused gas.
+        crate::mock::with_mocked_blockchain(|b| {
+            b.logic_fixture.ext.next_function_call_weight = Some(GasWeight(weight))
+        });
+        with_profiled_interface(
+            HostFunctionCategory::Promise,
+            function_name_len + arguments_len,
+            |b| {
+                b.promise_batch_action_function_call(
+                    promise_index,
+                    function_name_len,
+                    function_name_ptr,
+                    arguments_len,
+                    arguments_ptr,
+                    amount_ptr,
+                    gas,
+                )
+            },
+        )
     }
     #[no_mangle]
     extern "C" fn promise_batch_action_transfer(promise_index: u64, amount_ptr: u64) {
-        with_mock_interface(|b| b.promise_batch_action_transfer(promise_index, amount_ptr))
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| {
+            b.promise_batch_action_transfer(promise_index, amount_ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_batch_action_stake(
@@ -370,7 +517,7 @@ mod mock_chain {
         public_key_len: u64,
         public_key_ptr: u64,
     ) {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::Promise, public_key_len, |b| {
             b.promise_batch_action_stake(promise_index, amount_ptr, public_key_len, public_key_ptr)
         })
     }
@@ -381,7 +528,7 @@ mod mock_chain {
         public_key_ptr: u64,
         nonce: u64,
     ) {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::Promise, public_key_len, |b| {
             b.promise_batch_action_add_key_with_full_access(
                 promise_index,
                 public_key_len,
@@ -402,19 +549,23 @@ mod mock_chain {
         function_names_len: u64,
         function_names_ptr: u64,
     ) {
-        with_mock_interface(|b| {
-            b.promise_batch_action_add_key_with_function_call(
-                promise_index,
-                public_key_len,
-                public_key_ptr,
-                nonce,
-                allowance_ptr,
-                receiver_id_len,
-                receiver_id_ptr,
-                function_names_len,
-                function_names_ptr,
-            )
-        })
+        with_profiled_interface(
+            HostFunctionCategory::Promise,
+            public_key_len + receiver_id_len + function_names_len,
+            |b| {
+                b.promise_batch_action_add_key_with_function_call(
+                    promise_index,
+                    public_key_len,
+                    public_key_ptr,
+                    nonce,
+                    allowance_ptr,
+                    receiver_id_len,
+                    receiver_id_ptr,
+                    function_names_len,
+                    function_names_ptr,
+                )
+            },
+        )
     }
     #[no_mangle]
     extern "C" fn promise_batch_action_delete_key(
@@ -422,7 +573,7 @@ mod mock_chain {
         public_key_len: u64,
         public_key_ptr: u64,
     ) {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::Promise, public_key_len, |b| {
             b.promise_batch_action_delete_key(promise_index, public_key_len, public_key_ptr)
         })
     }
@@ -432,7 +583,7 @@ mod mock_chain {
         beneficiary_id_len: u64,
         beneficiary_id_ptr: u64,
     ) {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::Promise, beneficiary_id_len, |b| {
             b.promise_batch_action_delete_account(
                 promise_index,
                 beneficiary_id_len,
@@ -442,15 +593,17 @@ mod mock_chain {
     }
     #[no_mangle]
     extern "C" fn promise_results_count() -> u64 {
-        with_mock_interface(|b| b.promise_results_count())
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| b.promise_results_count())
     }
     #[no_mangle]
     extern "C" fn promise_result(result_idx: u64, register_id: u64) -> u64 {
-        with_mock_interface(|b| b.promise_result(result_idx, register_id))
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| {
+            b.promise_result(result_idx, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn promise_return(promise_id: u64) {
-        with_mock_interface(|b| b.promise_return(promise_id))
+        with_profiled_interface(HostFunctionCategory::Promise, 0, |b| b.promise_return(promise_id))
     }
     #[no_mangle]
     extern "C" fn storage_write(
@@ -460,21 +613,27 @@ mod mock_chain {
         value_ptr: u64,
         register_id: u64,
     ) -> u64 {
-        with_mock_interface(|b| {
+        with_profiled_interface(HostFunctionCategory::StorageWrite, key_len + value_len, |b| {
             b.storage_write(key_len, key_ptr, value_len, value_ptr, register_id)
         })
     }
     #[no_mangle]
     extern "C" fn storage_read(key_len: u64, key_ptr: u64, register_id: u64) -> u64 {
-        with_mock_interface(|b| b.storage_read(key_len, key_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::StorageRead, key_len, |b| {
+            b.storage_read(key_len, key_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn storage_remove(key_len: u64, key_ptr: u64, register_id: u64) -> u64 {
-        with_mock_interface(|b| b.storage_remove(key_len, key_ptr, register_id))
+        with_profiled_interface(HostFunctionCategory::StorageRemove, key_len, |b| {
+            b.storage_remove(key_len, key_ptr, register_id)
+        })
     }
     #[no_mangle]
     extern "C" fn storage_has_key(key_len: u64, key_ptr: u64) -> u64 {
-        with_mock_interface(|b| b.storage_has_key(key_len, key_ptr))
+        with_profiled_interface(HostFunctionCategory::StorageHasKey, key_len, |b| {
+            b.storage_has_key(key_len, key_ptr)
+        })
     }
     #[no_mangle]
     extern "C" fn validator_stake(account_id_len: u64, account_id_ptr: u64, stake_ptr: u64) {
@@ -1,4 +1,4 @@
-use crate::{AccountId, Balance, Gas, PublicKey};
+use crate::{AccountId, Balance, Gas, GasWeight, PublicKey};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Receipt {
@@ -20,6 +20,15 @@ pub enum VmAction {
         gas: Gas,
         deposit: Balance,
     },
+    /// A function call which also receives a share of the unused gas proportional to `weight`,
+    /// see [`Promise::function_call_weight`](crate::Promise::function_call_weight).
+    FunctionCallWeight {
+        function_name: String,
+        args: Vec<u8>,
+        gas: Gas,
+        deposit: Balance,
+        weight: GasWeight,
+    },
     Transfer {
         deposit: Balance,
     },
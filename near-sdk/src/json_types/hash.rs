@@ -40,6 +40,17 @@ impl<'de> de::Deserialize<'de> for Base58CryptoHash {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for Base58CryptoHash {
+    fn schema_name() -> String {
+        "Base58CryptoHash".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(generator)
+    }
+}
+
 impl From<&Base58CryptoHash> for String {
     fn from(hash: &Base58CryptoHash) -> Self {
         bs58::encode(&hash.0).into_string()
@@ -47,6 +47,19 @@ macro_rules! impl_str_type {
                 ))
             }
         }
+
+        #[cfg(feature = "abi")]
+        impl schemars::JsonSchema for $iden {
+            fn schema_name() -> String {
+                stringify!($iden).to_string()
+            }
+
+            fn json_schema(
+                generator: &mut schemars::gen::SchemaGenerator,
+            ) -> schemars::schema::Schema {
+                String::json_schema(generator)
+            }
+        }
     };
 }
 
@@ -50,6 +50,17 @@ mod base64_bytes {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for Base64VecU8 {
+    fn schema_name() -> String {
+        "Base64VecU8".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(generator)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
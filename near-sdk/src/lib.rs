@@ -6,7 +6,8 @@
 extern crate quickcheck;
 
 pub use near_sdk_macros::{
-    ext_contract, metadata, near_bindgen, BorshStorageKey, FunctionError, PanicOnDefault,
+    ext_contract, metadata, near_bindgen, near_event, BorshStorageKey, FunctionError,
+    PanicOnDefault, VersionedState,
 };
 
 #[cfg(feature = "unstable")]
@@ -20,7 +21,9 @@ pub use environment::env;
 pub use near_sys as sys;
 
 mod promise;
-pub use promise::{Promise, PromiseOrValue};
+pub use promise::{JoinAppend, Promise, PromiseJoin, PromiseOrValue, TypedPromise};
+mod promise_spec;
+pub use promise_spec::{PromiseActionSpec, PromiseSpec};
 
 // Private types just used within macro generation, not stable to be used.
 #[doc(hidden)]
@@ -70,3 +73,7 @@ pub use serde;
 
 #[doc(hidden)]
 pub use serde_json;
+
+#[cfg(feature = "abi")]
+#[doc(hidden)]
+pub use schemars;
@@ -0,0 +1,199 @@
+use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use borsh::BorshSchema;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Version of the ABI format.
+const ABI_SCHEMA_SEMVER: &str = "0.1.0";
+
+/// JSON ABI of the contract. Unlike [`Metadata`](super::Metadata), it is not exported from the
+/// wasm binary, it is generated natively through the `contract_abi()` function emitted by the
+/// `metadata!` macro when the `abi` feature is enabled.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ContractAbi {
+    /// Semver of the ABI format.
+    pub schema_version: String,
+    /// ABI of all exported methods.
+    pub functions: Vec<AbiFunction>,
+    /// JSON-schema definitions referenced by the `type_schema` of JSON serialized types.
+    pub definitions: schemars::Map<String, Schema>,
+}
+
+impl ContractAbi {
+    pub fn new(functions: Vec<AbiFunction>, definitions: schemars::Map<String, Schema>) -> Self {
+        Self { schema_version: ABI_SCHEMA_SEMVER.to_string(), functions, definitions }
+    }
+
+    /// Serializes the ABI into a pretty-printed JSON string.
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize the ABI using JSON")
+    }
+}
+
+/// ABI of a single exported method.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AbiFunction {
+    pub name: String,
+    /// Whether method does not modify the state.
+    pub is_view: bool,
+    /// Whether method can be used to initialize the state.
+    pub is_init: bool,
+    /// Whether method accepts an attached deposit.
+    pub is_payable: bool,
+    /// Whether method can only be called by the contract itself.
+    pub is_private: bool,
+    /// Arguments read from the input of the method.
+    pub params: Vec<AbiParameter>,
+    /// Types of each callback argument of the method.
+    pub callbacks: Vec<AbiType>,
+    /// If all callbacks have the same type then this field can be used instead.
+    pub callbacks_vec: Option<AbiType>,
+    /// Type of the returned value.
+    pub result: Option<AbiType>,
+    /// Name of the error type for methods marked with `#[handle_result]`. The error is not part of
+    /// the returned value, the method panics with its message instead.
+    pub error: Option<String>,
+}
+
+/// A single named argument of a method.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AbiParameter {
+    pub name: String,
+    #[serde(flatten)]
+    pub typ: AbiType,
+}
+
+/// Serialization format of a value passed to or returned from a method.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AbiSerializationType {
+    Json,
+    Borsh,
+}
+
+/// Type of a value along with the format it is serialized with.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AbiType {
+    pub serialization_type: AbiSerializationType,
+    /// JSON-schema of the type for JSON values, or the borsh schema rendered as JSON for borsh
+    /// values.
+    pub type_schema: Value,
+}
+
+impl AbiType {
+    /// Type serialized with JSON. Definitions of the nested types are collected in `generator`.
+    pub fn json<T: ?Sized + JsonSchema>(generator: &mut SchemaGenerator) -> Self {
+        let schema = generator.subschema_for::<T>();
+        Self {
+            serialization_type: AbiSerializationType::Json,
+            type_schema: serde_json::to_value(schema)
+                .expect("Failed to serialize the JSON schema of the type"),
+        }
+    }
+
+    /// Type serialized with Borsh.
+    pub fn borsh<T: ?Sized + BorshSchema>() -> Self {
+        Self {
+            serialization_type: AbiSerializationType::Borsh,
+            type_schema: borsh_schema_to_json(&T::schema_container()),
+        }
+    }
+}
+
+impl AbiParameter {
+    pub fn new(name: &str, typ: AbiType) -> Self {
+        Self { name: name.to_string(), typ }
+    }
+}
+
+fn borsh_schema_to_json(container: &BorshSchemaContainer) -> Value {
+    let definitions: serde_json::Map<String, Value> = container
+        .definitions
+        .iter()
+        .map(|(declaration, definition)| {
+            let definition = match definition {
+                Definition::Array { length, elements } => {
+                    json!({ "array": { "length": length, "elements": elements } })
+                }
+                Definition::Sequence { elements } => json!({ "sequence": elements }),
+                Definition::Tuple { elements } => json!({ "tuple": elements }),
+                Definition::Enum { variants } => json!({ "enum": variants }),
+                Definition::Struct { fields } => match fields {
+                    Fields::NamedFields(fields) => json!({ "struct": fields }),
+                    Fields::UnnamedFields(fields) => json!({ "struct": fields }),
+                    Fields::Empty => json!({ "struct": null }),
+                },
+            };
+            (declaration.clone(), definition)
+        })
+        .collect();
+    json!({ "declaration": container.declaration, "definitions": definitions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_types::U128;
+    use crate::AccountId;
+
+    #[test]
+    fn json_types_are_strings() {
+        let mut generator = SchemaGenerator::default();
+        let account = AbiType::json::<AccountId>(&mut generator);
+        let amount = AbiType::json::<U128>(&mut generator);
+        let definitions = generator.take_definitions();
+        assert_eq!(account.type_schema, json!({ "$ref": "#/definitions/AccountId" }));
+        assert_eq!(amount.type_schema, json!({ "$ref": "#/definitions/U128" }));
+        for name in ["AccountId", "U128"] {
+            assert_eq!(
+                serde_json::to_value(&definitions[name]).unwrap(),
+                json!({ "type": "string" })
+            );
+        }
+    }
+
+    #[test]
+    fn borsh_type() {
+        let typ = AbiType::borsh::<(u8, Vec<u64>)>();
+        assert_eq!(typ.serialization_type, AbiSerializationType::Borsh);
+        assert_eq!(
+            typ.type_schema,
+            json!({
+                "declaration": "Tuple<u8, Vec<u64>>",
+                "definitions": {
+                    "Tuple<u8, Vec<u64>>": { "tuple": ["u8", "Vec<u64>"] },
+                    "Vec<u64>": { "sequence": "u64" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn abi_json_layout() {
+        let mut generator = SchemaGenerator::default();
+        let abi = ContractAbi::new(
+            vec![AbiFunction {
+                name: "get".to_string(),
+                is_view: true,
+                is_init: false,
+                is_payable: false,
+                is_private: false,
+                params: vec![AbiParameter::new("key", AbiType::json::<String>(&mut generator))],
+                callbacks: vec![],
+                callbacks_vec: None,
+                result: Some(AbiType::json::<Option<u64>>(&mut generator)),
+                error: None,
+            }],
+            generator.take_definitions(),
+        );
+        let value: Value = serde_json::from_str(&abi.to_json_string()).unwrap();
+        assert_eq!(value["schema_version"], ABI_SCHEMA_SEMVER);
+        assert_eq!(
+            value["functions"][0]["params"][0],
+            json!({ "name": "key", "serialization_type": "json", "type_schema": { "type": "string" } })
+        );
+    }
+}
@@ -2,6 +2,23 @@ mod metadata;
 
 pub use metadata::{Metadata, MethodMetadata};
 
+#[cfg(feature = "abi")]
+mod abi;
+
+#[cfg(feature = "abi")]
+pub use abi::{AbiFunction, AbiParameter, AbiSerializationType, AbiType, ContractAbi};
+
+mod promise_result;
+pub use promise_result::{promise_result_borsh, promise_result_json};
+
+mod versioned_state;
+pub use versioned_state::{
+    migrate_state, read_previous_state, StateVersionWriter, WriteUnversionedState,
+    WriteVersionedState,
+};
+
+pub use crate::utils::storage_key_impl::nested_storage_key;
+
 use crate::IntoStorageKey;
 use borsh::BorshSerialize;
 
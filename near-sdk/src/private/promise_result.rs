@@ -0,0 +1,34 @@
+use crate::{env, PromiseError, PromiseResult};
+use borsh::BorshDeserialize;
+use serde::de::DeserializeOwned;
+
+fn promise_result_with<T>(
+    result_idx: u64,
+    deserialize: impl FnOnce(&[u8]) -> Result<T, String>,
+) -> Result<T, PromiseError> {
+    match env::promise_result(result_idx) {
+        PromiseResult::Successful(data) => {
+            deserialize(&data).map_err(PromiseError::Deserialization)
+        }
+        PromiseResult::NotReady => Err(PromiseError::NotReady),
+        PromiseResult::Failed => Err(PromiseError::Failed),
+    }
+}
+
+/// Reads the promise result at `result_idx` and deserializes it using JSON. Used by
+/// `#[callback_join]` arguments.
+///
+/// Functions without a return value produce an empty result, which is deserialized as JSON `null`
+/// so that it can be received as `()` or `Option<T>`.
+pub fn promise_result_json<T: DeserializeOwned>(result_idx: u64) -> Result<T, PromiseError> {
+    promise_result_with(result_idx, |data| {
+        let data = if data.is_empty() { b"null".as_ref() } else { data };
+        serde_json::from_slice(data).map_err(|e| e.to_string())
+    })
+}
+
+/// Reads the promise result at `result_idx` and deserializes it using Borsh. Used by
+/// `#[callback_join]` arguments marked with `#[serializer(borsh)]`.
+pub fn promise_result_borsh<T: BorshDeserialize>(result_idx: u64) -> Result<T, PromiseError> {
+    promise_result_with(result_idx, |data| T::try_from_slice(data).map_err(|e| e.to_string()))
+}
@@ -0,0 +1,78 @@
+use crate::utils::versioned_state::{has_state_version, read_state_version, write_state_version};
+use crate::{env, VersionedState};
+use borsh::BorshDeserialize;
+use std::marker::PhantomData;
+
+/// Writes the schema version of the contract state after the `#[init]` methods, and when a method
+/// creates the state with `Default`, if the contract implements [`VersionedState`]. Does nothing
+/// otherwise.
+///
+/// Both cases are resolved through autoref: the method of [`WriteVersionedState`] takes the
+/// writer by reference and is preferred by method resolution when the bound holds, otherwise the
+/// one of [`WriteUnversionedState`] is picked for `&StateVersionWriter<T>`.
+pub struct StateVersionWriter<T>(PhantomData<T>);
+
+impl<T> StateVersionWriter<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait WriteVersionedState {
+    fn write_state_version(&self);
+    fn write_default_state_version(&self);
+}
+
+impl<T: VersionedState> WriteVersionedState for StateVersionWriter<T> {
+    fn write_state_version(&self) {
+        write_state_version(T::STATE_VERSION);
+    }
+
+    /// The state is created with `Default`, which has the layout of the current version. The
+    /// version is only written if none is stored yet.
+    fn write_default_state_version(&self) {
+        if !has_state_version() {
+            write_state_version(T::STATE_VERSION);
+        }
+    }
+}
+
+pub trait WriteUnversionedState {
+    fn write_state_version(&self);
+    fn write_default_state_version(&self);
+}
+
+impl<T> WriteUnversionedState for &StateVersionWriter<T> {
+    fn write_state_version(&self) {}
+    fn write_default_state_version(&self) {}
+}
+
+/// Reads the stored state with the layout of a previous version of the contract.
+pub fn read_previous_state<T: BorshDeserialize>() -> T {
+    env::state_read().unwrap_or_else(|| env::panic_str("The contract is not initialized"))
+}
+
+/// Applies the migrations from the stored schema version up to [`VersionedState::STATE_VERSION`].
+/// `migrate` is called with the version to migrate from and returns `false` if there is no
+/// migration declared for it.
+pub fn migrate_state<T: VersionedState>(mut migrate: impl FnMut(u32) -> bool) {
+    let mut version = read_state_version();
+    if version > T::STATE_VERSION {
+        env::panic_str(&format!(
+            "The state version {} is newer than the contract state version {}",
+            version,
+            T::STATE_VERSION
+        ));
+    }
+    if version == T::STATE_VERSION {
+        return;
+    }
+    while version < T::STATE_VERSION {
+        if !migrate(version) {
+            env::panic_str(&format!("No migration from state version {}", version));
+        }
+        version += 1;
+    }
+    write_state_version(version);
+}
@@ -2,9 +2,10 @@ use borsh::BorshSchema;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Error, Write};
+use std::marker::PhantomData;
 use std::rc::Rc;
 
-use crate::{AccountId, Balance, Gas, PromiseIndex, PublicKey};
+use crate::{AccountId, Balance, Gas, GasWeight, PromiseIndex, PublicKey};
 
 enum PromiseAction {
     CreateAccount,
@@ -17,6 +18,13 @@ enum PromiseAction {
         amount: Balance,
         gas: Gas,
     },
+    FunctionCallWeight {
+        function_name: String,
+        arguments: Vec<u8>,
+        amount: Balance,
+        gas: Gas,
+        weight: GasWeight,
+    },
     Transfer {
         amount: Balance,
     },
@@ -60,6 +68,16 @@ impl PromiseAction {
                     *gas,
                 )
             }
+            FunctionCallWeight { function_name, arguments, amount, gas, weight } => {
+                crate::env::promise_batch_action_function_call_weight(
+                    promise_index,
+                    function_name,
+                    arguments,
+                    *amount,
+                    *gas,
+                    *weight,
+                )
+            }
             Transfer { amount } => {
                 crate::env::promise_batch_action_transfer(promise_index, *amount)
             }
@@ -252,6 +270,26 @@ impl Promise {
         self.add_action(PromiseAction::FunctionCall { function_name, arguments, amount, gas })
     }
 
+    /// A low-level interface for making a function call to the account that this promise acts on.
+    /// Unlike [`Promise::function_call`], the call also receives a share of the unused gas
+    /// proportional to `weight`, on top of the static `gas`.
+    pub fn function_call_weight(
+        self,
+        function_name: String,
+        arguments: Vec<u8>,
+        amount: Balance,
+        gas: Gas,
+        weight: GasWeight,
+    ) -> Self {
+        self.add_action(PromiseAction::FunctionCallWeight {
+            function_name,
+            arguments,
+            amount,
+            gas,
+            weight,
+        })
+    }
+
     /// Transfer tokens to the account that this promise acts on.
     pub fn transfer(self, amount: Balance) -> Self {
         self.add_action(PromiseAction::Transfer { amount })
@@ -399,6 +437,12 @@ impl Promise {
         self
     }
 
+    /// Marks the type of the value this promise resolves to, so that it can be joined with other
+    /// typed promises through [`TypedPromise::and`]. See [`PromiseJoin`] for details.
+    pub fn typed<T>(self) -> TypedPromise<T> {
+        TypedPromise { promise: self, _marker: PhantomData }
+    }
+
     fn construct_recursively(&self) -> PromiseIndex {
         let res = match &self.subtype {
             PromiseSubtype::Single(x) => x.construct_recursively(),
@@ -437,6 +481,22 @@ impl borsh::BorshSerialize for Promise {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for Promise {
+    fn schema_name() -> String {
+        "Promise".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        // The value returned by the promise is not known statically.
+        generator.schema_for_any()
+    }
+}
+
 /// When the method can return either a promise or a value, it can be called with `PromiseOrValue::Promise`
 /// or `PromiseOrValue::Value` to specify which one should be returned.
 /// # Example
@@ -476,6 +536,22 @@ where
     }
 }
 
+#[cfg(feature = "abi")]
+impl<T: schemars::JsonSchema> schemars::JsonSchema for PromiseOrValue<T> {
+    fn schema_name() -> String {
+        T::schema_name()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        // Promise results are not known statically, so only the value is described.
+        generator.subschema_for::<T>()
+    }
+}
+
 impl<T> From<Promise> for PromiseOrValue<T> {
     fn from(promise: Promise) -> Self {
         PromiseOrValue::Promise(promise)
@@ -492,3 +568,138 @@ impl<T: borsh::BorshSerialize> borsh::BorshSerialize for PromiseOrValue<T> {
         }
     }
 }
+
+/// A [`Promise`] that is known to resolve to a value of type `T`. Created with [`Promise::typed`].
+///
+/// The type is not checked against the method that is called, it only carries the expected type
+/// so that joins created with [`TypedPromise::and`] know the types of all of their results.
+pub struct TypedPromise<T> {
+    promise: Promise,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedPromise<T> {
+    /// Joins this promise with another typed promise. The results of both promises will be
+    /// available to the callback scheduled with [`PromiseJoin::then`].
+    pub fn and<U>(self, other: TypedPromise<U>) -> PromiseJoin<(T, U)> {
+        PromiseJoin { promise: self.promise.and(other.promise), _marker: PhantomData }
+    }
+
+    /// Schedules execution of another promise right after this promise finishes executing.
+    pub fn then(self, other: Promise) -> Promise {
+        self.promise.then(other)
+    }
+
+    /// Returns the untyped promise.
+    pub fn into_promise(self) -> Promise {
+        self.promise
+    }
+}
+
+impl<T> From<TypedPromise<T>> for Promise {
+    fn from(promise: TypedPromise<T>) -> Self {
+        promise.promise
+    }
+}
+
+/// A join of typed promises which carries the tuple `R` of the types of their results, in the
+/// order they were joined.
+///
+/// The callback scheduled with [`PromiseJoin::then`] receives the results through an argument
+/// marked with `#[callback_join]`, which has the type of a tuple of `Result<T_i, PromiseError>`.
+/// Unlike `#[callback_vec]`, a failed promise or a result that cannot be deserialized does not
+/// panic, but is reported through [`PromiseError`](crate::PromiseError) for that element only.
+///
+/// ```no_run
+/// # use near_sdk::{env, ext_contract, near_bindgen, Gas, Promise, PromiseError};
+/// # use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+/// #[ext_contract(ext)]
+/// pub trait Ext {
+///     fn get_number(&self) -> u8;
+///     fn get_name(&self) -> String;
+///     fn on_both(
+///         &mut self,
+///         #[callback_join] results: (Result<u8, PromiseError>, Result<String, PromiseError>),
+///     );
+/// }
+///
+/// #[near_bindgen]
+/// #[derive(Default, BorshDeserialize, BorshSerialize)]
+/// pub struct Contract {}
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     pub fn query(&self) -> Promise {
+///         let other: near_sdk::AccountId = "other.near".parse().unwrap();
+///         ext::get_number(other.clone(), 0, Gas(5_000_000_000_000))
+///             .typed::<u8>()
+///             .and(ext::get_name(other, 0, Gas(5_000_000_000_000)).typed::<String>())
+///             .then(ext::on_both(env::current_account_id(), 0, Gas(5_000_000_000_000)))
+///     }
+///
+///     #[private]
+///     pub fn on_both(
+///         &mut self,
+///         #[callback_join] results: (Result<u8, PromiseError>, Result<String, PromiseError>),
+///     ) {
+///         let (number, name) = results;
+///         if let (Ok(number), Ok(name)) = (number, name) {
+///             env::log_str(&format!("{} {}", number, name));
+///         }
+///     }
+/// }
+/// ```
+pub struct PromiseJoin<R> {
+    promise: Promise,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> PromiseJoin<R> {
+    /// Joins another typed promise, appending its result type to the result types of the join.
+    pub fn and<U>(self, other: TypedPromise<U>) -> PromiseJoin<R::Output>
+    where
+        R: JoinAppend<U>,
+    {
+        PromiseJoin { promise: self.promise.and(other.promise), _marker: PhantomData }
+    }
+
+    /// Schedules the callback that receives the results of all joined promises.
+    pub fn then(self, other: Promise) -> Promise {
+        self.promise.then(other)
+    }
+
+    /// Returns the untyped promise.
+    pub fn into_promise(self) -> Promise {
+        self.promise
+    }
+}
+
+impl<R> From<PromiseJoin<R>> for Promise {
+    fn from(join: PromiseJoin<R>) -> Self {
+        join.promise
+    }
+}
+
+/// Appends the type `U` to a tuple of result types of a [`PromiseJoin`].
+pub trait JoinAppend<U> {
+    type Output;
+}
+
+macro_rules! impl_join_append {
+    ($($ty:ident),+) => {
+        impl<$($ty,)+ U> JoinAppend<U> for ($($ty,)+) {
+            type Output = ($($ty,)+ U);
+        }
+    };
+}
+
+impl_join_append!(A, B);
+impl_join_append!(A, B, C);
+impl_join_append!(A, B, C, D);
+impl_join_append!(A, B, C, D, E);
+impl_join_append!(A, B, C, D, E, F);
+impl_join_append!(A, B, C, D, E, F, G);
+impl_join_append!(A, B, C, D, E, F, G, H);
+impl_join_append!(A, B, C, D, E, F, G, H, I);
+impl_join_append!(A, B, C, D, E, F, G, H, I, J);
+impl_join_append!(A, B, C, D, E, F, G, H, I, J, K);
@@ -0,0 +1,457 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+use crate::json_types::{Base64VecU8, U128};
+use crate::{AccountId, Balance, Gas, GasWeight, Promise, PublicKey};
+
+/// An action of a [`PromiseSpec`], mirroring the methods of [`Promise`] which add actions.
+///
+/// Balances are serialized as strings and byte arrays as base64 in JSON, see [`U128`] and
+/// [`Base64VecU8`].
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PromiseActionSpec {
+    CreateAccount,
+    DeployContract {
+        code: Base64VecU8,
+    },
+    FunctionCall {
+        function_name: String,
+        arguments: Base64VecU8,
+        amount: U128,
+        gas: Gas,
+    },
+    /// A function call which also receives a share of the unused gas proportional to `weight`,
+    /// see [`Promise::function_call_weight`].
+    FunctionCallWeight {
+        function_name: String,
+        arguments: Base64VecU8,
+        amount: U128,
+        gas: Gas,
+        weight: u64,
+    },
+    Transfer {
+        amount: U128,
+    },
+    Stake {
+        amount: U128,
+        public_key: PublicKey,
+    },
+    AddFullAccessKey {
+        public_key: PublicKey,
+        #[serde(default)]
+        nonce: u64,
+    },
+    /// Adds an access key restricted to calling the comma separated `function_names` of
+    /// `receiver_id`, or any of its methods if `function_names` is empty.
+    AddAccessKey {
+        public_key: PublicKey,
+        allowance: U128,
+        receiver_id: AccountId,
+        function_names: String,
+        #[serde(default)]
+        nonce: u64,
+    },
+    DeleteKey {
+        public_key: PublicKey,
+    },
+    DeleteAccount {
+        beneficiary_id: AccountId,
+    },
+}
+
+impl PromiseActionSpec {
+    /// Adds the action to `promise`.
+    pub fn add_to(self, promise: Promise) -> Promise {
+        match self {
+            Self::CreateAccount => promise.create_account(),
+            Self::DeployContract { code } => promise.deploy_contract(code.into()),
+            Self::FunctionCall { function_name, arguments, amount, gas } => {
+                promise.function_call(function_name, arguments.into(), amount.into(), gas)
+            }
+            Self::FunctionCallWeight { function_name, arguments, amount, gas, weight } => promise
+                .function_call_weight(
+                    function_name,
+                    arguments.into(),
+                    amount.into(),
+                    gas,
+                    GasWeight(weight),
+                ),
+            Self::Transfer { amount } => promise.transfer(amount.into()),
+            Self::Stake { amount, public_key } => promise.stake(amount.into(), public_key),
+            Self::AddFullAccessKey { public_key, nonce } => {
+                promise.add_full_access_key_with_nonce(public_key, nonce)
+            }
+            Self::AddAccessKey { public_key, allowance, receiver_id, function_names, nonce } => {
+                promise.add_access_key_with_nonce(
+                    public_key,
+                    allowance.into(),
+                    receiver_id,
+                    function_names,
+                    nonce,
+                )
+            }
+            Self::DeleteKey { public_key } => promise.delete_key(public_key),
+            Self::DeleteAccount { beneficiary_id } => promise.delete_account(beneficiary_id),
+        }
+    }
+}
+
+/// A description of a [`Promise`], which unlike a [`Promise`] isn't scheduled when dropped, so
+/// that it can be stored in the state of the contract, inspected, and turned into a [`Promise`]
+/// later with [`PromiseSpec::into_promise`], e.g. for calls which are executed at a later time or
+/// once approved.
+///
+/// It is built with the same methods as a [`Promise`]:
+/// ```
+/// # use near_sdk::{Gas, PromiseSpec};
+/// let spec = PromiseSpec::new("bob_near".parse().unwrap())
+///     .create_account()
+///     .transfer(1000)
+///     .then(PromiseSpec::new("carol_near".parse().unwrap()).function_call(
+///         "on_created".to_string(),
+///         vec![],
+///         0,
+///         Gas(5_000_000_000_000),
+///     ));
+/// // Later, e.g. in another call to the contract:
+/// // spec.into_promise();
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PromiseSpec {
+    /// The `actions` executed on `receiver_id`, after the promise `after` if any.
+    Single {
+        receiver_id: AccountId,
+        actions: Vec<PromiseActionSpec>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<Box<PromiseSpec>>,
+    },
+    /// Both promises, see [`Promise::and`].
+    Joint { promise_a: Box<PromiseSpec>, promise_b: Box<PromiseSpec> },
+}
+
+impl PromiseSpec {
+    /// Create a promise description that acts on the given account.
+    pub fn new(receiver_id: AccountId) -> Self {
+        Self::Single { receiver_id, actions: vec![], after: None }
+    }
+
+    /// Adds `action` to the promise, see the methods below for each action.
+    pub fn add_action(mut self, action: PromiseActionSpec) -> Self {
+        match &mut self {
+            Self::Single { actions, .. } => actions.push(action),
+            Self::Joint { .. } => crate::env::panic_str("Cannot add action to a joint promise."),
+        }
+        self
+    }
+
+    /// Create account on which this promise acts.
+    pub fn create_account(self) -> Self {
+        self.add_action(PromiseActionSpec::CreateAccount)
+    }
+
+    /// Deploy a smart contract to the account on which this promise acts.
+    pub fn deploy_contract(self, code: Vec<u8>) -> Self {
+        self.add_action(PromiseActionSpec::DeployContract { code: code.into() })
+    }
+
+    /// A function call to the account that this promise acts on, see [`Promise::function_call`].
+    pub fn function_call(
+        self,
+        function_name: String,
+        arguments: Vec<u8>,
+        amount: Balance,
+        gas: Gas,
+    ) -> Self {
+        self.add_action(PromiseActionSpec::FunctionCall {
+            function_name,
+            arguments: arguments.into(),
+            amount: amount.into(),
+            gas,
+        })
+    }
+
+    /// A function call to the account that this promise acts on, which also receives a share of
+    /// the unused gas proportional to `weight`, see [`Promise::function_call_weight`].
+    pub fn function_call_weight(
+        self,
+        function_name: String,
+        arguments: Vec<u8>,
+        amount: Balance,
+        gas: Gas,
+        weight: GasWeight,
+    ) -> Self {
+        self.add_action(PromiseActionSpec::FunctionCallWeight {
+            function_name,
+            arguments: arguments.into(),
+            amount: amount.into(),
+            gas,
+            weight: weight.0,
+        })
+    }
+
+    /// Transfer tokens to the account that this promise acts on.
+    pub fn transfer(self, amount: Balance) -> Self {
+        self.add_action(PromiseActionSpec::Transfer { amount: amount.into() })
+    }
+
+    /// Stake the account for the given amount of tokens using the given public key.
+    pub fn stake(self, amount: Balance, public_key: PublicKey) -> Self {
+        self.add_action(PromiseActionSpec::Stake { amount: amount.into(), public_key })
+    }
+
+    /// Add full access key to the given account.
+    pub fn add_full_access_key(self, public_key: PublicKey) -> Self {
+        self.add_full_access_key_with_nonce(public_key, 0)
+    }
+
+    /// Add full access key to the given account with a provided nonce.
+    pub fn add_full_access_key_with_nonce(self, public_key: PublicKey, nonce: u64) -> Self {
+        self.add_action(PromiseActionSpec::AddFullAccessKey { public_key, nonce })
+    }
+
+    /// Add an access key that is restricted to only calling a smart contract on some account using
+    /// only a restricted set of methods, see [`Promise::add_access_key`].
+    pub fn add_access_key(
+        self,
+        public_key: PublicKey,
+        allowance: Balance,
+        receiver_id: AccountId,
+        function_names: String,
+    ) -> Self {
+        self.add_access_key_with_nonce(public_key, allowance, receiver_id, function_names, 0)
+    }
+
+    /// Add an access key with a provided nonce.
+    pub fn add_access_key_with_nonce(
+        self,
+        public_key: PublicKey,
+        allowance: Balance,
+        receiver_id: AccountId,
+        function_names: String,
+        nonce: u64,
+    ) -> Self {
+        self.add_action(PromiseActionSpec::AddAccessKey {
+            public_key,
+            allowance: allowance.into(),
+            receiver_id,
+            function_names,
+            nonce,
+        })
+    }
+
+    /// Delete access key from the given account.
+    pub fn delete_key(self, public_key: PublicKey) -> Self {
+        self.add_action(PromiseActionSpec::DeleteKey { public_key })
+    }
+
+    /// Delete the given account.
+    pub fn delete_account(self, beneficiary_id: AccountId) -> Self {
+        self.add_action(PromiseActionSpec::DeleteAccount { beneficiary_id })
+    }
+
+    /// Merge this promise with another promise, see [`Promise::and`].
+    pub fn and(self, other: PromiseSpec) -> PromiseSpec {
+        Self::Joint { promise_a: Box::new(self), promise_b: Box::new(other) }
+    }
+
+    /// Schedules execution of another promise right after the current promise finish executing,
+    /// see [`Promise::then`].
+    pub fn then(self, mut other: PromiseSpec) -> PromiseSpec {
+        match &mut other {
+            Self::Single { after, .. } => {
+                if after.is_some() {
+                    crate::env::panic_str(
+                        "Cannot callback promise which is already scheduled after another",
+                    );
+                }
+                *after = Some(Box::new(self))
+            }
+            Self::Joint { .. } => crate::env::panic_str("Cannot callback joint promise."),
+        }
+        other
+    }
+
+    /// Returns the [`Promise`] described, which is scheduled once dropped or returned.
+    pub fn into_promise(self) -> Promise {
+        match self {
+            Self::Single { receiver_id, actions, after } => {
+                let promise = actions
+                    .into_iter()
+                    .fold(Promise::new(receiver_id), |promise, action| action.add_to(promise));
+                match after {
+                    Some(after) => after.into_promise().then(promise),
+                    None => promise,
+                }
+            }
+            Self::Joint { promise_a, promise_b } => {
+                promise_a.into_promise().and(promise_b.into_promise())
+            }
+        }
+    }
+}
+
+// The borsh derives don't support recursive types, the implementations below are equivalent to
+// them.
+impl BorshSerialize for PromiseSpec {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            Self::Single { receiver_id, actions, after } => {
+                BorshSerialize::serialize(&0u8, writer)?;
+                BorshSerialize::serialize(receiver_id, writer)?;
+                BorshSerialize::serialize(actions, writer)?;
+                BorshSerialize::serialize(after, writer)
+            }
+            Self::Joint { promise_a, promise_b } => {
+                BorshSerialize::serialize(&1u8, writer)?;
+                BorshSerialize::serialize(promise_a, writer)?;
+                BorshSerialize::serialize(promise_b, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for PromiseSpec {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        match <u8 as BorshDeserialize>::deserialize(buf)? {
+            0 => Ok(Self::Single {
+                receiver_id: BorshDeserialize::deserialize(buf)?,
+                actions: BorshDeserialize::deserialize(buf)?,
+                after: BorshDeserialize::deserialize(buf)?,
+            }),
+            1 => Ok(Self::Joint {
+                promise_a: BorshDeserialize::deserialize(buf)?,
+                promise_b: BorshDeserialize::deserialize(buf)?,
+            }),
+            variant => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unexpected variant index: {}", variant),
+            )),
+        }
+    }
+}
+
+impl From<PromiseSpec> for Promise {
+    fn from(spec: PromiseSpec) -> Self {
+        spec.into_promise()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Receipt, VmAction};
+    use crate::test_utils::{get_created_receipts, VMContextBuilder};
+    use crate::testing_env;
+
+    fn bob() -> AccountId {
+        "bob.near".parse().unwrap()
+    }
+
+    fn carol() -> AccountId {
+        "carol.near".parse().unwrap()
+    }
+
+    #[test]
+    fn builder() {
+        let spec = PromiseSpec::new(bob())
+            .create_account()
+            .transfer(10)
+            .and(PromiseSpec::new(carol()).delete_account(bob()))
+            .then(PromiseSpec::new(bob()).function_call("callback".to_string(), vec![], 0, Gas(1)));
+        let first = PromiseSpec::Joint {
+            promise_a: Box::new(PromiseSpec::Single {
+                receiver_id: bob(),
+                actions: vec![
+                    PromiseActionSpec::CreateAccount,
+                    PromiseActionSpec::Transfer { amount: U128(10) },
+                ],
+                after: None,
+            }),
+            promise_b: Box::new(PromiseSpec::Single {
+                receiver_id: carol(),
+                actions: vec![PromiseActionSpec::DeleteAccount { beneficiary_id: bob() }],
+                after: None,
+            }),
+        };
+        assert_eq!(
+            spec,
+            PromiseSpec::Single {
+                receiver_id: bob(),
+                actions: vec![PromiseActionSpec::FunctionCall {
+                    function_name: "callback".to_string(),
+                    arguments: Base64VecU8(vec![]),
+                    amount: U128(0),
+                    gas: Gas(1),
+                }],
+                after: Some(Box::new(first)),
+            }
+        );
+    }
+
+    #[test]
+    fn json() {
+        let spec = PromiseSpec::new(bob()).transfer(10).then(
+            PromiseSpec::new(carol()).function_call("f".to_string(), b"{}".to_vec(), 0, Gas(5)),
+        );
+        let json = r#"{"type":"single","receiver_id":"carol.near","actions":[{"type":"function_call","function_name":"f","arguments":"e30=","amount":"0","gas":"5"}],"after":{"type":"single","receiver_id":"bob.near","actions":[{"type":"transfer","amount":"10"}]}}"#;
+        assert_eq!(serde_json::to_string(&spec).unwrap(), json);
+        assert_eq!(serde_json::from_str::<PromiseSpec>(json).unwrap(), spec);
+    }
+
+    #[test]
+    fn borsh() {
+        let spec = PromiseSpec::new(bob())
+            .add_full_access_key(
+                "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap(),
+            )
+            .and(PromiseSpec::new(carol()).deploy_contract(vec![0, 1, 2]))
+            .then(PromiseSpec::new(bob()).transfer(1));
+        let bytes = spec.try_to_vec().unwrap();
+        assert_eq!(PromiseSpec::try_from_slice(&bytes).unwrap(), spec);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot callback joint promise.")]
+    fn then_joint() {
+        PromiseSpec::new(bob()).then(PromiseSpec::new(bob()).and(PromiseSpec::new(carol())));
+    }
+
+    #[test]
+    fn into_promise() {
+        testing_env!(VMContextBuilder::new().build());
+        let spec = PromiseSpec::new(bob())
+            .create_account()
+            .transfer(10)
+            .and(PromiseSpec::new(carol()).delete_account(bob()))
+            .then(PromiseSpec::new(bob()).function_call("callback".to_string(), vec![], 0, Gas(1)));
+        drop(spec.into_promise());
+        assert_eq!(
+            get_created_receipts(),
+            vec![
+                Receipt {
+                    receipt_indices: vec![],
+                    receiver_id: bob(),
+                    actions: vec![VmAction::CreateAccount, VmAction::Transfer { deposit: 10 }],
+                },
+                Receipt {
+                    receipt_indices: vec![],
+                    receiver_id: carol(),
+                    actions: vec![VmAction::DeleteAccount { beneficiary_id: bob() }],
+                },
+                Receipt {
+                    receipt_indices: vec![0, 1],
+                    receiver_id: bob(),
+                    actions: vec![VmAction::FunctionCall {
+                        function_name: "callback".to_string(),
+                        args: vec![],
+                        gas: Gas(1),
+                        deposit: 0,
+                    }],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::BinaryHeap;
+use crate::store::vec::Iter;
+
+impl<'a, T> IntoIterator for &'a BinaryHeap<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Extend<T> for BinaryHeap<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iter {
+            self.push(item)
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::iter::FusedIterator;
+use core::ops::{Deref, DerefMut};
+
+use super::BinaryHeap;
+use crate::env;
+
+const ERR_EMPTY_HEAP: &str = "Peeked element of an empty heap";
+
+/// A mutable reference to the greatest element of a [`BinaryHeap`], returned by
+/// [`BinaryHeap::peek_mut`]. If the element was modified, it is moved to its place in the heap
+/// when this is dropped.
+pub struct PeekMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    heap: &'a mut BinaryHeap<T>,
+    /// Whether the element was accessed mutably, and the heap needs to be restored.
+    modified: bool,
+}
+
+impl<'a, T> PeekMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    pub(super) fn new(heap: &'a mut BinaryHeap<T>) -> Self {
+        Self { heap, modified: false }
+    }
+
+    /// Removes the peeked element from the heap and returns it.
+    pub fn pop(mut this: PeekMut<'a, T>) -> T {
+        // The heap is restored by `pop`, no need to do it again on drop.
+        this.modified = false;
+        this.heap.pop().unwrap_or_else(|| env::panic_str(ERR_EMPTY_HEAP))
+    }
+}
+
+impl<'a, T> Deref for PeekMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.heap.peek().unwrap_or_else(|| env::panic_str(ERR_EMPTY_HEAP))
+    }
+}
+
+impl<'a, T> DerefMut for PeekMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        self.modified = true;
+        self.heap.vec.get_mut(0).unwrap_or_else(|| env::panic_str(ERR_EMPTY_HEAP))
+    }
+}
+
+impl<'a, T> Drop for PeekMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    fn drop(&mut self) {
+        if self.modified {
+            self.heap.sift_down(0);
+        }
+    }
+}
+
+/// A consuming iterator over the elements of a [`BinaryHeap`] from the greatest to the smallest,
+/// returned by [`BinaryHeap::into_sorted_iter`].
+pub struct IntoSortedIter<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    heap: BinaryHeap<T>,
+}
+
+impl<T> IntoSortedIter<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    pub(super) fn new(heap: BinaryHeap<T>) -> Self {
+        Self { heap }
+    }
+}
+
+impl<T> Iterator for IntoSortedIter<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.heap.len() as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoSortedIter<T> where T: BorshSerialize + BorshDeserialize + Ord {}
+impl<T> FusedIterator for IntoSortedIter<T> where T: BorshSerialize + BorshDeserialize + Ord {}
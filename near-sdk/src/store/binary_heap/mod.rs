@@ -0,0 +1,476 @@
+mod impls;
+mod iter;
+
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub use self::iter::{IntoSortedIter, PeekMut};
+use super::Vector;
+use crate::IntoStorageKey;
+
+/// A priority queue implemented with a binary heap that stores its content on the trie. The
+/// greatest element, according to its [`Ord`] implementation, is always at the top of the heap.
+///
+/// The heap is backed by a [`Vector`], so each node is loaded from storage lazily and only when
+/// it is compared or moved by an operation, and each node is cached after it's loaded. Pushing or
+/// popping an element reads and writes `O(log n)` nodes.
+///
+/// This implementation will cache all changes and loads and only updates values that are changed
+/// in storage after it's dropped through it's [`Drop`] implementation. These changes can be updated
+/// in storage before the variable is dropped by using [`BinaryHeap::flush`].
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::BinaryHeap;
+///
+/// let mut heap = BinaryHeap::new(b"h");
+/// assert!(heap.is_empty());
+///
+/// heap.push(1);
+/// heap.push(5);
+/// heap.push(2);
+///
+/// assert_eq!(heap.len(), 3);
+/// assert_eq!(heap.peek(), Some(&5));
+///
+/// assert_eq!(heap.pop(), Some(5));
+/// assert_eq!(heap.pop(), Some(2));
+/// assert_eq!(heap.pop(), Some(1));
+/// assert_eq!(heap.pop(), None);
+/// ```
+///
+/// A min-heap can be created by wrapping the elements in [`core::cmp::Reverse`]:
+/// ```
+/// use near_sdk::store::BinaryHeap;
+/// use std::cmp::Reverse;
+///
+/// let mut heap = BinaryHeap::new(b"h");
+/// heap.extend([Reverse(3), Reverse(1), Reverse(2)]);
+///
+/// assert_eq!(heap.pop(), Some(Reverse(1)));
+/// ```
+pub struct BinaryHeap<T>
+where
+    T: BorshSerialize,
+{
+    vec: Vector<T>,
+}
+
+//? Manual implementations needed only because borsh derive is leaking field types
+// https://github.com/near/borsh-rs/issues/41
+impl<T> BorshSerialize for BinaryHeap<T>
+where
+    T: BorshSerialize,
+{
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), borsh::maybestd::io::Error> {
+        BorshSerialize::serialize(&self.vec, writer)
+    }
+}
+
+impl<T> BorshDeserialize for BinaryHeap<T>
+where
+    T: BorshSerialize,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, borsh::maybestd::io::Error> {
+        Ok(Self { vec: BorshDeserialize::deserialize(buf)? })
+    }
+}
+
+impl<T> BinaryHeap<T>
+where
+    T: BorshSerialize,
+{
+    /// Create new heap with zero elements. Prefixes storage accesss with the prefix provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap: BinaryHeap<u8> = BinaryHeap::new(b"h");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { vec: Vector::new(prefix) }
+    }
+
+    /// Returns the number of elements in the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.push(1);
+    /// heap.push(2);
+    /// assert_eq!(heap.len(), 2);
+    /// ```
+    pub fn len(&self) -> u32 {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the heap contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// assert!(heap.is_empty());
+    ///
+    /// heap.push(1);
+    /// assert!(!heap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Removes all elements from the heap. This will remove all storage values for the
+    /// length of the [`BinaryHeap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.push(1);
+    ///
+    /// heap.clear();
+    ///
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Flushes the cache and writes all modified values to storage.
+    pub fn flush(&mut self) {
+        self.vec.flush();
+    }
+}
+
+impl<T> BinaryHeap<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    /// Pushes an element onto the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new length exceeds `u32::MAX`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.push(3);
+    /// heap.push(5);
+    ///
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    pub fn push(&mut self, element: T) {
+        self.vec.push(element);
+        self.sift_up(self.vec.len() - 1);
+    }
+
+    /// Removes the greatest element from the heap and returns it, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.extend([1, 3]);
+    ///
+    /// assert_eq!(heap.pop(), Some(3));
+    /// assert_eq!(heap.pop(), Some(1));
+    /// assert_eq!(heap.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.vec.len().checked_sub(1)?;
+        self.vec.swap(0, last);
+        let top = self.vec.pop();
+        self.sift_down(0);
+        top
+    }
+
+    /// Returns the greatest element of the heap, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// assert_eq!(heap.peek(), None);
+    ///
+    /// heap.extend([1, 5, 2]);
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        self.vec.get(0)
+    }
+
+    /// Returns a mutable reference to the greatest element of the heap, or `None` if it is empty.
+    ///
+    /// If the element is modified, the heap is restored when the returned [`PeekMut`] is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.extend([1, 5, 2]);
+    ///
+    /// if let Some(mut top) = heap.peek_mut() {
+    ///     *top = 0;
+    /// }
+    /// assert_eq!(heap.peek(), Some(&2));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut::new(self))
+        }
+    }
+
+    /// Returns an iterator over the elements of the heap, in arbitrary order. This iterator will
+    /// lazily load any values iterated over from storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.extend([1, 5, 2]);
+    ///
+    /// let mut elements: Vec<_> = heap.iter().copied().collect();
+    /// elements.sort();
+    /// assert_eq!(elements, [1, 2, 5]);
+    /// ```
+    pub fn iter(&self) -> super::vec::Iter<'_, T> {
+        self.vec.iter()
+    }
+
+    /// Consumes the heap and returns an iterator which yields its elements from the greatest to
+    /// the smallest. Each element is removed from the heap, and therefore from storage, as it is
+    /// yielded, and the elements not iterated over are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::BinaryHeap;
+    ///
+    /// let mut heap = BinaryHeap::new(b"h");
+    /// heap.extend([1, 5, 2, 4, 3]);
+    ///
+    /// assert_eq!(heap.into_sorted_iter().collect::<Vec<_>>(), [5, 4, 3, 2, 1]);
+    /// ```
+    pub fn into_sorted_iter(self) -> IntoSortedIter<T> {
+        IntoSortedIter::new(self)
+    }
+
+    /// Moves the element at `index` up the heap until its parent is not smaller.
+    fn sift_up(&mut self, mut index: u32) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.vec[index] <= self.vec[parent] {
+                break;
+            }
+            self.vec.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    /// Moves the element at `index` down the heap until none of its children is greater.
+    fn sift_down(&mut self, mut index: u32) {
+        let len = self.vec.len();
+        loop {
+            let left = match index.checked_mul(2).and_then(|i| i.checked_add(1)) {
+                Some(left) if left < len => left,
+                _ => break,
+            };
+            let right = left + 1;
+            let child = if right < len && self.vec[right] > self.vec[left] { right } else { left };
+            if self.vec[index] >= self.vec[child] {
+                break;
+            }
+            self.vec.swap(index, child);
+            index = child;
+        }
+    }
+}
+
+impl<T> fmt::Debug for BinaryHeap<T>
+where
+    T: BorshSerialize + BorshDeserialize + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BinaryHeap").field("vec", &self.vec).finish()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use rand::{Rng, RngCore, SeedableRng};
+
+    use super::BinaryHeap;
+    use crate::test_utils::test_env::setup_free;
+
+    #[test]
+    fn test_push_pop() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        let mut heap = BinaryHeap::new(b"h".to_vec());
+        let mut baseline = std::collections::BinaryHeap::new();
+        for _ in 0..500 {
+            let value = rng.gen::<u64>();
+            heap.push(value);
+            baseline.push(value);
+            assert_eq!(heap.peek(), baseline.peek());
+        }
+        for _ in 0..501 {
+            assert_eq!(heap.pop(), baseline.pop());
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut heap = BinaryHeap::new(b"h");
+        heap.extend([5u8, 3, 8, 1]);
+
+        // Not modifying the top keeps it in place
+        assert_eq!(*heap.peek_mut().unwrap(), 8);
+        assert_eq!(heap.peek(), Some(&8));
+
+        *heap.peek_mut().unwrap() = 2;
+        assert_eq!(heap.peek(), Some(&5));
+
+        let top = heap.peek_mut().unwrap();
+        assert_eq!(super::PeekMut::pop(top), 5);
+        assert_eq!(heap.into_sorted_iter().collect::<Vec<_>>(), [3, 2, 1]);
+    }
+
+    #[test]
+    fn test_into_sorted_iter() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+        let mut heap = BinaryHeap::new(b"h");
+        let mut baseline = vec![];
+        for _ in 0..200 {
+            let value = rng.gen::<u32>();
+            heap.push(value);
+            baseline.push(value);
+        }
+        baseline.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(heap.into_sorted_iter().collect::<Vec<_>>(), baseline);
+        crate::mock::with_mocked_blockchain(|m| assert!(m.take_storage().is_empty()));
+    }
+
+    #[test]
+    fn test_into_sorted_iter_count() {
+        let mut heap = BinaryHeap::new(b"h");
+        heap.extend([4u8, 9, 1, 7]);
+        heap.flush();
+
+        // Counting yields, and therefore removes, every element.
+        assert_eq!(heap.into_sorted_iter().count(), 4);
+        crate::mock::with_mocked_blockchain(|m| assert!(m.take_storage().is_empty()));
+    }
+
+    #[test]
+    fn test_flush_and_reload() {
+        let mut heap = BinaryHeap::new(b"h");
+        heap.extend([4u8, 9, 1, 7]);
+        heap.flush();
+
+        let serialized = heap.try_to_vec().unwrap();
+        let mut heap = BinaryHeap::<u8>::deserialize(&mut serialized.as_slice()).unwrap();
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.pop(), Some(9));
+        assert_eq!(heap.pop(), Some(7));
+    }
+
+    #[derive(Arbitrary, Debug)]
+    enum Op {
+        Push(u8),
+        Pop,
+        Peek,
+        PeekMut(u8),
+        Flush,
+        Reset,
+        Clear,
+    }
+
+    #[test]
+    fn arbitrary() {
+        setup_free();
+
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        let mut buf = vec![0; 4096];
+        for _ in 0..1024 {
+            // Clear storage in-between runs
+            crate::mock::with_mocked_blockchain(|b| b.take_storage());
+            rng.fill_bytes(&mut buf);
+
+            let mut sh = BinaryHeap::new(b"h");
+            let mut mh = std::collections::BinaryHeap::new();
+            let u = Unstructured::new(&buf);
+            if let Ok(ops) = Vec::<Op>::arbitrary_take_rest(u) {
+                for op in ops {
+                    match op {
+                        Op::Push(v) => {
+                            sh.push(v);
+                            mh.push(v);
+                            assert_eq!(sh.len() as usize, mh.len());
+                        }
+                        Op::Pop => {
+                            assert_eq!(sh.pop(), mh.pop());
+                            assert_eq!(sh.len() as usize, mh.len());
+                        }
+                        Op::Peek => {
+                            assert_eq!(sh.peek(), mh.peek());
+                        }
+                        Op::PeekMut(v) => {
+                            if let (Some(mut s), Some(mut m)) = (sh.peek_mut(), mh.peek_mut()) {
+                                *s = v;
+                                *m = v;
+                            }
+                        }
+                        Op::Flush => {
+                            sh.flush();
+                        }
+                        Op::Reset => {
+                            let serialized = sh.try_to_vec().unwrap();
+                            sh = BinaryHeap::deserialize(&mut serialized.as_slice()).unwrap();
+                        }
+                        Op::Clear => {
+                            sh.clear();
+                            mh.clear();
+                        }
+                    }
+                }
+            }
+
+            // After all operations, compare both heaps
+            assert!(Iterator::eq(sh.into_sorted_iter(), mh.into_sorted_vec().into_iter().rev()));
+        }
+    }
+}
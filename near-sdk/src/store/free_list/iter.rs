@@ -1,8 +1,9 @@
 use std::iter::FusedIterator;
+use std::ops;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use super::{FreeList, Slot, ERR_INCONSISTENT_STATE};
+use super::{FreeList, FreeListIndex, Slot, ERR_INCONSISTENT_STATE};
 use crate::{env, store::vec};
 
 impl<'a, T> IntoIterator for &'a FreeList<T>
@@ -117,6 +118,81 @@ where
     }
 }
 
+/// An iterator over elements in a range of indices of the storage bucket, along with their
+/// indices. This only yields the occupied entries.
+pub struct Range<'a, T>
+where
+    T: BorshDeserialize + BorshSerialize,
+{
+    /// Values of the bucket, which contain empty and filled cells.
+    values: &'a vec::Vector<Slot<T>>,
+    /// Range of indices left to iterate.
+    range: ops::Range<u32>,
+}
+
+impl<'a, T> Range<'a, T>
+where
+    T: BorshDeserialize + BorshSerialize,
+{
+    pub(super) fn new(bucket: &'a FreeList<T>, range: ops::Range<u32>) -> Self {
+        Self { values: &bucket.elements, range }
+    }
+
+    /// Returns the index of the next cell to iterate from the front, or `None` if the iteration
+    /// is finished.
+    pub fn next_index(&self) -> Option<FreeListIndex> {
+        if self.range.is_empty() {
+            None
+        } else {
+            Some(FreeListIndex(self.range.start))
+        }
+    }
+
+    fn occupied(&self, index: u32) -> Option<(FreeListIndex, &'a T)> {
+        match self.values.get(index) {
+            Some(Slot::Empty { .. }) => None,
+            Some(Slot::Occupied(value)) => Some((FreeListIndex(index), value)),
+            None => env::panic_str(ERR_INCONSISTENT_STATE),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T>
+where
+    T: BorshDeserialize + BorshSerialize,
+{
+    type Item = (FreeListIndex, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.range.next() {
+            if let Some(entry) = self.occupied(index) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.range.len()))
+    }
+}
+
+impl<'a, T> FusedIterator for Range<'a, T> where T: BorshSerialize + BorshDeserialize {}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.range.next_back() {
+            if let Some(entry) = self.occupied(index) {
+                return Some(entry);
+            }
+        }
+        None
+    }
+}
+
 /// An iterator over elements in the storage bucket. This only yields the occupied entries.
 pub struct IterMut<'a, T>
 where
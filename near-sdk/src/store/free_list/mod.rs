@@ -1,11 +1,12 @@
 mod iter;
-pub use self::iter::{Drain, Iter, IterMut};
+pub use self::iter::{Drain, Iter, IterMut, Range};
 
 use super::{Vector, ERR_INCONSISTENT_STATE};
 use crate::{env, IntoStorageKey};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use std::ops::{Bound, RangeBounds};
 use std::{fmt, mem};
 
 /// Index for value within a bucket.
@@ -55,7 +56,7 @@ where
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
-enum Slot<T> {
+pub(crate) enum Slot<T> {
     /// Represents a filled cell of a value in the collection.
     Occupied(T),
     /// Representing that the cell has been removed, points to next empty cell, if one previously
@@ -64,7 +65,7 @@ enum Slot<T> {
 }
 
 impl<T> Slot<T> {
-    fn into_value(self) -> Option<T> {
+    pub(crate) fn into_value(self) -> Option<T> {
         if let Slot::Occupied(value) = self {
             Some(value)
         } else {
@@ -205,6 +206,28 @@ where
         Iter::new(self)
     }
 
+    /// Generates iterator for shared references to each value in the bucket whose index is within
+    /// the range, along with its index. Indices out of the bounds of the bucket are ignored.
+    ///
+    /// Removed values leave empty cells, so the indices of the other values stay consistent and
+    /// can be used to resume an iteration.
+    pub fn range<R: RangeBounds<u32>>(&self, range: R) -> Range<T> {
+        let len = self.elements.len();
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.saturating_add(1),
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => len,
+        }
+        .min(len);
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        }
+        .min(end);
+        Range::new(self, start..end)
+    }
+
     /// Generates iterator for exclusive references to each value in the bucket.
     pub fn iter_mut(&mut self) -> IterMut<T> {
         IterMut::new(self)
@@ -271,6 +294,24 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn bucket_range() {
+        let mut bucket = FreeList::new(b"b");
+        let indices: Vec<_> = (0..5u8).map(|i| bucket.insert(i)).collect();
+        bucket.remove(indices[1]);
+
+        let range: Vec<_> = bucket.range(..).collect();
+        assert_eq!(range, [(indices[0], &0), (indices[2], &2), (indices[3], &3), (indices[4], &4)]);
+        assert_eq!(bucket.range(1..=3).rev().map(|(_, v)| *v).collect::<Vec<_>>(), [3, 2]);
+        assert_eq!(bucket.range(4..2).next(), None);
+        assert_eq!(bucket.range(10..).next_index(), None);
+
+        let mut range = bucket.range(1..);
+        assert_eq!(range.next_index(), Some(indices[1]));
+        assert_eq!(range.next(), Some((indices[2], &2)));
+        assert_eq!(range.next_index(), Some(indices[3]));
+    }
+
     #[test]
     fn delete_internals() {
         let mut bucket = FreeList::new(b"b");
@@ -1,21 +1,63 @@
 use borsh::BorshSerialize;
+use std::marker::PhantomData;
 
 use crate::env;
 
-mod private {
-    /// Seal `ToKey` implementations to limit usage to the builtin implementations
-    pub trait Sealed {}
-
-    impl Sealed for super::Sha256 {}
-    impl Sealed for super::Keccak256 {}
-    impl Sealed for super::Identity {}
-}
-
 /// Trait used to generate keys to store data based on a serializable structure.
-pub trait ToKey: self::private::Sealed {
+///
+/// Besides the builtin [`Sha256`], [`Keccak256`], [`Identity`], [`Truncated`] and [`Blake2s`]
+/// implementations, this trait can be implemented to use another key scheme, such as a
+/// domain-separated hash. Implementations must uphold the following requirements, which
+/// the collections rely on without checking them:
+///
+/// - The key must be deterministic: the same prefix and serialized key must always generate the
+///   same key, in every execution and every version of the contract, otherwise stored values
+///   become unreachable.
+/// - Different serialized keys with the same prefix must not generate the same key. For hashes,
+///   this means that finding a collision must be infeasible, including for keys chosen by an
+///   attacker, such as account IDs passed to the contract.
+/// - The generated keys should not collide with the storage keys of other collections or state,
+///   which is usually ensured by keeping the prefix of the collection in the hashed input.
+///
+/// Changing the hasher of a collection which already has values in storage makes those values
+/// unreachable, in the same way as changing its prefix.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::borsh::BorshSerialize;
+/// use near_sdk::env;
+/// use near_sdk::store::{key::ToKey, LookupMap};
+///
+/// /// Sha256 hash of the key separated from the other hashes of the contract by a tag.
+/// enum TaggedSha256 {}
+///
+/// impl ToKey for TaggedSha256 {
+///     type KeyType = [u8; 32];
+///
+///     fn to_key<Q: ?Sized>(prefix: &[u8], key: &Q, buffer: &mut Vec<u8>) -> Self::KeyType
+///     where
+///         Q: BorshSerialize,
+///     {
+///         buffer.extend(b"storage:");
+///         buffer.extend(prefix);
+///         key.serialize(buffer).unwrap_or_else(|_| env::abort());
+///
+///         env::sha256_array(buffer)
+///     }
+/// }
+///
+/// let mut map = LookupMap::<u64, String, TaggedSha256>::with_hasher(b"m");
+/// map.insert(1, "a".to_string());
+/// assert_eq!(map.get(&1), Some(&"a".to_string()));
+/// ```
+pub trait ToKey {
     /// Output type for the generated lookup key.
     type KeyType: AsRef<[u8]>;
 
+    /// Generates the storage key of `key` within the collection with the given `prefix`. The
+    /// `buffer` is an empty vector which can be used to serialize the key, to reuse its
+    /// allocation.
     fn to_key<Q: ?Sized>(prefix: &[u8], key: &Q, buffer: &mut Vec<u8>) -> Self::KeyType
     where
         Q: BorshSerialize;
@@ -76,3 +118,218 @@ impl ToKey for Identity {
         buffer.clone()
     }
 }
+
+/// Truncates the keys generated by the hasher `H` to their first `N` bytes, to save storage for
+/// each entry at the cost of a higher probability of collisions. This type satisfies the
+/// [`ToKey`] trait.
+///
+/// `H` must generate 32 byte hashes, such as [`Sha256`] or [`Keccak256`], and `N` can't be
+/// larger than 32. With `N` bytes, an attacker choosing the keys of the collection can find two
+/// keys generating the same storage key in about `2^(4 * N)` hashes, so `N` should be at least
+/// 16 if the keys can be chosen by users, such as account IDs.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::store::{LookupMap, key::{Sha256, Truncated}};
+///
+/// let mut map = LookupMap::<String, u8, Truncated<Sha256, 16>>::with_hasher(b"m");
+/// map.insert("alice.near".to_string(), 1);
+/// assert_eq!(map.get("alice.near"), Some(&1));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Truncated<H, const N: usize> {
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<H, const N: usize> ToKey for Truncated<H, N>
+where
+    H: ToKey<KeyType = [u8; 32]>,
+{
+    type KeyType = [u8; N];
+
+    fn to_key<Q: ?Sized>(prefix: &[u8], key: &Q, buffer: &mut Vec<u8>) -> Self::KeyType
+    where
+        Q: BorshSerialize,
+    {
+        // Hash with the inner hasher, then keep the first `N` bytes of the hash.
+        let hash = H::to_key(prefix, key, buffer);
+        let truncated = hash
+            .get(..N)
+            .unwrap_or_else(|| env::panic_str("Truncated key length is larger than the hash"));
+
+        let mut key = [0; N];
+        key.copy_from_slice(truncated);
+        key
+    }
+}
+
+/// BLAKE2s-256 hash helper which hashes within the contract, without a syscall. This type
+/// satisfies the [`ToKey`] trait.
+///
+/// The keys can be computed the same way off-chain or without a mocked blockchain, for example to
+/// find the storage key of an entry from an indexer. Hashing within the contract is charged as
+/// regular wasm execution instead of the fixed costs of the [`Sha256`] and [`Keccak256`] syscalls.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Blake2s {}
+
+impl ToKey for Blake2s {
+    type KeyType = [u8; 32];
+
+    fn to_key<Q: ?Sized>(prefix: &[u8], key: &Q, buffer: &mut Vec<u8>) -> Self::KeyType
+    where
+        Q: BorshSerialize,
+    {
+        // Prefix the serialized bytes, then hash the combined value.
+        buffer.extend(prefix);
+        key.serialize(buffer).unwrap_or_else(|_| env::abort());
+
+        blake2s_256(buffer)
+    }
+}
+
+const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const BLAKE2S_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Unkeyed BLAKE2s with a 32 byte digest, as specified in RFC 7693.
+fn blake2s_256(data: &[u8]) -> [u8; 32] {
+    let mut h = BLAKE2S_IV;
+    // Parameter block: digest length of 32 bytes, no key, fanout and depth of 1.
+    h[0] ^= 0x0101_0020;
+
+    let mut counter: u64 = 0;
+    let mut chunks = data.chunks(64).peekable();
+    loop {
+        let chunk = chunks.next().unwrap_or(&[]);
+        let last = chunks.peek().is_none();
+        let mut block = [0u8; 64];
+        block[..chunk.len()].copy_from_slice(chunk);
+        counter += chunk.len() as u64;
+        blake2s_compress(&mut h, &block, counter, last);
+        if last {
+            break;
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (bytes, word) in out.chunks_exact_mut(4).zip(h.iter()) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+fn blake2s_compress(h: &mut [u32; 8], block: &[u8; 64], counter: u64, last: bool) {
+    let mut m = [0u32; 16];
+    for (word, bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    let mut v = [0u32; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&BLAKE2S_IV);
+    v[12] ^= counter as u32;
+    v[13] ^= (counter >> 32) as u32;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in BLAKE2S_SIGMA.iter() {
+        blake2s_mix(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        blake2s_mix(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        blake2s_mix(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        blake2s_mix(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        blake2s_mix(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        blake2s_mix(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        blake2s_mix(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        blake2s_mix(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[allow(clippy::many_single_char_names)]
+fn blake2s_mix(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env::setup_free;
+    use quickcheck::QuickCheck;
+
+    #[test]
+    fn blake2s_test_vectors() {
+        assert_eq!(
+            hex::encode(blake2s_256(b"")),
+            "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"
+        );
+        assert_eq!(
+            hex::encode(blake2s_256(b"abc")),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+        // Exactly one block, then the first byte of a second block.
+        assert_eq!(
+            hex::encode(blake2s_256(&[0; 64])),
+            "ae09db7cd54f42b490ef09b6bc541af688e4959bb8c53f359a6f56e38ab454a3"
+        );
+        assert_eq!(
+            hex::encode(blake2s_256(&[0; 65])),
+            "857328bf990b00922782d3e81c6054c25d3375d386c7424abe3e01d79041046c"
+        );
+    }
+
+    #[test]
+    fn prop_truncated_is_hash_prefix() {
+        setup_free();
+
+        fn prop(prefix: Vec<u8>, key: Vec<u8>) -> bool {
+            let hash = Sha256::to_key(&prefix, &key, &mut Vec::new());
+            let truncated = Truncated::<Sha256, 12>::to_key(&prefix, &key, &mut Vec::new());
+            truncated[..] == hash[..12]
+        }
+
+        QuickCheck::new().tests(300).quickcheck(prop as fn(Vec<u8>, Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn prop_distinct_keys_distinct_hashes() {
+        setup_free();
+
+        fn prop(a: String, b: String) -> bool {
+            fn key<H: ToKey>(key: &str) -> Vec<u8> {
+                H::to_key(b"m", key, &mut Vec::new()).as_ref().to_vec()
+            }
+            a == b
+                || (key::<Blake2s>(&a) != key::<Blake2s>(&b)
+                    && key::<Truncated<Sha256, 16>>(&a) != key::<Truncated<Sha256, 16>>(&b)
+                    && key::<Truncated<Keccak256, 16>>(&a) != key::<Truncated<Keccak256, 16>>(&b))
+        }
+
+        QuickCheck::new().tests(300).quickcheck(prop as fn(String, String) -> bool);
+    }
+}
@@ -453,6 +453,21 @@ where
             }
         }
     }
+
+    /// Calls `f` on the cached values modified since the last flush. Used to flush the caches of
+    /// collections nested in the map before flushing the map.
+    pub(crate) fn for_each_modified<F: FnMut(&mut V)>(&mut self, mut f: F) {
+        for v in self.cache.inner().values_mut() {
+            if let Some(val) = v.value.get_mut() {
+                if val.is_modified() {
+                    // The entry is already modified, so this doesn't change its state.
+                    if let Some(value) = val.value_mut() {
+                        f(value);
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -7,6 +7,9 @@ pub use lazy_option::LazyOption;
 pub mod vec;
 pub use vec::Vector;
 
+pub mod vec_deque;
+pub use self::vec_deque::VecDeque;
+
 pub mod lookup_map;
 pub use self::lookup_map::LookupMap;
 
@@ -22,6 +25,15 @@ pub use self::unordered_set::UnorderedSet;
 pub mod tree_map;
 pub use self::tree_map::TreeMap;
 
+pub mod multi_map;
+pub use self::multi_map::MultiMap;
+
+pub mod binary_heap;
+pub use self::binary_heap::BinaryHeap;
+
+mod paginated;
+pub use self::paginated::Paginated;
+
 mod index_map;
 pub(crate) use self::index_map::IndexMap;
 
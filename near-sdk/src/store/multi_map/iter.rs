@@ -0,0 +1,56 @@
+use std::iter::FusedIterator;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::store::key::ToKey;
+use crate::store::{tree_map, TreeMap};
+
+/// An iterator over the values of a key of a [`MultiMap`](super::MultiMap), in ascending order.
+///
+/// This `struct` is created by the `get_all` method on [`MultiMap`](super::MultiMap).
+pub struct GetAll<'a, V>
+where
+    V: BorshSerialize + BorshDeserialize + Ord,
+{
+    /// Values of the key, `None` if the key has no values.
+    values: Option<tree_map::Keys<'a, V>>,
+}
+
+impl<'a, V> GetAll<'a, V>
+where
+    V: BorshSerialize + BorshDeserialize + Ord,
+{
+    pub(super) fn new<H>(set: Option<&'a TreeMap<V, (), H>>) -> Self
+    where
+        H: ToKey,
+    {
+        Self { values: set.map(|set| set.keys()) }
+    }
+}
+
+impl<'a, V> Iterator for GetAll<'a, V>
+where
+    V: BorshSerialize + BorshDeserialize + Ord,
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.values.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.as_ref().map_or((0, Some(0)), |values| values.size_hint())
+    }
+}
+
+impl<'a, V> ExactSizeIterator for GetAll<'a, V> where V: BorshSerialize + BorshDeserialize + Ord {}
+impl<'a, V> FusedIterator for GetAll<'a, V> where V: BorshSerialize + BorshDeserialize + Ord {}
+
+impl<'a, V> DoubleEndedIterator for GetAll<'a, V>
+where
+    V: BorshSerialize + BorshDeserialize + Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.values.as_mut()?.next_back()
+    }
+}
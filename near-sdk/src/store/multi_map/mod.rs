@@ -0,0 +1,364 @@
+mod iter;
+
+pub use self::iter::GetAll;
+
+use std::borrow::Borrow;
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{LookupMap, TreeMap};
+use crate::store::key::{Sha256, ToKey};
+use crate::IntoStorageKey;
+
+/// A multimap which stores any number of values for each key, implemented on top of a
+/// [`LookupMap`] of [`TreeMap`]s. It can be used for secondary indexes, like the tokens of each
+/// owner or the orders at each price.
+///
+/// The values of each key are stored in their own [`TreeMap`], whose prefix is derived from the
+/// prefix of the multimap and the key, so that they can be iterated in ascending order. The
+/// [`TreeMap`] of a key is removed from storage along with its last value.
+///
+/// The keys are hashed with `H` to look them up and to derive the prefixes of their values,
+/// which are also hashed with `H`. The default hash function is [`Sha256`].
+///
+/// The keys themselves can't be iterated, use an [`UnorderedMap`](super::UnorderedMap) of
+/// [`TreeMap`]s for that.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::store::MultiMap;
+///
+/// let mut tokens_per_owner: MultiMap<String, u64> = MultiMap::new(b"t");
+/// tokens_per_owner.insert("alice".to_string(), 3);
+/// tokens_per_owner.insert("alice".to_string(), 1);
+/// tokens_per_owner.insert("bob".to_string(), 2);
+///
+/// assert_eq!(tokens_per_owner.get_all("alice").collect::<Vec<_>>(), [&1, &3]);
+/// assert_eq!(tokens_per_owner.len_of("bob"), 1);
+///
+/// tokens_per_owner.remove("bob", &2);
+/// assert!(!tokens_per_owner.contains_key("bob"));
+/// assert_eq!(tokens_per_owner.get_all("bob").next(), None);
+/// ```
+pub struct MultiMap<K, V, H = Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    prefix: Box<[u8]>,
+    sets: LookupMap<K, TreeMap<V, (), H>, H>,
+}
+
+//? Manual implementations needed only because borsh derive is leaking field types
+// https://github.com/near/borsh-rs/issues/41
+impl<K, V, H> BorshSerialize for MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), borsh::maybestd::io::Error> {
+        BorshSerialize::serialize(&self.prefix, writer)?;
+        BorshSerialize::serialize(&self.sets, writer)?;
+        Ok(())
+    }
+}
+
+impl<K, V, H> BorshDeserialize for MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, borsh::maybestd::io::Error> {
+        Ok(Self {
+            prefix: BorshDeserialize::deserialize(buf)?,
+            sets: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl<K, V, H> fmt::Debug for MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiMap").field("prefix", &self.prefix).finish()
+    }
+}
+
+impl<K, V> MultiMap<K, V, Sha256>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+{
+    /// Create a new multimap. Use `prefix` as a unique prefix for keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::MultiMap;
+    ///
+    /// let mut map: MultiMap<String, u64> = MultiMap::new(b"m");
+    /// ```
+    #[inline]
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self::with_hasher(prefix)
+    }
+}
+
+impl<K, V, H> MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    /// Initialize a [`MultiMap`] with a custom hash function.
+    ///
+    /// # Example
+    /// ```
+    /// use near_sdk::store::{MultiMap, key::Keccak256};
+    ///
+    /// let map = MultiMap::<String, String, Keccak256>::with_hasher(b"m");
+    /// ```
+    pub fn with_hasher<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let prefix = prefix.into_storage_key();
+        let sets_key = [prefix.as_slice(), b"m"].concat();
+        Self { prefix: prefix.into_boxed_slice(), sets: LookupMap::with_hasher(sets_key) }
+    }
+
+    /// The prefix of the [`TreeMap`] storing the values of `key`.
+    fn set_prefix<Q: ?Sized>(&self, key: &Q) -> Vec<u8>
+    where
+        Q: BorshSerialize,
+    {
+        let hash = H::to_key(&[], key, &mut Vec::new());
+        [&self.prefix[..], b"s", hash.as_ref()].concat()
+    }
+}
+
+impl<K, V, H> MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize + Ord + Clone,
+    H: ToKey,
+{
+    /// Inserts a value for the key. Returns `true` if the value wasn't already stored for the
+    /// key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::MultiMap;
+    ///
+    /// let mut map = MultiMap::new(b"m");
+    /// assert!(map.insert("a".to_string(), 1u8));
+    /// assert!(!map.insert("a".to_string(), 1));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        if let Some(set) = self.sets.get_mut(&key) {
+            return set.insert(value, ()).is_none();
+        }
+        let mut set = TreeMap::with_hasher(self.set_prefix(&key));
+        set.insert(value, ());
+        self.sets.set(key, Some(set));
+        true
+    }
+
+    /// Removes a value of the key. Returns `true` if the value was stored for the key. The
+    /// [`TreeMap`] of the key is removed along with its last value.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::MultiMap;
+    ///
+    /// let mut map = MultiMap::new(b"m");
+    /// map.insert("a".to_string(), 1u8);
+    /// assert!(map.remove("a", &1));
+    /// assert!(!map.remove("a", &1));
+    /// assert!(!map.contains_key("a"));
+    /// ```
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let set = match self.sets.get_mut(key) {
+            Some(set) => set,
+            None => return false,
+        };
+        if set.remove(value).is_none() {
+            return false;
+        }
+        if set.is_empty() {
+            // Clear the nodes of the tree, which aren't removed from storage one by one.
+            set.clear();
+            self.sets.set(key.to_owned(), None);
+        }
+        true
+    }
+
+    /// Removes all the values of the key, returning the number of values removed.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn remove_all<Q: ?Sized>(&mut self, key: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        let set = match self.sets.get_mut(key) {
+            Some(set) => set,
+            None => return 0,
+        };
+        let len = set.len();
+        set.clear();
+        self.sets.set(key.to_owned(), None);
+        len
+    }
+
+    /// Returns `true` if the value is stored for the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn contains<Q: ?Sized>(&self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.sets.get(key).map_or(false, |set| set.contains_key(value))
+    }
+
+    /// Returns `true` if at least one value is stored for the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K> + Ord,
+    {
+        self.sets.contains_key(key)
+    }
+
+    /// Returns the number of values stored for the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn len_of<Q: ?Sized>(&self, key: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.sets.get(key).map_or(0, |set| set.len())
+    }
+
+    /// An iterator visiting the values of the key in ascending order.
+    /// The iterator element type is `&'a V`.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`BorshSerialize`] and [`ToOwned<Owned = K>`](ToOwned) on the borrowed form *must* match
+    /// those for the key type.
+    pub fn get_all<Q: ?Sized>(&self, key: &Q) -> GetAll<V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        GetAll::new(self.sets.get(key))
+    }
+}
+
+impl<K, V, H> MultiMap<K, V, H>
+where
+    K: BorshSerialize + Ord,
+    V: BorshSerialize + Ord,
+    H: ToKey,
+{
+    /// Flushes the intermediate values of the map before this is called when the structure is
+    /// [`Drop`]ed. This will write all modified values to storage but keep all cached values
+    /// in memory.
+    pub fn flush(&mut self) {
+        self.sets.for_each_modified(|set| set.flush());
+        self.sets.flush();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::MultiMap;
+    use crate::store::key::Identity;
+    use crate::test_utils::test_env::setup_free;
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    #[test]
+    fn basic_functionality() {
+        let mut map = MultiMap::new(b"m");
+        assert!(map.insert(1u8, 30u32));
+        assert!(map.insert(1, 10));
+        assert!(map.insert(1, 20));
+        assert!(!map.insert(1, 10));
+        assert!(map.insert(2, 10));
+
+        assert_eq!(map.get_all(&1).collect::<Vec<_>>(), [&10, &20, &30]);
+        assert_eq!(map.get_all(&1).rev().collect::<Vec<_>>(), [&30, &20, &10]);
+        assert_eq!(map.get_all(&3).count(), 0);
+        assert_eq!((map.len_of(&1), map.len_of(&2), map.len_of(&3)), (3, 1, 0));
+        assert!(map.contains(&1, &20));
+        assert!(!map.contains(&2, &20));
+
+        assert!(map.remove(&1, &20));
+        assert!(!map.remove(&1, &20));
+        assert!(!map.remove(&3, &20));
+        assert_eq!(map.get_all(&1).collect::<Vec<_>>(), [&10, &30]);
+        assert_eq!(map.remove_all(&1), 2);
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.remove_all(&1), 0);
+        assert!(map.contains_key(&2));
+    }
+
+    #[test]
+    fn empty_sets_are_removed_from_storage() {
+        setup_free();
+        let mut map = MultiMap::<String, u64, Identity>::with_hasher(b"m");
+        map.insert("a".to_string(), 1);
+        map.insert("a".to_string(), 2);
+        map.insert("b".to_string(), 3);
+        map.flush();
+
+        let serialized = map.try_to_vec().unwrap();
+        drop(map);
+
+        let mut map = MultiMap::<String, u64, Identity>::try_from_slice(&serialized).unwrap();
+        assert_eq!(map.get_all("a").collect::<Vec<_>>(), [&1, &2]);
+        map.remove("a", &1);
+        map.remove("a", &2);
+        map.remove_all("b");
+        map.flush();
+
+        crate::mock::with_mocked_blockchain(|m| assert!(m.take_storage().is_empty()));
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// A page of the elements of a collection, returned by the `paginate` methods of
+/// [`UnorderedMap`](super::UnorderedMap) and [`UnorderedSet`](super::UnorderedSet) to implement
+/// enumeration views.
+///
+/// The cursor is the storage index of the next element, which isn't shifted by removals, so that
+/// the next page can be requested after elements were removed without skipping or repeating
+/// elements. Elements inserted while paginating can be placed before the cursor, in cells left
+/// empty by removals, and then won't be part of the next pages.
+///
+/// # Examples
+///
+/// ```
+/// use near_sdk::store::{Paginated, UnorderedMap};
+///
+/// let mut map = UnorderedMap::new(b"m");
+/// map.insert("a".to_string(), 1);
+/// map.insert("b".to_string(), 2);
+/// map.insert("c".to_string(), 3);
+///
+/// let page = map.paginate(None, 2).map(|(key, value)| (key.clone(), *value));
+/// assert_eq!(page.items, [("a".to_string(), 1), ("b".to_string(), 2)]);
+///
+/// map.remove("a");
+/// let page: Paginated<u32> = map.paginate(page.next_cursor, 2).map(|(_, value)| *value);
+/// assert_eq!(page, Paginated { items: vec![3], next_cursor: None });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct Paginated<T> {
+    /// The elements of the page.
+    pub items: Vec<T>,
+    /// The cursor to pass to `paginate` to get the next page, or `None` if this is the last page.
+    pub next_cursor: Option<u32>,
+}
+
+impl<T> Paginated<T> {
+    /// Maps the elements of the page, keeping its cursor.
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Paginated<U> {
+        Paginated { items: self.items.into_iter().map(f).collect(), next_cursor: self.next_cursor }
+    }
+}
@@ -117,8 +117,8 @@ where
 }
 
 #[derive(Clone, BorshSerialize, BorshDeserialize, Debug)]
-struct Node<K> {
-    key: K,                     // key stored in a node
+pub(crate) struct Node<K> {
+    pub(crate) key: K,          // key stored in a node
     lft: Option<FreeListIndex>, // left link of a node
     rgt: Option<FreeListIndex>, // right link of a node
     ht: u32,                    // height of a subtree at a node
@@ -1014,6 +1014,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::key::{Blake2s, Identity, Keccak256, Truncated};
     use crate::test_utils::test_env::setup_free;
     use crate::test_utils::{next_trie_id, test_env};
 
@@ -1849,9 +1850,18 @@ mod tests {
     where
         K: Ord + Clone + BorshSerialize + BorshDeserialize,
         V: Default + BorshSerialize + BorshDeserialize + Clone,
+    {
+        avl_with_hasher(insert, remove)
+    }
+
+    fn avl_with_hasher<K, V, H>(insert: &[(K, V)], remove: &[K]) -> TreeMap<K, V, H>
+    where
+        K: Ord + Clone + BorshSerialize + BorshDeserialize,
+        V: Default + BorshSerialize + BorshDeserialize + Clone,
+        H: ToKey,
     {
         test_env::setup_free();
-        let mut map: TreeMap<K, V, _> = TreeMap::new(next_trie_id());
+        let mut map: TreeMap<K, V, H> = TreeMap::with_hasher(next_trie_id());
         for k in remove {
             map.insert(k.clone(), Default::default());
         }
@@ -1905,6 +1915,29 @@ mod tests {
             .quickcheck(prop as fn(std::vec::Vec<(u32, u32)>, std::vec::Vec<u32>) -> bool);
     }
 
+    #[test]
+    fn prop_avl_vs_rb_hashers() {
+        fn prop_with_hasher<H: ToKey>(insert: &[(u32, u32)], remove: &[u32]) -> bool {
+            let a = avl_with_hasher::<_, _, H>(insert, remove);
+            let b = rb(insert, remove);
+            let v1: Vec<(&u32, &u32)> = a.iter().collect();
+            let v2: Vec<(&u32, &u32)> = b.iter().collect();
+            v1 == v2
+        }
+
+        fn prop(insert: Vec<(u32, u32)>, remove: Vec<u32>) -> bool {
+            prop_with_hasher::<Identity>(&insert, &remove)
+                && prop_with_hasher::<Keccak256>(&insert, &remove)
+                && prop_with_hasher::<Blake2s>(&insert, &remove)
+                && prop_with_hasher::<Truncated<Sha256, 16>>(&insert, &remove)
+                && prop_with_hasher::<Truncated<Keccak256, 8>>(&insert, &remove)
+        }
+
+        QuickCheck::new()
+            .tests(300)
+            .quickcheck(prop as fn(std::vec::Vec<(u32, u32)>, std::vec::Vec<u32>) -> bool);
+    }
+
     #[test]
     fn insert_delete_insert() {
         let mut map = TreeMap::new(b"t");
@@ -1,4 +1,5 @@
 use std::iter::FusedIterator;
+use std::ops::RangeBounds;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
@@ -120,6 +121,80 @@ where
     }
 }
 
+/// An iterator over the elements of a [`UnorderedMap`] within a range of storage indices.
+///
+/// This `struct` is created by the `range` and `iter_from` methods on [`UnorderedMap`].
+pub struct Range<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    /// Keys within the range, along with their indices.
+    keys: free_list::Range<'a, K>,
+    /// Reference to underlying map to lookup values with `keys`.
+    values: &'a LookupMap<K, ValueAndIndex<V>, H>,
+}
+
+impl<'a, K, V, H> Range<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize,
+    V: BorshSerialize,
+    H: ToKey,
+{
+    pub(super) fn new<R: RangeBounds<u32>>(map: &'a UnorderedMap<K, V, H>, range: R) -> Self {
+        Self { keys: map.keys.range(range), values: &map.values }
+    }
+
+    /// Returns the storage index to resume the iteration from with `iter_from`, or `None` if all
+    /// the elements of the range were visited.
+    pub fn next_index(&self) -> Option<u32> {
+        self.keys.next_index().map(|index| index.0)
+    }
+}
+
+impl<'a, K, V, H> Iterator for Range<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, key) = self.keys.next()?;
+        let entry = self.values.get(key).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+
+        Some((key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<'a, K, V, H> FusedIterator for Range<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+}
+
+impl<'a, K, V, H> DoubleEndedIterator for Range<'a, K, V, H>
+where
+    K: BorshSerialize + Ord + BorshDeserialize + Clone,
+    V: BorshSerialize + BorshDeserialize,
+    H: ToKey,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, key) = self.keys.next_back()?;
+        let entry = self.values.get(key).unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE));
+
+        Some((key, &entry.value))
+    }
+}
+
 /// A mutable iterator over elements of a [`UnorderedMap`].
 ///
 /// This `struct` is created by the `iter_mut` method on [`UnorderedMap`].
@@ -3,6 +3,7 @@ mod impls;
 mod iter;
 
 use std::borrow::Borrow;
+use std::ops::RangeBounds;
 use std::{fmt, mem};
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -12,9 +13,9 @@ use crate::{env, IntoStorageKey};
 
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 
-pub use self::iter::{Drain, Iter, IterMut, Keys, Values, ValuesMut};
+pub use self::iter::{Drain, Iter, IterMut, Keys, Range, Values, ValuesMut};
 use super::free_list::FreeListIndex;
-use super::{FreeList, LookupMap, ERR_INCONSISTENT_STATE, ERR_NOT_EXIST};
+use super::{FreeList, LookupMap, Paginated, ERR_INCONSISTENT_STATE, ERR_NOT_EXIST};
 
 /// A lazily loaded storage map that stores its content directly on the storage trie.
 /// This structure is similar to [`near_sdk::store::LookupMap`](crate::store::LookupMap), except
@@ -86,8 +87,8 @@ where
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
-struct ValueAndIndex<V> {
-    value: V,
+pub(crate) struct ValueAndIndex<V> {
+    pub(crate) value: V,
     key_index: FreeListIndex,
 }
 
@@ -276,6 +277,60 @@ where
         Iter::new(self)
     }
 
+    /// An iterator visiting the key-value pairs stored at the indices within `range`, in the order
+    /// of the indices. The iterator element type is `(&'a K, &'a V)`.
+    ///
+    /// The indices are the positions of the keys in storage, which are in the same order as
+    /// [`iter`](Self::iter) and aren't shifted when entries are removed. Unlike skipping elements of
+    /// [`iter`](Self::iter), the entries before the range aren't loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedMap;
+    ///
+    /// let mut map = UnorderedMap::new(b"m");
+    /// map.insert("a".to_string(), 1);
+    /// map.insert("b".to_string(), 2);
+    /// map.insert("c".to_string(), 3);
+    /// map.remove("b");
+    ///
+    /// let entries: Vec<_> = map.range(1..).collect();
+    /// assert_eq!(entries, [(&"c".to_string(), &3)]);
+    /// let entries: Vec<_> = map.range(..3).rev().collect();
+    /// assert_eq!(entries, [(&"c".to_string(), &3), (&"a".to_string(), &1)]);
+    /// ```
+    pub fn range<R>(&self, range: R) -> Range<K, V, H>
+    where
+        K: BorshDeserialize,
+        R: RangeBounds<u32>,
+    {
+        Range::new(self, range)
+    }
+
+    /// An iterator visiting the key-value pairs stored from the index `index`, equivalent to
+    /// [`range(index..)`](Self::range).
+    pub fn iter_from(&self, index: u32) -> Range<K, V, H>
+    where
+        K: BorshDeserialize,
+    {
+        self.range(index..)
+    }
+
+    /// Returns a page of at most `limit` key-value pairs, starting from the `cursor` of the
+    /// previous page, or from the first entry if `None`.
+    ///
+    /// See [`Paginated`] for an example.
+    pub fn paginate(&self, cursor: Option<u32>, limit: usize) -> Paginated<(&K, &V)>
+    where
+        K: BorshDeserialize + Clone,
+        V: BorshDeserialize,
+    {
+        let mut range = self.iter_from(cursor.unwrap_or(0));
+        let items = range.by_ref().take(limit).collect();
+        Paginated { items, next_cursor: range.next_index() }
+    }
+
     /// An iterator visiting all key-value pairs in arbitrary order,
     /// with exclusive references to the values.
     /// The iterator element type is `(&'a K, &'a mut V)`.
@@ -700,6 +755,51 @@ mod tests {
         assert_eq!(map.keys().collect::<Vec<_>>(), [&0, &2, &3]);
     }
 
+    #[test]
+    fn map_range() {
+        let mut map = UnorderedMap::new(b"b");
+        for i in 0..6u8 {
+            map.insert(i, i * 10);
+        }
+        map.remove(&1);
+        map.remove(&4);
+
+        assert_eq!(map.range(1..4).collect::<Vec<_>>(), [(&2, &20), (&3, &30)]);
+        assert_eq!(map.range(..=2).rev().collect::<Vec<_>>(), [(&2, &20), (&0, &0)]);
+        assert_eq!(map.range(5..100).collect::<Vec<_>>(), [(&5, &50)]);
+        assert_eq!(map.iter_from(6).next(), None);
+
+        let mut range = map.iter_from(2);
+        assert_eq!(range.next(), Some((&2, &20)));
+        assert_eq!(range.next_back(), Some((&5, &50)));
+        assert_eq!(range.next_index(), Some(3));
+        assert_eq!(range.next(), Some((&3, &30)));
+        assert_eq!(range.next(), None);
+        assert_eq!(range.next_index(), None);
+    }
+
+    #[test]
+    fn map_paginate() {
+        let mut map = UnorderedMap::new(b"b");
+        for i in 0..5u8 {
+            map.insert(i, i);
+        }
+
+        let page = map.paginate(None, 2);
+        assert_eq!(page.items, [(&0, &0), (&1, &1)]);
+        assert_eq!(page.next_cursor, Some(2));
+
+        // Removing entries doesn't shift the cursor of the next page.
+        map.remove(&0);
+        map.remove(&2);
+        let page = map.paginate(Some(2), 2);
+        assert_eq!(page.items, [(&3, &3), (&4, &4)]);
+        assert_eq!(page.next_cursor, None);
+
+        let json = serde_json::to_string(&page).unwrap();
+        assert_eq!(json, r#"{"items":[[3,3],[4,4]],"next_cursor":null}"#);
+    }
+
     #[derive(Arbitrary, Debug)]
     enum Op {
         Insert(u8, u8),
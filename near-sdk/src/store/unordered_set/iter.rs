@@ -4,6 +4,7 @@ use crate::store::key::ToKey;
 use crate::store::{free_list, LookupMap};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::iter::{Chain, FusedIterator};
+use std::ops::RangeBounds;
 
 impl<'a, T, H> IntoIterator for &'a UnorderedSet<T, H>
 where
@@ -82,6 +83,66 @@ where
     }
 }
 
+/// An iterator over the elements of a [`UnorderedSet`] within a range of storage indices.
+///
+/// This `struct` is created by the [`range`] and [`iter_from`] methods on [`UnorderedSet`].
+/// See their documentation for more.
+///
+/// [`range`]: UnorderedSet::range
+/// [`iter_from`]: UnorderedSet::iter_from
+pub struct Range<'a, T>
+where
+    T: BorshSerialize + Ord + BorshDeserialize,
+{
+    elements: free_list::Range<'a, T>,
+}
+
+impl<'a, T> Range<'a, T>
+where
+    T: BorshSerialize + Ord + BorshDeserialize,
+{
+    pub(super) fn new<H, R>(set: &'a UnorderedSet<T, H>, range: R) -> Self
+    where
+        H: ToKey,
+        R: RangeBounds<u32>,
+    {
+        Self { elements: set.elements.range(range) }
+    }
+
+    /// Returns the storage index to resume the iteration from with
+    /// [`iter_from`](UnorderedSet::iter_from), or `None` if all the elements of the range were
+    /// visited.
+    pub fn next_index(&self) -> Option<u32> {
+        self.elements.next_index().map(|index| index.0)
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T>
+where
+    T: BorshSerialize + Ord + BorshDeserialize,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.elements.next().map(|(_, element)| element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.elements.size_hint()
+    }
+}
+
+impl<'a, T> FusedIterator for Range<'a, T> where T: BorshSerialize + Ord + BorshDeserialize {}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T>
+where
+    T: BorshSerialize + Ord + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.elements.next_back().map(|(_, element)| element)
+    }
+}
+
 /// A lazy iterator producing elements in the difference of `UnorderedSet`s.
 ///
 /// This `struct` is created by the [`difference`] method on [`UnorderedSet`].
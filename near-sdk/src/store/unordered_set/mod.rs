@@ -1,16 +1,17 @@
 mod impls;
 mod iter;
 
-use super::{FreeList, LookupMap, ERR_INCONSISTENT_STATE};
+use super::{FreeList, LookupMap, Paginated, ERR_INCONSISTENT_STATE};
 use crate::store::free_list::FreeListIndex;
 use crate::store::key::{Sha256, ToKey};
 use crate::store::unordered_set::iter::{
-    Difference, Drain, Intersection, Iter, SymmetricDifference, Union,
+    Difference, Drain, Intersection, Iter, Range, SymmetricDifference, Union,
 };
 use crate::{env, IntoStorageKey};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::borrow::Borrow;
 use std::fmt;
+use std::ops::RangeBounds;
 
 /// A lazily loaded storage set that stores its content directly on the storage trie.
 /// This structure is similar to [`near_sdk::store::LookupSet`](crate::store::LookupSet), except
@@ -408,6 +409,57 @@ where
         Iter::new(self)
     }
 
+    /// An iterator visiting the elements stored at the indices within `range`, in the order of the
+    /// indices. The iterator element type is `&'a T`.
+    ///
+    /// The indices are the positions of the elements in storage, which are in the same order as
+    /// [`iter`](Self::iter) and aren't shifted when elements are removed. Unlike skipping elements
+    /// of [`iter`](Self::iter), the elements before the range aren't loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::UnorderedSet;
+    ///
+    /// let mut set = UnorderedSet::new(b"m");
+    /// set.insert("a".to_string());
+    /// set.insert("b".to_string());
+    /// set.insert("c".to_string());
+    /// set.remove("b");
+    ///
+    /// assert_eq!(set.range(1..).collect::<Vec<_>>(), ["c"]);
+    /// assert_eq!(set.range(..3).rev().collect::<Vec<_>>(), ["c", "a"]);
+    /// ```
+    pub fn range<R>(&self, range: R) -> Range<T>
+    where
+        T: BorshDeserialize,
+        R: RangeBounds<u32>,
+    {
+        Range::new(self, range)
+    }
+
+    /// An iterator visiting the elements stored from the index `index`, equivalent to
+    /// [`range(index..)`](Self::range).
+    pub fn iter_from(&self, index: u32) -> Range<T>
+    where
+        T: BorshDeserialize,
+    {
+        self.range(index..)
+    }
+
+    /// Returns a page of at most `limit` elements, starting from the `cursor` of the previous
+    /// page, or from the first element if `None`.
+    ///
+    /// See [`Paginated`] for an example.
+    pub fn paginate(&self, cursor: Option<u32>, limit: usize) -> Paginated<&T>
+    where
+        T: BorshDeserialize,
+    {
+        let mut range = self.iter_from(cursor.unwrap_or(0));
+        let items = range.by_ref().take(limit).collect();
+        Paginated { items, next_cursor: range.next_index() }
+    }
+
     /// Clears the set, returning all elements in an iterator.
     ///
     /// # Examples
@@ -0,0 +1,625 @@
+mod impls;
+mod iter;
+
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub use self::iter::{Iter, IterMut};
+use super::{IndexMap, ERR_INCONSISTENT_STATE};
+use crate::{env, IntoStorageKey};
+
+const ERR_INDEX_OUT_OF_BOUNDS: &str = "Index out of bounds";
+
+fn expect_consistent_state<T>(val: Option<T>) -> T {
+    val.unwrap_or_else(|| env::panic_str(ERR_INCONSISTENT_STATE))
+}
+
+/// A double-ended queue implemented with a ring buffer that stores its content on the trie. This
+/// implementation will load and store values in the underlying storage lazily.
+///
+/// Uses the same index -> element map as [`Vector`](super::Vector), where the indices of the
+/// front and the back of the queue are kept in the structure and wrap around at `u32::MAX`. This
+/// makes pushing and popping at either end `O(1)`, as no other element is moved, unlike removing
+/// the first element of a [`Vector`](super::Vector).
+///
+/// This implementation will cache all changes and loads and only updates values that are changed
+/// in storage after it's dropped through it's [`Drop`] implementation. These changes can be updated
+/// in storage before the variable is dropped by using [`VecDeque::flush`]. During the lifetime of
+/// this type, storage will only be read a maximum of one time per index and only written once per
+/// index unless specifically flushed.
+///
+/// # Examples
+/// ```
+/// use near_sdk::store::VecDeque;
+///
+/// let mut queue = VecDeque::new(b"q");
+/// assert!(queue.is_empty());
+///
+/// queue.push_back(2);
+/// queue.push_back(3);
+/// queue.push_front(1);
+///
+/// assert_eq!(queue.len(), 3);
+/// assert_eq!(queue[0], 1);
+///
+/// assert_eq!(queue.pop_front(), Some(1));
+/// assert_eq!(queue.pop_back(), Some(3));
+/// assert_eq!(queue.len(), 1);
+///
+/// queue.extend([4, 5]);
+/// assert!(Iterator::eq(queue.iter(), [2, 4, 5].iter()));
+/// ```
+pub struct VecDeque<T>
+where
+    T: BorshSerialize,
+{
+    /// Index of the first element of the queue.
+    head: u32,
+    /// Index after the last element of the queue.
+    tail: u32,
+    values: IndexMap<T>,
+}
+
+//? Manual implementations needed only because borsh derive is leaking field types
+// https://github.com/near/borsh-rs/issues/41
+impl<T> BorshSerialize for VecDeque<T>
+where
+    T: BorshSerialize,
+{
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), borsh::maybestd::io::Error> {
+        BorshSerialize::serialize(&self.head, writer)?;
+        BorshSerialize::serialize(&self.tail, writer)?;
+        BorshSerialize::serialize(&self.values, writer)?;
+        Ok(())
+    }
+}
+
+impl<T> BorshDeserialize for VecDeque<T>
+where
+    T: BorshSerialize,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, borsh::maybestd::io::Error> {
+        Ok(Self {
+            head: BorshDeserialize::deserialize(buf)?,
+            tail: BorshDeserialize::deserialize(buf)?,
+            values: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+impl<T> VecDeque<T>
+where
+    T: BorshSerialize,
+{
+    /// Create new queue with zero elements. Prefixes storage accesss with the prefix provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue: VecDeque<u8> = VecDeque::new(b"q");
+    /// ```
+    pub fn new<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { head: 0, tail: 0, values: IndexMap::new(prefix) }
+    }
+
+    /// Returns the number of elements in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.push_back(1);
+    /// queue.push_front(2);
+    /// assert_eq!(queue.len(), 2);
+    /// ```
+    pub fn len(&self) -> u32 {
+        self.tail.wrapping_sub(self.head)
+    }
+
+    /// Returns `true` if the queue contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// assert!(queue.is_empty());
+    ///
+    /// queue.push_back(1);
+    /// assert!(!queue.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Converts a position in the queue to the index of the element in storage.
+    fn storage_index(&self, index: u32) -> u32 {
+        self.head.wrapping_add(index)
+    }
+
+    /// Removes all elements from the queue. This will remove all storage values for the
+    /// length of the [`VecDeque`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.push_back(1);
+    ///
+    /// queue.clear();
+    ///
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        for i in 0..self.len() {
+            self.values.set(self.storage_index(i), None);
+        }
+        self.head = 0;
+        self.tail = 0;
+    }
+
+    /// Flushes the cache and writes all modified values to storage.
+    pub fn flush(&mut self) {
+        self.values.flush();
+    }
+
+    fn expect_capacity(&self) {
+        if self.len() == u32::MAX {
+            env::panic_str(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+    }
+
+    /// Appends an element to the back of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new length exceeds `u32::MAX`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.push_back(1);
+    /// queue.push_back(2);
+    ///
+    /// assert_eq!(queue.back(), Some(&2));
+    /// ```
+    pub fn push_back(&mut self, element: T) {
+        self.expect_capacity();
+        self.values.set(self.tail, Some(element));
+        self.tail = self.tail.wrapping_add(1);
+    }
+
+    /// Prepends an element to the front of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if new length exceeds `u32::MAX`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.push_front(1);
+    /// queue.push_front(2);
+    ///
+    /// assert_eq!(queue.front(), Some(&2));
+    /// ```
+    pub fn push_front(&mut self, element: T) {
+        self.expect_capacity();
+        self.head = self.head.wrapping_sub(1);
+        self.values.set(self.head, Some(element));
+    }
+}
+
+impl<T> VecDeque<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    /// Returns the element at `index` from the front of the queue or `None` if it is not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.push_back(1);
+    /// queue.push_front(0);
+    ///
+    /// assert_eq!(queue.get(1), Some(&1));
+    /// assert_eq!(queue.get(2), None);
+    /// ```
+    pub fn get(&self, index: u32) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        self.values.get(self.storage_index(index))
+    }
+
+    /// Returns a mutable reference to the element at `index` from the front of the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.extend([0, 1, 2]);
+    ///
+    /// if let Some(elem) = queue.get_mut(1) {
+    ///     *elem = 42;
+    /// }
+    ///
+    /// let actual: Vec<_> = queue.iter().cloned().collect();
+    /// assert_eq!(actual, &[0, 42, 2]);
+    /// ```
+    pub fn get_mut(&mut self, index: u32) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+        self.values.get_mut(self.storage_index(index))
+    }
+
+    /// Returns the first element of the queue, or `None` if it is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element of the queue, or `None` if it is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns the last element of the queue, or `None` if it is empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Returns a mutable reference to the last element of the queue, or `None` if it is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+
+    /// Removes the first element of the queue and returns it, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.extend([1, 2]);
+    ///
+    /// assert_eq!(queue.pop_front(), Some(1));
+    /// assert_eq!(queue.pop_front(), Some(2));
+    /// assert_eq!(queue.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let prev = self.values.get_mut_inner(self.head).replace(None);
+        self.head = self.head.wrapping_add(1);
+        Some(expect_consistent_state(prev))
+    }
+
+    /// Removes the last element of the queue and returns it, or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.extend([1, 2]);
+    ///
+    /// assert_eq!(queue.pop_back(), Some(2));
+    /// assert_eq!(queue.pop_back(), Some(1));
+    /// assert_eq!(queue.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.tail = self.tail.wrapping_sub(1);
+        let prev = self.values.get_mut_inner(self.tail).replace(None);
+        Some(expect_consistent_state(prev))
+    }
+
+    /// Returns an iterator over the queue, from the front to the back. This iterator will lazily
+    /// load any values iterated over from storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.extend([1, 2]);
+    /// queue.push_front(0);
+    ///
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn iter(&self) -> Iter<T> {
+        Iter::new(self)
+    }
+
+    /// Returns an iterator over the [`VecDeque`] that allows modifying each value. This iterator
+    /// will lazily load any values iterated over from storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::store::VecDeque;
+    ///
+    /// let mut queue = VecDeque::new(b"q");
+    /// queue.extend([1, 2, 3]);
+    ///
+    /// for elem in queue.iter_mut() {
+    ///     *elem *= 2;
+    /// }
+    ///
+    /// assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        IterMut::new(self)
+    }
+}
+
+impl<T> fmt::Debug for VecDeque<T>
+where
+    T: BorshSerialize + BorshDeserialize + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if cfg!(feature = "expensive-debug") {
+            fmt::Debug::fmt(&self.iter().collect::<Vec<_>>(), f)
+        } else {
+            f.debug_struct("VecDeque")
+                .field("head", &self.head)
+                .field("tail", &self.tail)
+                .field("prefix", &self.values.prefix)
+                .finish()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use rand::{Rng, RngCore, SeedableRng};
+
+    use super::VecDeque;
+    use crate::store::IndexMap;
+    use crate::test_utils::test_env::setup_free;
+
+    #[test]
+    fn test_push_pop() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        let mut queue = VecDeque::new(b"q".to_vec());
+        let mut baseline = std::collections::VecDeque::new();
+        for _ in 0..500 {
+            let value = rng.gen::<u64>();
+            if rng.gen::<bool>() {
+                queue.push_back(value);
+                baseline.push_back(value);
+            } else {
+                queue.push_front(value);
+                baseline.push_front(value);
+            }
+        }
+        let actual: Vec<u64> = queue.iter().cloned().collect();
+        assert_eq!(actual, baseline.iter().cloned().collect::<Vec<_>>());
+        for _ in 0..501 {
+            if rng.gen::<bool>() {
+                assert_eq!(queue.pop_back(), baseline.pop_back());
+            } else {
+                assert_eq!(queue.pop_front(), baseline.pop_front());
+            }
+        }
+        assert!(queue.is_empty());
+        crate::mock::with_mocked_blockchain(|m| assert!(m.take_storage().is_empty()));
+    }
+
+    #[test]
+    fn test_wrapping_indices() {
+        let mut queue = VecDeque::new(b"q");
+        // Starts at index 0, so pushing to the front wraps around to `u32::MAX`.
+        queue.push_front(1u8);
+        queue.push_back(2);
+        queue.push_front(0);
+        assert_eq!(queue.head, u32::MAX - 1);
+        assert_eq!(queue.len(), 3);
+        assert!(Iterator::eq(queue.iter(), [0, 1, 2].iter()));
+        assert_eq!(queue.back(), Some(&2));
+
+        queue.flush();
+        let deserialized = VecDeque::<u8>::try_from_slice(&queue.try_to_vec().unwrap()).unwrap();
+        assert!(Iterator::eq(deserialized.iter(), [0, 1, 2].iter()));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(3);
+        let mut queue = VecDeque::new(b"q".to_vec());
+        for _ in 0..100 {
+            for _ in 0..(rng.gen::<u64>() % 20 + 1) {
+                let value = rng.gen::<u64>();
+                queue.push_front(value);
+            }
+            assert!(!queue.is_empty());
+            queue.clear();
+            assert!(queue.is_empty());
+        }
+        queue.flush();
+        crate::mock::with_mocked_blockchain(|m| assert!(m.take_storage().is_empty()));
+    }
+
+    #[test]
+    fn iterator_checks() {
+        let mut queue = VecDeque::new(b"q");
+        let mut baseline = std::collections::VecDeque::new();
+        for i in 0..10 {
+            queue.push_front(i);
+            baseline.push_front(i);
+        }
+
+        let mut queue_iter = queue.iter();
+        let mut bl_iter = baseline.iter();
+        assert_eq!(queue_iter.next(), bl_iter.next());
+        assert_eq!(queue_iter.next_back(), bl_iter.next_back());
+        assert_eq!(queue_iter.nth(3), bl_iter.nth(3));
+        assert_eq!(queue_iter.nth_back(2), bl_iter.nth_back(2));
+
+        // Check to make sure indexing overflow is handled correctly
+        assert!(queue_iter.nth(5).is_none());
+        assert!(bl_iter.nth(5).is_none());
+
+        assert!(queue_iter.next().is_none());
+        assert!(bl_iter.next().is_none());
+
+        // Count check
+        assert_eq!(queue.iter().count(), baseline.len());
+
+        for (a, b) in queue.iter_mut().rev().zip(baseline.iter_mut().rev()) {
+            *a += 1;
+            *b += 1;
+        }
+        assert!(Iterator::eq(queue.iter(), baseline.iter()));
+    }
+
+    #[test]
+    fn test_debug() {
+        let prefix = b"q".to_vec();
+        let mut queue = VecDeque::new(prefix.clone());
+        queue.extend([1u8, 2, 3]);
+        queue.push_front(0);
+        if cfg!(feature = "expensive-debug") {
+            assert_eq!(format!("{:?}", queue), "[0, 1, 2, 3]");
+        } else {
+            assert_eq!(
+                format!("{:?}", queue),
+                format!(
+                    "VecDeque {{ head: {}, tail: 3, prefix: {:?} }}",
+                    u32::MAX,
+                    queue.values.prefix
+                )
+            );
+        }
+
+        // * The storage is reused in the second part of this test, need to flush
+        queue.flush();
+
+        #[derive(Debug, BorshSerialize, BorshDeserialize)]
+        struct TestType(u8);
+
+        let deserialize_only_queue = VecDeque::<TestType> {
+            head: queue.head,
+            tail: queue.tail,
+            values: IndexMap::new(prefix),
+        };
+        assert_eq!(deserialize_only_queue.get(1).map(|v| v.0), Some(1));
+    }
+
+    #[derive(Arbitrary, Debug)]
+    enum Op {
+        PushBack(u8),
+        PushFront(u8),
+        PopBack,
+        PopFront,
+        Set(u32, u8),
+        Flush,
+        Reset,
+        Get(u32),
+        Clear,
+    }
+
+    #[test]
+    fn arbitrary() {
+        setup_free();
+
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        let mut buf = vec![0; 4096];
+        for _ in 0..1024 {
+            // Clear storage in-between runs
+            crate::mock::with_mocked_blockchain(|b| b.take_storage());
+            rng.fill_bytes(&mut buf);
+
+            let mut sq = VecDeque::new(b"q");
+            let mut mq = std::collections::VecDeque::new();
+            let u = Unstructured::new(&buf);
+            if let Ok(ops) = Vec::<Op>::arbitrary_take_rest(u) {
+                for op in ops {
+                    match op {
+                        Op::PushBack(v) => {
+                            sq.push_back(v);
+                            mq.push_back(v);
+                        }
+                        Op::PushFront(v) => {
+                            sq.push_front(v);
+                            mq.push_front(v);
+                        }
+                        Op::PopBack => {
+                            assert_eq!(sq.pop_back(), mq.pop_back());
+                        }
+                        Op::PopFront => {
+                            assert_eq!(sq.pop_front(), mq.pop_front());
+                        }
+                        Op::Set(k, v) => {
+                            if sq.is_empty() {
+                                continue;
+                            }
+                            let k = k % sq.len();
+
+                            sq[k] = v;
+                            mq[k as usize] = v;
+
+                            // Extra get just to make sure set happened correctly
+                            assert_eq!(sq[k], mq[k as usize]);
+                        }
+                        Op::Flush => {
+                            sq.flush();
+                        }
+                        Op::Reset => {
+                            let serialized = sq.try_to_vec().unwrap();
+                            sq = VecDeque::deserialize(&mut serialized.as_slice()).unwrap();
+                        }
+                        Op::Get(k) => {
+                            let r1 = sq.get(k);
+                            let r2 = mq.get(k as usize);
+                            assert_eq!(r1, r2)
+                        }
+                        Op::Clear => {
+                            sq.clear();
+                            mq.clear();
+                        }
+                    }
+                    assert_eq!(sq.len() as usize, mq.len());
+                }
+            }
+
+            // After all operations, compare both queues
+            assert!(Iterator::eq(sq.iter(), mq.iter()));
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use crate::mock::with_mocked_blockchain;
+use crate::Gas;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Category of the host functions recorded in a [`GasProfile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostFunctionCategory {
+    /// `storage_read`, the bytes are the length of the key.
+    StorageRead,
+    /// `storage_write`, the bytes are the length of the key and the value.
+    StorageWrite,
+    /// `storage_remove`, the bytes are the length of the key.
+    StorageRemove,
+    /// `storage_has_key`, the bytes are the length of the key.
+    StorageHasKey,
+    /// `sha256`, `keccak256`, `keccak512` and `ripemd160`, the bytes are the length of the value.
+    Hashing,
+    /// `log_utf8` and `log_utf16`, the bytes are the length of the message.
+    Log,
+    /// The creation of promises and their actions, and the reading of promise results.
+    Promise,
+    /// `value_return`, the bytes are the length of the value.
+    ValueReturn,
+}
+
+impl HostFunctionCategory {
+    /// The name of the category in the reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StorageRead => "storage_read",
+            Self::StorageWrite => "storage_write",
+            Self::StorageRemove => "storage_remove",
+            Self::StorageHasKey => "storage_has_key",
+            Self::Hashing => "hashing",
+            Self::Log => "log",
+            Self::Promise => "promise",
+            Self::ValueReturn => "value_return",
+        }
+    }
+}
+
+impl fmt::Display for HostFunctionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Gas burnt by the calls to the host functions of a [`HostFunctionCategory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostFunctionCost {
+    /// The number of calls.
+    pub calls: u64,
+    /// The size of the inputs of the calls.
+    pub bytes: u64,
+    /// The gas burnt by the calls.
+    pub gas: Gas,
+}
+
+/// Gas burnt by the calls to a method of the contract.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodCost {
+    /// The number of calls.
+    pub calls: u64,
+    /// The gas burnt by the calls, including the host functions they called.
+    pub gas: Gas,
+}
+
+/// Gas burnt in a unit test, by host function category and by method of the contract, returned by
+/// [`gas_profile`](crate::test_utils::gas_profile).
+///
+/// The text report of the [`Display`](fmt::Display) implementation and the JSON report of
+/// [`GasProfile::to_json`] are ordered by category and method, so that they can be committed and
+/// compared to catch gas regressions. Only the host functions are metered by the mocked blockchain,
+/// the gas of the Wasm instructions of the contract isn't included.
+///
+/// # Example
+///
+/// ```
+/// use near_sdk::env;
+/// use near_sdk::test_utils::{gas_profile, profile_method, HostFunctionCategory};
+///
+/// profile_method("set", || env::storage_write(b"key", b"value"));
+/// let profile = gas_profile();
+/// assert_eq!(profile.host_functions[&HostFunctionCategory::StorageWrite].bytes, 8);
+/// assert_eq!(profile.methods["set"].calls, 1);
+/// println!("{}", profile);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasProfile {
+    /// The gas burnt in total.
+    pub total: Gas,
+    /// The gas burnt by the host functions of each category.
+    pub host_functions: BTreeMap<HostFunctionCategory, HostFunctionCost>,
+    /// The gas burnt by each method of the contract, recorded with
+    /// [`profile_method`](crate::test_utils::profile_method) or by the
+    /// [`MockRuntime`](crate::test_utils::MockRuntime).
+    pub methods: BTreeMap<String, MethodCost>,
+}
+
+/// Change of the gas burnt between two [`GasProfile`]s, see [`GasProfile::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasChange {
+    /// The path of the entry in the JSON report, like `host_functions.storage_write`.
+    pub name: String,
+    /// The gas burnt in the baseline.
+    pub baseline: Gas,
+    /// The gas burnt in the current profile.
+    pub current: Gas,
+}
+
+impl GasProfile {
+    pub(crate) fn record_host_function(
+        &mut self,
+        category: HostFunctionCategory,
+        bytes: u64,
+        gas: Gas,
+    ) {
+        let cost = self.host_functions.entry(category).or_default();
+        cost.calls += 1;
+        cost.bytes += bytes;
+        cost.gas += gas;
+    }
+
+    pub(crate) fn record_method(&mut self, method_name: &str, gas: Gas) {
+        let cost = self.methods.entry(method_name.to_string()).or_default();
+        cost.calls += 1;
+        cost.gas += gas;
+    }
+
+    /// The gas burnt by the host functions which don't belong to a category, like reading the
+    /// context or the registers.
+    pub fn other(&self) -> Gas {
+        let categorized = self.host_functions.values().map(|cost| cost.gas.0).sum::<u64>();
+        Gas(self.total.0.saturating_sub(categorized))
+    }
+
+    /// Adds the gas burnt in `other` to this profile.
+    pub fn merge(&mut self, other: &GasProfile) {
+        self.total += other.total;
+        for (category, cost) in &other.host_functions {
+            let total = self.host_functions.entry(*category).or_default();
+            total.calls += cost.calls;
+            total.bytes += cost.bytes;
+            total.gas += cost.gas;
+        }
+        for (method_name, cost) in &other.methods {
+            let total = self.methods.entry(method_name.clone()).or_default();
+            total.calls += cost.calls;
+            total.gas += cost.gas;
+        }
+    }
+
+    /// Returns the entries whose gas differs from the `baseline`, including the entries missing
+    /// from one of the profiles.
+    pub fn diff(&self, baseline: &GasProfile) -> Vec<GasChange> {
+        let mut changes = vec![];
+        let mut compare = |name: String, baseline: Gas, current: Gas| {
+            if baseline != current {
+                changes.push(GasChange { name, baseline, current });
+            }
+        };
+        compare("total".to_string(), baseline.total, self.total);
+        for category in keys(&baseline.host_functions, &self.host_functions) {
+            compare(
+                format!("host_functions.{}", category),
+                baseline.host_functions.get(category).map_or(Gas(0), |cost| cost.gas),
+                self.host_functions.get(category).map_or(Gas(0), |cost| cost.gas),
+            );
+        }
+        for method_name in keys(&baseline.methods, &self.methods) {
+            compare(
+                format!("methods.{}", method_name),
+                baseline.methods.get(method_name).map_or(Gas(0), |cost| cost.gas),
+                self.methods.get(method_name).map_or(Gas(0), |cost| cost.gas),
+            );
+        }
+        changes
+    }
+
+    /// Serializes the profile as a JSON report.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize the gas profile")
+    }
+
+    /// Deserializes a JSON report written by [`GasProfile::to_json`].
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// The keys of both maps, in order.
+fn keys<'a, K: Ord, V>(a: &'a BTreeMap<K, V>, b: &'a BTreeMap<K, V>) -> Vec<&'a K> {
+    let mut keys: Vec<_> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+impl fmt::Display for GasProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<24}{:>24}", "total", self.total.0)?;
+        writeln!(f, "{:<24}{:>8}{:>12}{:>24}", "host functions", "calls", "bytes", "gas")?;
+        for (category, cost) in &self.host_functions {
+            writeln!(
+                f,
+                "  {:<22}{:>8}{:>12}{:>24}",
+                category.as_str(),
+                cost.calls,
+                cost.bytes,
+                cost.gas.0
+            )?;
+        }
+        writeln!(f, "  {:<22}{:>44}", "other", self.other().0)?;
+        if !self.methods.is_empty() {
+            writeln!(f, "{:<24}{:>8}{:>36}", "methods", "calls", "gas")?;
+            for (method_name, cost) in &self.methods {
+                writeln!(f, "  {:<22}{:>8}{:>36}", method_name, cost.calls, cost.gas.0)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Calls `f`, which calls a method of the contract, and records the gas it burnt under
+/// `method_name` in the [`GasProfile`] of the mocked blockchain.
+pub fn profile_method<R>(method_name: &str, f: impl FnOnce() -> R) -> R {
+    let burnt_before = with_mocked_blockchain(|b| b.burnt_gas());
+    let result = f();
+    with_mocked_blockchain(|b| {
+        let burnt = Gas(b.burnt_gas().0.saturating_sub(burnt_before.0));
+        b.gas_profile_mut().record_method(method_name, burnt);
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env;
+    use crate::test_utils::{gas_profile, VMContextBuilder};
+    use crate::testing_env;
+
+    #[test]
+    fn records_host_functions_and_methods() {
+        testing_env!(VMContextBuilder::new().build());
+        env::storage_write(b"key", b"value");
+        profile_method("get", || {
+            env::storage_read(b"key");
+            env::log_str("read");
+        });
+        profile_method("get", || env::storage_has_key(b"key"));
+        env::sha256(b"hash me");
+
+        let profile = gas_profile();
+        let write = profile.host_functions[&HostFunctionCategory::StorageWrite];
+        assert_eq!((write.calls, write.bytes), (1, 8));
+        assert!(write.gas.0 > 0);
+        assert_eq!(profile.host_functions[&HostFunctionCategory::StorageRead].bytes, 3);
+        assert_eq!(profile.host_functions[&HostFunctionCategory::Log].bytes, 4);
+        assert_eq!(profile.host_functions[&HostFunctionCategory::Hashing].bytes, 7);
+        assert!(!profile.host_functions.contains_key(&HostFunctionCategory::Promise));
+        assert_eq!(profile.methods["get"].calls, 2);
+        assert!(profile.total >= profile.methods["get"].gas);
+        assert!(profile.total.0 >= write.gas.0 + profile.methods["get"].gas.0);
+
+        // A new mocked blockchain starts a new profile.
+        testing_env!(VMContextBuilder::new().build());
+        assert_eq!(gas_profile(), GasProfile::default());
+    }
+
+    #[test]
+    fn json_report_and_diff() {
+        let mut baseline = GasProfile::default();
+        baseline.record_host_function(HostFunctionCategory::StorageWrite, 8, Gas(100));
+        baseline.record_method("set", Gas(150));
+        baseline.total = Gas(200);
+        assert_eq!(GasProfile::from_json(&baseline.to_json()).unwrap(), baseline);
+        assert_eq!(baseline.other(), Gas(100));
+        assert!(baseline.diff(&baseline).is_empty());
+
+        let mut current = baseline.clone();
+        current.merge(&baseline);
+        current.record_method("get", Gas(10));
+        assert_eq!(current.host_functions[&HostFunctionCategory::StorageWrite].calls, 2);
+        assert_eq!(
+            current.diff(&baseline),
+            vec![
+                GasChange { name: "total".into(), baseline: Gas(200), current: Gas(400) },
+                GasChange {
+                    name: "host_functions.storage_write".into(),
+                    baseline: Gas(100),
+                    current: Gas(200)
+                },
+                GasChange { name: "methods.get".into(), baseline: Gas(0), current: Gas(10) },
+                GasChange { name: "methods.set".into(), baseline: Gas(150), current: Gas(300) },
+            ]
+        );
+    }
+
+    #[test]
+    fn text_report() {
+        let mut profile = GasProfile::default();
+        profile.record_host_function(HostFunctionCategory::StorageRead, 3, Gas(100));
+        profile.record_method("get", Gas(150));
+        profile.total = Gas(200);
+        let report = profile.to_string();
+        let lines: Vec<Vec<_>> =
+            report.lines().map(|line| line.split_whitespace().collect()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                vec!["total", "200"],
+                vec!["host", "functions", "calls", "bytes", "gas"],
+                vec!["storage_read", "1", "3", "100"],
+                vec!["other", "100"],
+                vec!["methods", "calls", "gas"],
+                vec!["get", "1", "150"],
+            ]
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use crate::mock::with_mocked_blockchain;
+use crate::store::free_list::Slot;
+use crate::store::key::ToKey;
+use crate::store::tree_map::Node;
+use crate::store::unordered_map::ValueAndIndex;
+use crate::test_utils::StorageSnapshot;
+use crate::IntoStorageKey;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::ascii;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+use std::fmt;
+
+type Walker = Box<dyn Fn(&mut Walk) -> StorageNode>;
+
+/// Decodes the storage of the mocked blockchain back to the root state and the `store`
+/// collections of a contract, to debug the opaque keys of the collections.
+///
+/// Rust has no reflection, so the collections are registered with their types, a name and the
+/// prefix they were created with, usually a variant of the `BorshStorageKey` enum of the
+/// contract. The inspector then walks the storage of each collection and renders a tree of their
+/// prefixes, lengths, byte usage and decoded entries, followed by the keys no collection claimed.
+///
+/// The keys of the collections using the [`Identity`](crate::store::key::Identity) hasher are
+/// decoded directly. The keys hashed with [`Sha256`](crate::store::key::Sha256) or
+/// [`Keccak256`](crate::store::key::Keccak256) are matched against the keys stored by the
+/// collection itself, like the keys of an `UnorderedMap`, and against the keys registered with
+/// [`StorageInspector::known_keys`].
+///
+/// Values of the `store` collections are only written to the storage when they are flushed, so the
+/// contract should be flushed or dropped before inspecting the storage.
+///
+/// # Example
+///
+/// ```
+/// use near_sdk::store::key::Identity;
+/// use near_sdk::store::{LookupMap, Vector};
+/// use near_sdk::test_utils::StorageInspector;
+///
+/// let mut vector = Vector::new(b"v");
+/// vector.push(7u64);
+/// vector.flush();
+/// let mut map = LookupMap::<String, u8, Identity>::with_hasher(b"m");
+/// map.insert("seven".to_string(), 7);
+/// map.flush();
+///
+/// let inspector = StorageInspector::new()
+///     .vector::<u64>("vector", b"v")
+///     .lookup_map::<String, u8, Identity>("map", b"m");
+/// let tree = inspector.tree();
+/// assert_eq!(tree[0].entries, vec![("0".to_string(), "7".to_string())]);
+/// assert_eq!(tree[1].entries, vec![("\"seven\"".to_string(), "7".to_string())]);
+/// assert!(inspector.unknown().is_empty());
+/// println!("{}", inspector);
+/// ```
+pub struct StorageInspector {
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    known_keys: Vec<Vec<u8>>,
+    walkers: Vec<Walker>,
+}
+
+/// Decoded storage of the root state or of a collection, in the tree of a [`StorageInspector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageNode {
+    /// The name the collection was registered with.
+    pub name: String,
+    /// The storage prefix of the collection, or the key of the root state.
+    pub prefix: Vec<u8>,
+    /// The number of entries found in the storage.
+    pub len: u64,
+    /// The bytes of the keys and values of the entries.
+    pub bytes: u64,
+    /// The `Debug` representations of the keys, or indices, and values of the entries.
+    pub entries: Vec<(String, String)>,
+    /// The nested collections the collection stores its data in.
+    pub children: Vec<StorageNode>,
+}
+
+impl Default for StorageInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StorageInspector {
+    /// Creates an inspector of the storage of the current mocked blockchain.
+    pub fn new() -> Self {
+        let storage = with_mocked_blockchain(|b| {
+            b.storage().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        });
+        Self { storage, known_keys: vec![], walkers: vec![] }
+    }
+
+    /// Creates an inspector of the storage of a snapshot.
+    pub fn from_snapshot(snapshot: &StorageSnapshot) -> Self {
+        Self { storage: snapshot.storage().clone(), known_keys: vec![], walkers: vec![] }
+    }
+
+    /// Registers keys used in the collections with hashed keys, so that their entries can be
+    /// matched.
+    pub fn known_keys<K: BorshSerialize>(mut self, keys: impl IntoIterator<Item = K>) -> Self {
+        self.known_keys.extend(keys.into_iter().map(|key| key.try_to_vec().unwrap()));
+        self
+    }
+
+    /// Decodes the root state of the contract, stored under the `STATE` key.
+    pub fn state<T>(self) -> Self
+    where
+        T: BorshDeserialize + fmt::Debug + 'static,
+    {
+        self.lazy::<T>("STATE", b"STATE".to_vec())
+    }
+
+    /// Decodes a [`Lazy`](crate::store::Lazy) or a [`LazyOption`](crate::store::LazyOption).
+    pub fn lazy<T>(self, name: &str, key: impl IntoStorageKey) -> Self
+    where
+        T: BorshDeserialize + fmt::Debug + 'static,
+    {
+        let key = key.into_storage_key();
+        self.walker(name, move |walk, node| {
+            if let Some(value) = walk.claim(&key) {
+                node.push(&key, value, escape(&key), decode::<T>(value));
+            }
+            node.prefix = key.clone();
+        })
+    }
+
+    /// Decodes a [`Vector`](crate::store::Vector).
+    pub fn vector<T>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        T: BorshDeserialize + fmt::Debug + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            for (index, key, value) in walk.indices(&prefix) {
+                node.push(&key, value, index.to_string(), decode::<T>(value));
+            }
+            node.prefix = prefix.clone();
+        })
+    }
+
+    /// Decodes a [`LookupMap`](crate::store::LookupMap).
+    pub fn lookup_map<K, V, H>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        K: BorshSerialize + BorshDeserialize + fmt::Debug + 'static,
+        V: BorshDeserialize + fmt::Debug + 'static,
+        H: ToKey + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            *node = walk.lookup::<K, H, _>(node.name.clone(), &prefix, vec![], decode::<V>);
+        })
+    }
+
+    /// Decodes a [`LookupSet`](crate::store::LookupSet).
+    pub fn lookup_set<T, H>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        T: BorshSerialize + BorshDeserialize + fmt::Debug + 'static,
+        H: ToKey + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            *node = walk.lookup::<T, H, _>(node.name.clone(), &prefix, vec![], |_| String::new());
+        })
+    }
+
+    /// Decodes an [`UnorderedMap`](crate::store::UnorderedMap), with its keys stored in a
+    /// `keys` child and its values in a `values` child.
+    pub fn unordered_map<K, V, H>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        K: BorshSerialize + BorshDeserialize + fmt::Debug + 'static,
+        V: BorshDeserialize + fmt::Debug + 'static,
+        H: ToKey + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            let (keys_node, keys) =
+                walk.free_list::<K, _, _>("keys", &[prefix.as_slice(), b"v"].concat(), |key| {
+                    (format!("{:?}", key), key)
+                });
+            let values_node = walk.lookup::<K, H, _>(
+                "values".to_string(),
+                &[prefix.as_slice(), b"m"].concat(),
+                keys,
+                |v| {
+                    ValueAndIndex::<V>::try_from_slice(v)
+                        .map_or_else(|_| undecodable(v), |entry| format!("{:?}", entry.value))
+                },
+            );
+            node.entries = values_node.entries.clone();
+            node.len = values_node.len;
+            node.children = vec![keys_node, values_node];
+            node.prefix = prefix.clone();
+        })
+    }
+
+    /// Decodes an [`UnorderedSet`](crate::store::UnorderedSet), with its elements stored in an
+    /// `elements` child and their indices in an `index` child.
+    pub fn unordered_set<T, H>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        T: BorshSerialize + BorshDeserialize + fmt::Debug + 'static,
+        H: ToKey + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            let (elements_node, elements) = walk.free_list::<T, _, _>(
+                "elements",
+                &[prefix.as_slice(), b"v"].concat(),
+                |element| (format!("{:?}", element), element),
+            );
+            let index_node = walk.lookup::<T, H, _>(
+                "index".to_string(),
+                &[prefix.as_slice(), b"m"].concat(),
+                elements,
+                decode::<u32>,
+            );
+            node.entries = elements_node.entries.clone();
+            node.len = elements_node.len;
+            node.children = vec![elements_node, index_node];
+            node.prefix = prefix.clone();
+        })
+    }
+
+    /// Decodes a [`TreeMap`](crate::store::TreeMap), with its tree nodes stored in a `nodes` child
+    /// and its values in a `values` child.
+    pub fn tree_map<K, V, H>(self, name: &str, prefix: impl IntoStorageKey) -> Self
+    where
+        K: BorshSerialize + BorshDeserialize + fmt::Debug + 'static,
+        V: BorshDeserialize + fmt::Debug + 'static,
+        H: ToKey + 'static,
+    {
+        let prefix = prefix.into_storage_key();
+        self.walker(name, move |walk, node| {
+            let (nodes_node, keys) = walk.free_list::<Node<K>, _, _>(
+                "nodes",
+                &[prefix.as_slice(), b"n"].concat(),
+                |tree_node| (format!("{:?}", tree_node), tree_node.key),
+            );
+            let values_node = walk.lookup::<K, H, _>(
+                "values".to_string(),
+                &[prefix.as_slice(), b"v"].concat(),
+                keys,
+                decode::<V>,
+            );
+            node.entries = values_node.entries.clone();
+            node.len = values_node.len;
+            node.children = vec![nodes_node, values_node];
+            node.prefix = prefix.clone();
+        })
+    }
+
+    /// Walks the storage of the registered collections, in the order they were registered.
+    pub fn tree(&self) -> Vec<StorageNode> {
+        self.walk().0
+    }
+
+    /// The key-value pairs of the storage which don't belong to a registered collection.
+    pub fn unknown(&self) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        let claimed = self.walk().1;
+        self.storage
+            .iter()
+            .filter(|(key, _)| !claimed.contains(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn walker(mut self, name: &str, f: impl Fn(&mut Walk, &mut StorageNode) + 'static) -> Self {
+        let name = name.to_string();
+        self.walkers.push(Box::new(move |walk| {
+            let mut node = StorageNode::new(name.clone());
+            f(walk, &mut node);
+            node.bytes += node.children.iter().map(|child| child.bytes).sum::<u64>();
+            node
+        }));
+        self
+    }
+
+    fn walk(&self) -> (Vec<StorageNode>, BTreeSet<Vec<u8>>) {
+        let mut walk =
+            Walk { storage: &self.storage, known_keys: &self.known_keys, claimed: BTreeSet::new() };
+        let tree = self.walkers.iter().map(|walker| walker(&mut walk)).collect();
+        (tree, walk.claimed)
+    }
+}
+
+impl StorageNode {
+    fn new(name: String) -> Self {
+        Self { name, prefix: vec![], len: 0, bytes: 0, entries: vec![], children: vec![] }
+    }
+
+    fn push(&mut self, key: &[u8], value: &[u8], decoded_key: String, decoded_value: String) {
+        self.len += 1;
+        self.bytes += (key.len() + value.len()) as u64;
+        self.entries.push((decoded_key, decoded_value));
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{} [{}]: {} entries, {} bytes",
+            "",
+            self.name,
+            escape(&self.prefix),
+            self.len,
+            self.bytes,
+            indent = indent
+        )?;
+        if self.children.is_empty() {
+            for (key, value) in &self.entries {
+                writeln!(f, "{:indent$}{}: {}", "", key, value, indent = indent + 2)?;
+            }
+        }
+        for child in &self.children {
+            child.fmt_indented(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StorageNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl fmt::Display for StorageInspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (tree, claimed) = self.walk();
+        for node in &tree {
+            write!(f, "{}", node)?;
+        }
+        let unknown: Vec<_> =
+            self.storage.iter().filter(|(key, _)| !claimed.contains(*key)).collect();
+        if !unknown.is_empty() {
+            let bytes: usize = unknown.iter().map(|(key, value)| key.len() + value.len()).sum();
+            writeln!(f, "unknown: {} entries, {} bytes", unknown.len(), bytes)?;
+            for (key, value) in unknown {
+                writeln!(f, "  {}: {} bytes", escape(key), value.len())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// State of a walk through the storage, which records the keys claimed by the collections.
+struct Walk<'a> {
+    storage: &'a BTreeMap<Vec<u8>, Vec<u8>>,
+    known_keys: &'a [Vec<u8>],
+    claimed: BTreeSet<Vec<u8>>,
+}
+
+impl<'a> Walk<'a> {
+    fn claim(&mut self, key: &[u8]) -> Option<&'a [u8]> {
+        let value = self.storage.get(key)?;
+        self.claimed.insert(key.to_vec());
+        Some(value)
+    }
+
+    /// The entries of an index map, like the elements of a `Vector`, stored under the prefix
+    /// followed by a little endian `u32` index.
+    fn indices(&mut self, prefix: &[u8]) -> Vec<(u32, Vec<u8>, &'a [u8])> {
+        let storage = self.storage;
+        let entries: Vec<_> = storage
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter_map(|(key, value)| {
+                let index: [u8; 4] = key[prefix.len()..].try_into().ok()?;
+                Some((u32::from_le_bytes(index), key.clone(), value.as_slice()))
+            })
+            .collect();
+        for (_, key, _) in &entries {
+            self.claimed.insert(key.clone());
+        }
+        entries
+    }
+
+    /// Walks a free list, returning its node and the keys extracted from its occupied slots.
+    fn free_list<T, K, F>(&mut self, name: &str, prefix: &[u8], extract: F) -> (StorageNode, Vec<K>)
+    where
+        T: BorshDeserialize,
+        F: Fn(T) -> (String, K),
+    {
+        let mut node = StorageNode::new(name.to_string());
+        node.prefix = prefix.to_vec();
+        let mut keys = vec![];
+        for (index, key, value) in self.indices(prefix) {
+            let decoded = match Slot::<T>::try_from_slice(value) {
+                Ok(Slot::Occupied(element)) => {
+                    let (decoded, key) = extract(element);
+                    keys.push(key);
+                    decoded
+                }
+                Ok(Slot::Empty { .. }) => "<empty>".to_string(),
+                Err(_) => undecodable(value),
+            };
+            node.push(&key, value, index.to_string(), decoded);
+        }
+        (node, keys)
+    }
+
+    /// Walks a lookup map, matching its keys hashed with `H` against the keys decoded directly
+    /// from the storage, the `keys` given and the known keys of the inspector.
+    fn lookup<K, H, F>(
+        &mut self,
+        name: String,
+        prefix: &[u8],
+        keys: Vec<K>,
+        decode_value: F,
+    ) -> StorageNode
+    where
+        K: BorshSerialize + BorshDeserialize + fmt::Debug,
+        H: ToKey,
+        F: Fn(&[u8]) -> String,
+    {
+        let storage = self.storage;
+        let direct = storage
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter_map(|(key, _)| K::try_from_slice(&key[prefix.len()..]).ok());
+        let known = self.known_keys.iter().filter_map(|key| K::try_from_slice(key).ok());
+
+        let mut entries = BTreeMap::new();
+        for key in keys.into_iter().chain(direct).chain(known) {
+            let lookup_key = H::to_key(prefix, &key, &mut Vec::new()).as_ref().to_vec();
+            if let Some(value) = storage.get(&lookup_key) {
+                entries.entry(lookup_key).or_insert((key, value));
+            }
+        }
+
+        let mut node = StorageNode::new(name);
+        node.prefix = prefix.to_vec();
+        for (lookup_key, (key, value)) in entries {
+            self.claimed.insert(lookup_key.clone());
+            node.push(&lookup_key, value, format!("{:?}", key), decode_value(value));
+        }
+        node
+    }
+}
+
+fn decode<T: BorshDeserialize + fmt::Debug>(value: &[u8]) -> String {
+    T::try_from_slice(value).map_or_else(|_| undecodable(value), |value| format!("{:?}", value))
+}
+
+fn undecodable(value: &[u8]) -> String {
+    format!("<{} undecodable bytes>", value.len())
+}
+
+fn escape(bytes: &[u8]) -> String {
+    bytes.iter().flat_map(|byte| ascii::escape_default(*byte)).map(char::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env;
+    use crate::store::key::{Identity, Sha256};
+    use crate::store::{
+        LazyOption, LookupMap, LookupSet, TreeMap, UnorderedMap, UnorderedSet, Vector,
+    };
+
+    fn entries(node: &StorageNode) -> Vec<(&str, &str)> {
+        node.entries.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect()
+    }
+
+    #[test]
+    fn decodes_collections() {
+        env::state_write(&42u64);
+        let mut vector = Vector::new(b"v");
+        vector.extend([1u8, 2]);
+        let mut identity_map = LookupMap::<u32, String, Identity>::with_hasher(b"i");
+        identity_map.insert(1, "one".to_string());
+        let mut hashed_map = LookupMap::<u32, String, Sha256>::with_hasher(b"h");
+        hashed_map.insert(2, "two".to_string());
+        hashed_map.insert(3, "three".to_string());
+        let mut set = LookupSet::<u32, Identity>::with_hasher(b"s");
+        set.insert(4);
+        let mut unordered_map = UnorderedMap::new(b"u");
+        unordered_map.insert("a".to_string(), 5u8);
+        unordered_map.insert("b".to_string(), 6);
+        unordered_map.remove("a");
+        let mut unordered_set = UnorderedSet::new(b"e");
+        unordered_set.insert(7u8);
+        let mut tree_map = TreeMap::new(b"t");
+        tree_map.insert(8u8, 9u8);
+        let lazy = LazyOption::new(b"l", Some(10u8));
+        vector.flush();
+        identity_map.flush();
+        hashed_map.flush();
+        set.flush();
+        unordered_map.flush();
+        unordered_set.flush();
+        tree_map.flush();
+        drop(lazy);
+        env::storage_write(b"x", b"unknown");
+
+        let inspector = StorageInspector::new()
+            .state::<u64>()
+            .vector::<u8>("vector", b"v")
+            .lookup_map::<u32, String, Identity>("identity_map", b"i")
+            .lookup_map::<u32, String, Sha256>("hashed_map", b"h")
+            .known_keys([2u32])
+            .lookup_set::<u32, Identity>("set", b"s")
+            .unordered_map::<String, u8, Sha256>("unordered_map", b"u")
+            .unordered_set::<u8, Sha256>("unordered_set", b"e")
+            .tree_map::<u8, u8, Sha256>("tree_map", b"t")
+            .lazy::<u8>("lazy", b"l");
+        let tree = inspector.tree();
+        assert_eq!(entries(&tree[0]), vec![("STATE", "42")]);
+        assert_eq!(tree[0].bytes, 5 + 8);
+        assert_eq!(entries(&tree[1]), vec![("0", "1"), ("1", "2")]);
+        assert_eq!((tree[1].len, tree[1].bytes), (2, 2 * (1 + 4 + 1)));
+        assert_eq!(entries(&tree[2]), vec![("1", "\"one\"")]);
+        assert_eq!(entries(&tree[3]), vec![("2", "\"two\"")]);
+        assert_eq!(entries(&tree[4]), vec![("4", "")]);
+        assert_eq!(entries(&tree[5]), vec![("\"b\"", "6")]);
+        assert_eq!(entries(&tree[5].children[0]), vec![("0", "<empty>"), ("1", "\"b\"")]);
+        assert_eq!(tree[5].bytes, tree[5].children[0].bytes + tree[5].children[1].bytes);
+        assert_eq!(entries(&tree[6]), vec![("0", "7")]);
+        assert_eq!(entries(&tree[6].children[1]), vec![("7", "0")]);
+        assert_eq!(entries(&tree[7]), vec![("8", "9")]);
+        assert_eq!(entries(&tree[8]), vec![("l", "10")]);
+
+        // The entry of the hashed map with an unregistered key and the unrelated key are unknown.
+        let unknown = inspector.unknown();
+        assert_eq!(unknown.len(), 2);
+        assert_eq!(unknown[&b"x".to_vec()], b"unknown");
+
+        let report = inspector.to_string();
+        assert!(report.starts_with("STATE [STATE]: 1 entries, 13 bytes\n  STATE: 42\n"));
+        assert!(report.contains("unordered_map [u]: 1 entries, "));
+        assert!(report.contains("\n  keys [uv]: 2 entries, "));
+        assert!(report.contains("\nunknown: 2 entries, "));
+        assert!(report.contains("\n  x: 7 bytes\n"));
+    }
+
+    #[test]
+    fn inspects_snapshot() {
+        let mut vector = Vector::new(b"v");
+        vector.push(1u8);
+        vector.flush();
+        let snapshot = StorageSnapshot::take();
+        vector.push(2);
+        vector.flush();
+
+        let inspector = StorageInspector::from_snapshot(&snapshot).vector::<u8>("vector", b"v");
+        assert_eq!(inspector.tree()[0].len, 1);
+        let inspector = StorageInspector::new().vector::<u64>("vector", b"v");
+        assert_eq!(
+            entries(&inspector.tree()[0]),
+            vec![("0", "<1 undecodable bytes>"), ("1", "<1 undecodable bytes>")]
+        );
+    }
+}
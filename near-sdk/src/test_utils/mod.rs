@@ -8,6 +8,22 @@ use crate::mock::Receipt;
 #[allow(deprecated)]
 pub use context::{accounts, testing_env_with_promise_results, VMContextBuilder};
 
+mod snapshot;
+pub use snapshot::{PrefixedChange, StorageChange, StorageDiff, StorageSnapshot};
+
+mod runtime;
+pub use runtime::{CallOutcome, MockMethods, MockRuntime, ReceiptOutcome};
+
+#[cfg(feature = "unstable")]
+mod inspector;
+#[cfg(feature = "unstable")]
+pub use inspector::{StorageInspector, StorageNode};
+
+mod gas_profile;
+pub use gas_profile::{
+    profile_method, GasChange, GasProfile, HostFunctionCategory, HostFunctionCost, MethodCost,
+};
+
 /// Initializes a testing environment to mock interactions which would otherwise go through a
 /// validator node. This macro will initialize or overwrite the [`MockedBlockchain`]
 /// instance for interactions from a smart contract.
@@ -95,12 +111,27 @@ pub fn get_logs() -> Vec<String> {
     crate::mock::with_mocked_blockchain(|b| b.logs())
 }
 
+/// Returns the events of type `T` parsed from the logs, skipping the logs which aren't such
+/// events. Only available in unit tests.
+pub fn get_events<T>() -> Vec<T>
+where
+    T: crate::NearEvent + serde::de::DeserializeOwned,
+{
+    get_logs().iter().filter_map(|log| T::from_event_log(log)).collect()
+}
+
 /// Accessing receipts created by the contract. Only available in unit tests.
 #[allow(dead_code)]
 pub fn get_created_receipts() -> Vec<Receipt> {
     crate::mock::with_mocked_blockchain(|b| b.created_receipts().clone())
 }
 
+/// Returns the gas burnt since the last [`testing_env!`], by host function category and by method.
+/// Only available in unit tests.
+pub fn gas_profile() -> GasProfile {
+    crate::mock::with_mocked_blockchain(|b| b.gas_profile())
+}
+
 /// Objects stored on the trie directly should have identifiers. If identifier is not provided
 /// explicitly than `Default` trait would use this index to generate an id.
 #[allow(dead_code)]
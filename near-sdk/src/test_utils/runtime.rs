@@ -0,0 +1,805 @@
+use crate::mock::{with_mocked_blockchain, MockedBlockchain, VmAction};
+use crate::test_utils::{GasProfile, VMContextBuilder};
+use crate::{
+    env, AccountId, Balance, BlockHeight, Gas, PromiseResult, ReturnData, RuntimeFeesConfig,
+    StorageUsage, VMConfig,
+};
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Calls an exported method of a contract by name in the [`MockRuntime`], returns `false` if the
+/// method doesn't exist.
+///
+/// With the `mock-runtime` feature, [`near_bindgen`](crate::near_bindgen) generates one for each
+/// `impl` section of the contract, named `dispatch`, in the `_methods` module for inherent `impl`
+/// sections and in the `<Trait>_methods` module for trait implementations.
+pub type MockMethods = fn(&str) -> bool;
+
+/// Time between two blocks of the [`MockRuntime`], in nanoseconds.
+const BLOCK_TIME: u64 = 1_000_000_000;
+
+/// Local runtime of several contracts for the unit tests, which executes the receipts created by
+/// the contracts until all of them are processed.
+///
+/// Contracts are registered by account id with the [`MockMethods`] of their `impl` sections, and
+/// their methods are called natively on the [`MockedBlockchain`] of the thread, which is replaced
+/// for each function call. Promise results are passed to the callbacks, the attached deposits are
+/// moved between the accounts and refunded to the predecessor if the receipt fails, in which case
+/// the changes of the receipt are reverted. Each receipt is executed in a new block.
+///
+/// Function calls fail once they used their prepaid gas, which is charged for the gas they burn
+/// and the static gas of the calls they create. The prepaid gas they didn't use is split between
+/// the calls they created with a weight, see [`Promise::function_call_weight`].
+///
+/// The runtime doesn't charge gas from the balances nor check the storage staking. Actions other
+/// than `CreateAccount`, `Transfer`, `FunctionCall` and `DeleteAccount` have no effect.
+///
+/// [`Promise::function_call_weight`]: crate::Promise::function_call_weight
+///
+/// # Example
+///
+/// ```ignore
+/// use near_sdk::test_utils::{accounts, MockRuntime};
+/// use near_sdk::Gas;
+///
+/// let mut runtime = MockRuntime::new();
+/// runtime.add_account(accounts(0), 10u128.pow(25));
+/// runtime.add_account(accounts(1), 10u128.pow(24));
+/// runtime.deploy(&accounts(1), &[exchange::_methods::dispatch]);
+/// runtime.add_account(accounts(2), 10u128.pow(24));
+/// runtime.deploy(&accounts(2), &[token::_methods::dispatch]);
+///
+/// let outcome = runtime.call(
+///     accounts(0),
+///     accounts(1),
+///     "swap",
+///     br#"{"amount": "10"}"#.to_vec(),
+///     0,
+///     Gas(300_000_000_000_000),
+/// );
+/// assert_eq!(outcome.unwrap_json::<String>(), "10");
+/// ```
+pub struct MockRuntime {
+    accounts: HashMap<AccountId, MockAccount>,
+    config: VMConfig,
+    fees_config: RuntimeFeesConfig,
+    block_index: BlockHeight,
+    block_timestamp: u64,
+    next_receipt_id: u64,
+}
+
+#[derive(Clone, Default)]
+struct MockAccount {
+    balance: Balance,
+    storage_usage: StorageUsage,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    methods: Vec<MockMethods>,
+}
+
+/// Receipt waiting for the results of the receipts it depends on.
+struct PendingReceipt {
+    id: u64,
+    signer_id: AccountId,
+    predecessor_id: AccountId,
+    receiver_id: AccountId,
+    dependencies: Vec<u64>,
+    actions: Vec<VmAction>,
+}
+
+/// Result of an executed receipt, which is the result of another receipt when the receipt returned
+/// a promise.
+enum Resolution {
+    Done(PromiseResult),
+    Forwarded(u64),
+}
+
+/// Outcome of a receipt executed by the [`MockRuntime`].
+#[derive(Debug)]
+pub struct ReceiptOutcome {
+    /// The account which created the receipt.
+    pub predecessor_id: AccountId,
+    /// The account the receipt was executed on.
+    pub receiver_id: AccountId,
+    /// The actions of the receipt.
+    pub actions: Vec<VmAction>,
+    /// The result of the receipt, or of the promise it returned.
+    pub result: PromiseResult,
+    /// The error that failed the receipt.
+    pub failure: Option<String>,
+    /// The logs of the function calls.
+    pub logs: Vec<String>,
+    /// The gas burnt by the function calls.
+    pub gas_burnt: Gas,
+    /// The gas burnt by the function calls, by host function category and by method.
+    pub gas_profile: GasProfile,
+}
+
+/// Outcome of a call made with [`MockRuntime::call`].
+#[derive(Debug)]
+pub struct CallOutcome {
+    /// The result of the call, or of the promise it returned.
+    pub result: PromiseResult,
+    /// The outcomes of the receipts in the order of execution, starting with the call.
+    pub receipts: Vec<ReceiptOutcome>,
+}
+
+impl CallOutcome {
+    /// Returns `true` if the call succeeded.
+    pub fn is_success(&self) -> bool {
+        matches!(self.result, PromiseResult::Successful(_))
+    }
+
+    /// Deserializes the JSON result of the call, panics if the call failed.
+    pub fn unwrap_json<T: DeserializeOwned>(&self) -> T {
+        match &self.result {
+            PromiseResult::Successful(value) => serde_json::from_slice(value)
+                .unwrap_or_else(|err| panic!("Failed to deserialize the result: {}", err)),
+            _ => panic!("The call failed: {:?}", self.failures().collect::<Vec<_>>()),
+        }
+    }
+
+    /// The errors of the failed receipts.
+    pub fn failures(&self) -> impl Iterator<Item = &str> {
+        self.receipts.iter().filter_map(|receipt| receipt.failure.as_deref())
+    }
+
+    /// The logs of all the receipts.
+    pub fn logs(&self) -> Vec<&str> {
+        self.receipts.iter().flat_map(|receipt| receipt.logs.iter().map(String::as_str)).collect()
+    }
+
+    /// The gas burnt by all the receipts.
+    pub fn gas_burnt(&self) -> Gas {
+        self.receipts.iter().fold(Gas(0), |total, receipt| total + receipt.gas_burnt)
+    }
+
+    /// The gas profile of all the receipts.
+    pub fn gas_profile(&self) -> GasProfile {
+        let mut profile = GasProfile::default();
+        for receipt in &self.receipts {
+            profile.merge(&receipt.gas_profile);
+        }
+        profile
+    }
+}
+
+impl Default for MockRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockRuntime {
+    /// Creates a runtime without accounts, with the test configs of the VM.
+    pub fn new() -> Self {
+        Self::with_config(VMConfig::test(), RuntimeFeesConfig::test())
+    }
+
+    /// Creates a runtime without accounts, with the given configs of the VM.
+    pub fn with_config(config: VMConfig, fees_config: RuntimeFeesConfig) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            config,
+            fees_config,
+            block_index: 0,
+            block_timestamp: 0,
+            next_receipt_id: 0,
+        }
+    }
+
+    /// Adds an account without a contract.
+    pub fn add_account(&mut self, account_id: AccountId, balance: Balance) {
+        if self.accounts.contains_key(&account_id) {
+            panic!("Account {} already exists", account_id);
+        }
+        self.accounts.insert(account_id, MockAccount { balance, ..Default::default() });
+    }
+
+    /// Deploys the contract with the methods of its `impl` sections to the account. The storage of
+    /// the account is kept when the contract is redeployed.
+    pub fn deploy(&mut self, account_id: &AccountId, methods: &[MockMethods]) {
+        self.account_mut(account_id).methods = methods.to_vec();
+    }
+
+    /// Returns the balance of the account.
+    pub fn balance(&self, account_id: &AccountId) -> Balance {
+        self.accounts
+            .get(account_id)
+            .unwrap_or_else(|| panic!("Account {} doesn't exist", account_id))
+            .balance
+    }
+
+    /// Returns `true` if the account exists.
+    pub fn has_account(&self, account_id: &AccountId) -> bool {
+        self.accounts.contains_key(account_id)
+    }
+
+    /// Runs `f` with the storage and the balance of the account, as if the account called itself,
+    /// to set up or inspect the state of its contract with [`env`]. Changes are kept.
+    pub fn with_account<R>(&mut self, account_id: &AccountId, f: impl FnOnce() -> R) -> R {
+        let mut context = VMContextBuilder::new()
+            .current_account_id(account_id.clone())
+            .signer_account_id(account_id.clone())
+            .predecessor_account_id(account_id.clone())
+            .block_index(self.block_index)
+            .block_timestamp(self.block_timestamp)
+            .build();
+        let account = self.account_mut(account_id);
+        context.account_balance = account.balance;
+        context.storage_usage = account.storage_usage;
+        let storage = mem::take(&mut account.storage);
+        let (config, fees_config) = (self.config.clone(), self.fees_config.clone());
+        env::set_blockchain_interface(MockedBlockchain::new(
+            context,
+            config,
+            fees_config,
+            vec![],
+            storage,
+            HashMap::new(),
+            None,
+        ));
+        let result = f();
+        let (storage, outcome) = with_mocked_blockchain(|b| (b.take_storage(), b.outcome()));
+        let account = self.account_mut(account_id);
+        account.storage = storage;
+        account.balance = outcome.balance;
+        account.storage_usage = outcome.storage_usage;
+        result
+    }
+
+    /// Calls the method of the contract of `receiver_id` from `signer_id`, and executes all the
+    /// receipts created by the call and its callbacks. The deposit is taken from the balance of
+    /// the signer.
+    pub fn call(
+        &mut self,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        method_name: &str,
+        args: Vec<u8>,
+        deposit: Balance,
+        gas: Gas,
+    ) -> CallOutcome {
+        let signer = self.account_mut(&signer_id);
+        if signer.balance < deposit {
+            panic!("Account {} doesn't have enough balance to attach {}", signer_id, deposit);
+        }
+        signer.balance -= deposit;
+
+        let call_id = self.next_receipt_id();
+        let mut pending = VecDeque::new();
+        pending.push_back(PendingReceipt {
+            id: call_id,
+            signer_id: signer_id.clone(),
+            predecessor_id: signer_id,
+            receiver_id,
+            dependencies: vec![],
+            actions: vec![VmAction::FunctionCall {
+                function_name: method_name.to_string(),
+                args,
+                gas,
+                deposit,
+            }],
+        });
+        let mut resolutions = HashMap::new();
+        let mut executed = vec![];
+        // Receipts are executed in the order they were created, once the receipts they depend on
+        // have a result.
+        while let Some(position) = pending.iter().position(|receipt| {
+            receipt.dependencies.iter().all(|id| resolve(&resolutions, *id).is_some())
+        }) {
+            let receipt = pending.remove(position).unwrap();
+            let promise_results =
+                receipt.dependencies.iter().map(|id| resolve(&resolutions, *id).unwrap()).collect();
+            let (outcome, resolution, created) = self.execute(&receipt, promise_results);
+            pending.extend(created);
+            resolutions.insert(receipt.id, resolution);
+            executed.push((receipt.id, outcome));
+        }
+
+        let receipts = executed
+            .into_iter()
+            .map(|(id, mut outcome)| {
+                outcome.result = resolve(&resolutions, id).unwrap_or(PromiseResult::NotReady);
+                outcome
+            })
+            .collect();
+        let result = resolve(&resolutions, call_id).unwrap_or(PromiseResult::NotReady);
+        CallOutcome { result, receipts }
+    }
+
+    fn account_mut(&mut self, account_id: &AccountId) -> &mut MockAccount {
+        self.accounts
+            .get_mut(account_id)
+            .unwrap_or_else(|| panic!("Account {} doesn't exist", account_id))
+    }
+
+    fn next_receipt_id(&mut self) -> u64 {
+        let id = self.next_receipt_id;
+        self.next_receipt_id += 1;
+        id
+    }
+
+    /// Executes the actions of the receipt on a copy of the receiver account, which replaces the
+    /// account if all of them succeed.
+    fn execute(
+        &mut self,
+        receipt: &PendingReceipt,
+        promise_results: Vec<PromiseResult>,
+    ) -> (ReceiptOutcome, Resolution, Vec<PendingReceipt>) {
+        self.block_index += 1;
+        self.block_timestamp += BLOCK_TIME;
+        let mut outcome = ReceiptOutcome {
+            predecessor_id: receipt.predecessor_id.clone(),
+            receiver_id: receipt.receiver_id.clone(),
+            actions: receipt.actions.clone(),
+            result: PromiseResult::NotReady,
+            failure: None,
+            logs: vec![],
+            gas_burnt: Gas(0),
+            gas_profile: GasProfile::default(),
+        };
+        let mut account = self.accounts.get(&receipt.receiver_id).cloned();
+        let mut resolution = Resolution::Done(PromiseResult::Successful(vec![]));
+        let mut created = vec![];
+        let mut transfers = vec![];
+
+        for action in &receipt.actions {
+            let applied = match action {
+                VmAction::CreateAccount => match account {
+                    Some(_) => Err(format!("Account {} already exists", receipt.receiver_id)),
+                    None => {
+                        account = Some(MockAccount::default());
+                        Ok(())
+                    }
+                },
+                VmAction::Transfer { deposit } => {
+                    existing_account(&mut account, &receipt.receiver_id).map(|account| {
+                        account.balance += deposit;
+                    })
+                }
+                VmAction::FunctionCall { function_name, args, gas, deposit } => {
+                    existing_account(&mut account, &receipt.receiver_id).and_then(|account| {
+                        account.balance += deposit;
+                        let call = FunctionCallArgs {
+                            function_name,
+                            args,
+                            gas: *gas,
+                            deposit: *deposit,
+                            promise_results: &promise_results,
+                        };
+                        self.call_function(receipt, account, call, &mut outcome).map(
+                            |(return_data, receipts)| {
+                                resolution = return_data;
+                                created.extend(receipts);
+                            },
+                        )
+                    })
+                }
+                VmAction::DeleteAccount { beneficiary_id } => account
+                    .take()
+                    .map(|deleted| transfers.push((beneficiary_id.clone(), deleted.balance)))
+                    .ok_or_else(|| format!("Account {} doesn't exist", receipt.receiver_id)),
+                // The keys, the code and the stake of the accounts aren't mocked.
+                _ => Ok(()),
+            };
+            if let Err(err) = applied {
+                outcome.failure = Some(err);
+                break;
+            }
+        }
+
+        if outcome.failure.is_some() {
+            let refund: Balance = receipt
+                .actions
+                .iter()
+                .map(|action| match action {
+                    VmAction::FunctionCall { deposit, .. } | VmAction::Transfer { deposit } => {
+                        *deposit
+                    }
+                    _ => 0,
+                })
+                .sum();
+            if let Some(predecessor) = self.accounts.get_mut(&receipt.predecessor_id) {
+                predecessor.balance += refund;
+            }
+            return (outcome, Resolution::Done(PromiseResult::Failed), vec![]);
+        }
+
+        match account {
+            Some(account) => {
+                self.accounts.insert(receipt.receiver_id.clone(), account);
+            }
+            None => {
+                self.accounts.remove(&receipt.receiver_id);
+            }
+        }
+        for (beneficiary_id, amount) in transfers {
+            if let Some(beneficiary) = self.accounts.get_mut(&beneficiary_id) {
+                beneficiary.balance += amount;
+            }
+        }
+        (outcome, resolution, created)
+    }
+
+    /// Calls the method of the contract on the [`MockedBlockchain`] of the thread, returns the
+    /// result of the call and the receipts it created.
+    fn call_function(
+        &mut self,
+        receipt: &PendingReceipt,
+        account: &mut MockAccount,
+        call: FunctionCallArgs,
+        outcome: &mut ReceiptOutcome,
+    ) -> Result<(Resolution, Vec<PendingReceipt>), String> {
+        if account.methods.is_empty() {
+            return Err(format!("Account {} has no contract", receipt.receiver_id));
+        }
+        let mut context = VMContextBuilder::new()
+            .current_account_id(receipt.receiver_id.clone())
+            .signer_account_id(receipt.signer_id.clone())
+            .predecessor_account_id(receipt.predecessor_id.clone())
+            .block_index(self.block_index)
+            .block_timestamp(self.block_timestamp)
+            .account_balance(account.balance)
+            .storage_usage(account.storage_usage)
+            .attached_deposit(call.deposit)
+            .prepaid_gas(call.gas)
+            .build();
+        context.input = call.args.to_vec();
+        env::set_blockchain_interface(MockedBlockchain::new(
+            context,
+            self.config.clone(),
+            self.fees_config.clone(),
+            call.promise_results.to_vec(),
+            mem::take(&mut account.storage),
+            HashMap::new(),
+            None,
+        ));
+
+        let methods = &account.methods;
+        let called = panic::catch_unwind(AssertUnwindSafe(|| {
+            methods.iter().any(|dispatch| dispatch(call.function_name))
+        }));
+        let (storage, receipts, vm_outcome, mut gas_profile) = with_mocked_blockchain(|b| {
+            (b.take_storage(), b.created_receipts().clone(), b.outcome(), b.gas_profile())
+        });
+        gas_profile.record_method(call.function_name, gas_profile.total);
+        outcome.gas_profile.merge(&gas_profile);
+        outcome.logs.extend(vm_outcome.logs);
+        outcome.gas_burnt += Gas(vm_outcome.burnt_gas);
+        match called {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(format!("Contract method {} is not found", call.function_name))
+            }
+            Err(_) if vm_outcome.used_gas >= call.gas.0 => {
+                return Err("Exceeded the prepaid gas.".to_string())
+            }
+            Err(payload) => return Err(panic_message(payload)),
+        }
+        account.storage = storage;
+        account.balance = vm_outcome.balance;
+        account.storage_usage = vm_outcome.storage_usage;
+
+        // Indices of the receipts created by the call are relative to the call.
+        let first_id = self.next_receipt_id;
+        self.next_receipt_id += receipts.len() as u64;
+        let mut created: Vec<_> = receipts
+            .into_iter()
+            .enumerate()
+            .map(|(index, created)| PendingReceipt {
+                id: first_id + index as u64,
+                signer_id: receipt.signer_id.clone(),
+                predecessor_id: receipt.receiver_id.clone(),
+                receiver_id: created.receiver_id,
+                dependencies: created
+                    .receipt_indices
+                    .iter()
+                    .map(|index| first_id + index)
+                    .collect(),
+                actions: created.actions,
+            })
+            .collect();
+        // The burnt gas and the static gas of the created calls are charged against the prepaid
+        // gas, the rest of it goes to the created calls with a weight.
+        distribute_unused_gas(&mut created, call.gas.0.saturating_sub(vm_outcome.used_gas));
+        let resolution = match vm_outcome.return_data {
+            ReturnData::Value(value) => Resolution::Done(PromiseResult::Successful(value)),
+            ReturnData::ReceiptIndex(index) => Resolution::Forwarded(first_id + index),
+            ReturnData::None => Resolution::Done(PromiseResult::Successful(vec![])),
+        };
+        Ok((resolution, created))
+    }
+}
+
+/// Arguments of a function call action.
+struct FunctionCallArgs<'a> {
+    function_name: &'a str,
+    args: &'a [u8],
+    gas: Gas,
+    deposit: Balance,
+    promise_results: &'a [PromiseResult],
+}
+
+/// Splits `unused_gas` between the weighted function calls of `receipts` in proportion to their
+/// weights, the last of them receiving the remainder, and turns them into plain function calls.
+fn distribute_unused_gas(receipts: &mut [PendingReceipt], unused_gas: u64) {
+    let weighted: Vec<(usize, usize, u64)> = receipts
+        .iter()
+        .enumerate()
+        .flat_map(|(receipt_index, receipt)| {
+            receipt.actions.iter().enumerate().filter_map(
+                move |(action_index, action)| match action {
+                    VmAction::FunctionCallWeight { weight, .. } if weight.0 > 0 => {
+                        Some((receipt_index, action_index, weight.0))
+                    }
+                    _ => None,
+                },
+            )
+        })
+        .collect();
+    let total_weight: u128 = weighted.iter().map(|(_, _, weight)| u128::from(*weight)).sum();
+    let mut remaining_gas = unused_gas;
+    for (position, (receipt_index, action_index, weight)) in weighted.iter().enumerate() {
+        let share = if position + 1 == weighted.len() {
+            remaining_gas
+        } else {
+            (u128::from(unused_gas) * u128::from(*weight) / total_weight) as u64
+        };
+        remaining_gas -= share;
+        if let VmAction::FunctionCallWeight { gas, .. } =
+            &mut receipts[*receipt_index].actions[*action_index]
+        {
+            *gas = Gas(gas.0 + share);
+        }
+    }
+
+    for action in receipts.iter_mut().flat_map(|receipt| receipt.actions.iter_mut()) {
+        if let VmAction::FunctionCallWeight { function_name, args, gas, deposit, .. } = action {
+            *action = VmAction::FunctionCall {
+                function_name: mem::take(function_name),
+                args: mem::take(args),
+                gas: *gas,
+                deposit: *deposit,
+            };
+        }
+    }
+}
+
+fn existing_account<'a>(
+    account: &'a mut Option<MockAccount>,
+    account_id: &AccountId,
+) -> Result<&'a mut MockAccount, String> {
+    account.as_mut().ok_or_else(|| format!("Account {} doesn't exist", account_id))
+}
+
+/// Returns the result of the receipt, following the promises it returned, or `None` if it wasn't
+/// executed yet.
+fn resolve(resolutions: &HashMap<u64, Resolution>, mut id: u64) -> Option<PromiseResult> {
+    loop {
+        match resolutions.get(&id)? {
+            Resolution::Done(result) => return Some(result.clone()),
+            Resolution::Forwarded(next) => id = *next,
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "Contract panicked".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::accounts;
+    use crate::GasWeight;
+    use std::convert::TryInto;
+
+    const GAS: Gas = Gas(300_000_000_000_000);
+    const CALLBACK_GAS: Gas = Gas(50_000_000_000_000);
+
+    fn counter_id() -> AccountId {
+        "counter.near".parse().unwrap()
+    }
+
+    fn caller_id() -> AccountId {
+        "caller.near".parse().unwrap()
+    }
+
+    fn counter(method_name: &str) -> bool {
+        match method_name {
+            "increment" => {
+                let value = env::storage_read(b"count")
+                    .map_or(0, |bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+                    + 1;
+                env::storage_write(b"count", &value.to_le_bytes());
+                env::log_str(&format!("count is {}", value));
+                env::value_return(value.to_string().as_bytes());
+            }
+            "fail" => env::panic_str("Failed on purpose"),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Calls the method of the counter passed in the input, and returns the result of the callback.
+    fn caller(method_name: &str) -> bool {
+        match method_name {
+            "call_counter" => {
+                let input = String::from_utf8(env::input().unwrap()).unwrap();
+                let call = env::promise_create(
+                    counter_id(),
+                    &input,
+                    &[],
+                    env::attached_deposit(),
+                    CALLBACK_GAS,
+                );
+                let callback = env::promise_then(
+                    call,
+                    env::current_account_id(),
+                    "on_counter",
+                    &[],
+                    0,
+                    CALLBACK_GAS,
+                );
+                env::promise_return(callback);
+            }
+            "call_counter_weighted" => {
+                let input = String::from_utf8(env::input().unwrap()).unwrap();
+                let call = env::promise_batch_create(&counter_id());
+                env::promise_batch_action_function_call_weight(
+                    call,
+                    &input,
+                    &[],
+                    0,
+                    Gas(0),
+                    GasWeight(1),
+                );
+                let callback = env::promise_batch_then(call, &env::current_account_id());
+                env::promise_batch_action_function_call_weight(
+                    callback,
+                    "on_counter",
+                    &[],
+                    0,
+                    CALLBACK_GAS,
+                    GasWeight(3),
+                );
+                env::promise_return(callback);
+            }
+            "on_counter" => {
+                assert_eq!(env::predecessor_account_id(), env::current_account_id());
+                assert_eq!(env::signer_account_id(), accounts(0));
+                match env::promise_result(0) {
+                    PromiseResult::Successful(value) => env::value_return(&value),
+                    _ => env::value_return(b"\"failed\""),
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn runtime() -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime.add_account(accounts(0), 1000);
+        runtime.add_account(counter_id(), 100);
+        runtime.deploy(&counter_id(), &[counter]);
+        runtime.add_account(caller_id(), 100);
+        runtime.deploy(&caller_id(), &[caller]);
+        runtime
+    }
+
+    #[test]
+    fn callback_receives_result() {
+        let mut runtime = runtime();
+        let outcome =
+            runtime.call(accounts(0), caller_id(), "call_counter", b"increment".to_vec(), 0, GAS);
+        assert_eq!(outcome.unwrap_json::<u64>(), 1);
+        assert_eq!(outcome.receipts.len(), 3);
+        assert_eq!(outcome.receipts[1].receiver_id, counter_id());
+        assert_eq!(outcome.receipts[1].predecessor_id, caller_id());
+        assert_eq!(outcome.receipts[2].predecessor_id, caller_id());
+        assert_eq!(outcome.logs(), vec!["count is 1"]);
+        assert!(outcome.gas_burnt() > Gas(0));
+        let profile = outcome.gas_profile();
+        assert_eq!(profile.total, outcome.gas_burnt());
+        assert_eq!(profile.methods["increment"].calls, 1);
+        assert_eq!(profile.methods["call_counter"].calls, 1);
+
+        let outcome =
+            runtime.call(accounts(0), caller_id(), "call_counter", b"increment".to_vec(), 0, GAS);
+        assert_eq!(outcome.unwrap_json::<u64>(), 2);
+        let count = runtime.with_account(&counter_id(), || env::storage_read(b"count"));
+        assert_eq!(count, Some(2u64.to_le_bytes().to_vec()));
+    }
+
+    #[test]
+    fn deposit_moves_between_accounts() {
+        let mut runtime = runtime();
+        let outcome =
+            runtime.call(accounts(0), caller_id(), "call_counter", b"increment".to_vec(), 10, GAS);
+        assert!(outcome.is_success());
+        assert_eq!(runtime.balance(&accounts(0)), 990);
+        assert_eq!(runtime.balance(&caller_id()), 100);
+        assert_eq!(runtime.balance(&counter_id()), 110);
+    }
+
+    #[test]
+    fn failed_receipt_is_reverted_and_refunded() {
+        let mut runtime = runtime();
+        let outcome =
+            runtime.call(accounts(0), caller_id(), "call_counter", b"fail".to_vec(), 10, GAS);
+        assert_eq!(outcome.unwrap_json::<String>(), "failed");
+        assert_eq!(outcome.receipts[1].result, PromiseResult::Failed);
+        assert!(outcome.failures().next().unwrap().contains("Failed on purpose"));
+        // The deposit is refunded to the caller, which made the failed call.
+        assert_eq!(runtime.balance(&accounts(0)), 990);
+        assert_eq!(runtime.balance(&caller_id()), 110);
+        assert_eq!(runtime.balance(&counter_id()), 100);
+
+        let outcome = runtime.call(accounts(0), counter_id(), "fail", vec![], 5, GAS);
+        assert!(!outcome.is_success());
+        assert_eq!(runtime.balance(&accounts(0)), 990);
+    }
+
+    #[test]
+    fn missing_method_and_account() {
+        let mut runtime = runtime();
+        let outcome = runtime.call(accounts(0), counter_id(), "decrement", vec![], 0, GAS);
+        assert_eq!(outcome.result, PromiseResult::Failed);
+        assert_eq!(
+            outcome.failures().collect::<Vec<_>>(),
+            vec!["Contract method decrement is not found"]
+        );
+
+        let outcome = runtime.call(accounts(0), accounts(1), "increment", vec![], 0, GAS);
+        assert_eq!(
+            outcome.failures().collect::<Vec<_>>(),
+            vec![format!("Account {} doesn't exist", accounts(1))]
+        );
+    }
+
+    #[test]
+    fn unused_gas_is_split_by_weight() {
+        let mut runtime = runtime();
+        let outcome = runtime.call(
+            accounts(0),
+            caller_id(),
+            "call_counter_weighted",
+            b"increment".to_vec(),
+            0,
+            GAS,
+        );
+        assert_eq!(outcome.unwrap_json::<u64>(), 1);
+
+        let gas_of = |receipt: &ReceiptOutcome| match receipt.actions.as_slice() {
+            [VmAction::FunctionCall { gas, .. }] => *gas,
+            actions => panic!("Unexpected actions {:?}", actions),
+        };
+        let call_share = gas_of(&outcome.receipts[1]).0;
+        let callback_share = gas_of(&outcome.receipts[2]).0 - CALLBACK_GAS.0;
+        assert!(call_share > 0);
+        // The callback receives three times the share of the call, and the remainder.
+        assert!((3 * call_share..3 * call_share + 4).contains(&callback_share));
+        assert!(
+            outcome.receipts[0].gas_burnt.0 + CALLBACK_GAS.0 + call_share + callback_share < GAS.0
+        );
+    }
+
+    #[test]
+    fn exceeded_prepaid_gas() {
+        let mut runtime = runtime();
+        let outcome = runtime.call(accounts(0), counter_id(), "increment", vec![], 0, Gas(1));
+        assert_eq!(outcome.result, PromiseResult::Failed);
+        assert_eq!(outcome.failures().collect::<Vec<_>>(), vec!["Exceeded the prepaid gas."]);
+        assert_eq!(runtime.with_account(&counter_id(), || env::storage_read(b"count")), None);
+    }
+}
@@ -0,0 +1,442 @@
+use crate::json_types::{Base64VecU8, U128, U64};
+use crate::mock::{with_mocked_blockchain, MockedBlockchain};
+use crate::{env, AccountId, Gas, PublicKey, VMContext};
+use borsh::BorshDeserialize;
+use near_vm_logic::ViewConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::{self, BTreeMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Snapshot of the storage and the context of the [`MockedBlockchain`], used to run several
+/// scenarios of a unit test from a common state.
+///
+/// Values of the `store` collections are only written to the storage when they are flushed, so the
+/// contract should be flushed or dropped before taking the snapshot.
+///
+/// # Example
+///
+/// ```
+/// use near_sdk::env;
+/// use near_sdk::test_utils::{StorageChange, StorageSnapshot};
+///
+/// env::storage_write(b"a", b"1");
+/// let before = StorageSnapshot::take();
+///
+/// env::storage_write(b"b", b"2");
+/// let after = StorageSnapshot::take();
+/// assert_eq!(
+///     before.diff(&after).iter().collect::<Vec<_>>(),
+///     vec![(&b"b".to_vec(), &StorageChange::Added(b"2".to_vec()))]
+/// );
+///
+/// before.restore();
+/// assert!(!env::storage_has_key(b"b"));
+/// ```
+#[derive(Clone)]
+pub struct StorageSnapshot {
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    context: VMContext,
+}
+
+impl StorageSnapshot {
+    /// Takes a snapshot of the storage of the current [`MockedBlockchain`], along with its context
+    /// updated with the current balances and storage usage of the account.
+    pub fn take() -> Self {
+        let (storage, mut context) = with_mocked_blockchain(|b| {
+            let storage = b.storage().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            (storage, b.context().clone())
+        });
+        context.account_balance = env::account_balance();
+        context.account_locked_balance = env::account_locked_balance();
+        context.storage_usage = env::storage_usage();
+        Self { storage, context }
+    }
+
+    /// Replaces the [`MockedBlockchain`] with one created from the snapshot. The configs and the
+    /// validators of the current blockchain are kept, while the promise results, logs and receipts
+    /// are cleared.
+    pub fn restore(&self) {
+        let (config, fees_config, validators) = with_mocked_blockchain(|b| {
+            (b.config().clone(), b.fees_config().clone(), b.validators().clone())
+        });
+        env::set_blockchain_interface(MockedBlockchain::new(
+            self.context.clone(),
+            config,
+            fees_config,
+            vec![],
+            self.storage.clone().into_iter().collect(),
+            validators,
+            None,
+        ));
+    }
+
+    /// The key-value pairs of the storage.
+    pub fn storage(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.storage
+    }
+
+    /// The context of the blockchain.
+    pub fn context(&self) -> &VMContext {
+        &self.context
+    }
+
+    /// Returns the changes of the storage from this snapshot to `other`.
+    pub fn diff(&self, other: &StorageSnapshot) -> StorageDiff {
+        let mut changes = BTreeMap::new();
+        for (key, old) in &self.storage {
+            match other.storage.get(key) {
+                None => {
+                    changes.insert(key.clone(), StorageChange::Removed(old.clone()));
+                }
+                Some(new) if new != old => {
+                    changes.insert(
+                        key.clone(),
+                        StorageChange::Changed { old: old.clone(), new: new.clone() },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new) in &other.storage {
+            if !self.storage.contains_key(key) {
+                changes.insert(key.clone(), StorageChange::Added(new.clone()));
+            }
+        }
+        StorageDiff { changes }
+    }
+
+    /// Writes the snapshot to `path` as JSON, to be used as a fixture by [`StorageSnapshot::load`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let file = SnapshotFile::from(self);
+        let json = serde_json::to_string_pretty(&file)?;
+        fs::write(path, json)
+    }
+
+    /// Reads a snapshot written by [`StorageSnapshot::save`] from `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let json = fs::read(path)?;
+        let file: SnapshotFile = serde_json::from_slice(&json)?;
+        Ok(file.into())
+    }
+}
+
+/// Change of the value of a storage key between two [`StorageSnapshot`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageChange {
+    /// The key was added with the value.
+    Added(Vec<u8>),
+    /// The value of the key changed.
+    Changed { old: Vec<u8>, new: Vec<u8> },
+    /// The key with the value was removed.
+    Removed(Vec<u8>),
+}
+
+/// Changes of the storage between two [`StorageSnapshot`]s, ordered by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageDiff {
+    changes: BTreeMap<Vec<u8>, StorageChange>,
+}
+
+impl StorageDiff {
+    /// Returns `true` if the storage didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of changed keys.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns the change of `key`, if any.
+    pub fn get(&self, key: &[u8]) -> Option<&StorageChange> {
+        self.changes.get(key)
+    }
+
+    /// Iterates over the changed keys and their changes.
+    pub fn iter(&self) -> btree_map::Iter<'_, Vec<u8>, StorageChange> {
+        self.changes.iter()
+    }
+
+    /// Iterates over the added keys and their values.
+    pub fn added(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.changes.iter().filter_map(|(key, change)| match change {
+            StorageChange::Added(value) => Some((key.as_slice(), value.as_slice())),
+            _ => None,
+        })
+    }
+
+    /// Iterates over the changed keys and their old and new values.
+    pub fn changed(&self) -> impl Iterator<Item = (&[u8], &[u8], &[u8])> {
+        self.changes.iter().filter_map(|(key, change)| match change {
+            StorageChange::Changed { old, new } => {
+                Some((key.as_slice(), old.as_slice(), new.as_slice()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Iterates over the removed keys and their old values.
+    pub fn removed(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.changes.iter().filter_map(|(key, change)| match change {
+            StorageChange::Removed(value) => Some((key.as_slice(), value.as_slice())),
+            _ => None,
+        })
+    }
+
+    /// Returns the changes with the prefixes of their keys decoded as `K`, usually the
+    /// [`BorshStorageKey`](crate::BorshStorageKey) of the contract. The prefix is `None` for the
+    /// keys which don't start with a borsh serialized `K`, like the key of the contract state.
+    pub fn with_prefixes<K: BorshDeserialize>(&self) -> Vec<PrefixedChange<'_, K>> {
+        self.changes
+            .iter()
+            .map(|(key, change)| {
+                let mut suffix = key.as_slice();
+                match K::deserialize(&mut suffix) {
+                    Ok(prefix) => PrefixedChange { prefix: Some(prefix), suffix, key, change },
+                    Err(_) => PrefixedChange { prefix: None, suffix: key, key, change },
+                }
+            })
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a StorageDiff {
+    type Item = (&'a Vec<u8>, &'a StorageChange);
+    type IntoIter = btree_map::Iter<'a, Vec<u8>, StorageChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Change of a storage key with the prefix of the key decoded, see [`StorageDiff::with_prefixes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixedChange<'a, K> {
+    /// The decoded prefix of the key.
+    pub prefix: Option<K>,
+    /// The rest of the key after the prefix, or the whole key if it couldn't be decoded.
+    pub suffix: &'a [u8],
+    /// The whole key.
+    pub key: &'a [u8],
+    /// The change of the value.
+    pub change: &'a StorageChange,
+}
+
+/// JSON format of the snapshot fixtures.
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    context: ContextFile,
+    storage: Vec<(Base64VecU8, Base64VecU8)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ContextFile {
+    current_account_id: AccountId,
+    signer_account_id: AccountId,
+    signer_account_pk: PublicKey,
+    predecessor_account_id: AccountId,
+    input: Base64VecU8,
+    block_index: U64,
+    block_timestamp: U64,
+    epoch_height: U64,
+    account_balance: U128,
+    account_locked_balance: U128,
+    storage_usage: U64,
+    attached_deposit: U128,
+    prepaid_gas: Gas,
+    random_seed: [u8; 32],
+    /// The `max_gas_burnt` of the view config.
+    view_config: Option<U64>,
+    output_data_receivers: Vec<AccountId>,
+}
+
+impl From<&StorageSnapshot> for SnapshotFile {
+    fn from(snapshot: &StorageSnapshot) -> Self {
+        let context = snapshot.context.clone();
+        Self {
+            context: ContextFile {
+                current_account_id: context.current_account_id,
+                signer_account_id: context.signer_account_id,
+                signer_account_pk: context.signer_account_pk,
+                predecessor_account_id: context.predecessor_account_id,
+                input: context.input.into(),
+                block_index: context.block_index.into(),
+                block_timestamp: context.block_timestamp.into(),
+                epoch_height: context.epoch_height.into(),
+                account_balance: context.account_balance.into(),
+                account_locked_balance: context.account_locked_balance.into(),
+                storage_usage: context.storage_usage.into(),
+                attached_deposit: context.attached_deposit.into(),
+                prepaid_gas: context.prepaid_gas,
+                random_seed: context.random_seed,
+                view_config: context.view_config.map(|config| config.max_gas_burnt.into()),
+                output_data_receivers: context.output_data_receivers,
+            },
+            storage: snapshot
+                .storage
+                .iter()
+                .map(|(key, value)| (key.clone().into(), value.clone().into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<SnapshotFile> for StorageSnapshot {
+    fn from(file: SnapshotFile) -> Self {
+        let context = file.context;
+        Self {
+            context: VMContext {
+                current_account_id: context.current_account_id,
+                signer_account_id: context.signer_account_id,
+                signer_account_pk: context.signer_account_pk,
+                predecessor_account_id: context.predecessor_account_id,
+                input: context.input.into(),
+                block_index: context.block_index.into(),
+                block_timestamp: context.block_timestamp.into(),
+                epoch_height: context.epoch_height.into(),
+                account_balance: context.account_balance.into(),
+                account_locked_balance: context.account_locked_balance.into(),
+                storage_usage: context.storage_usage.into(),
+                attached_deposit: context.attached_deposit.into(),
+                prepaid_gas: context.prepaid_gas,
+                random_seed: context.random_seed,
+                view_config: context
+                    .view_config
+                    .map(|max_gas_burnt| ViewConfig { max_gas_burnt: max_gas_burnt.into() }),
+                output_data_receivers: context.output_data_receivers,
+            },
+            storage: file
+                .storage
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borsh::{self, BorshSerialize};
+    use crate::test_utils::{accounts, VMContextBuilder};
+    use crate::testing_env;
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    enum StorageKey {
+        Balances,
+        Metadata,
+    }
+
+    fn key(prefix: StorageKey, suffix: &[u8]) -> Vec<u8> {
+        let mut key = prefix.try_to_vec().unwrap();
+        key.extend_from_slice(suffix);
+        key
+    }
+
+    #[test]
+    fn restore_branches_from_snapshot() {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(1)).build());
+        env::storage_write(b"shared", b"1");
+        let snapshot = StorageSnapshot::take();
+
+        env::storage_write(b"first", b"2");
+        env::storage_remove(b"shared");
+        snapshot.restore();
+        assert_eq!(env::storage_read(b"shared"), Some(b"1".to_vec()));
+        assert!(!env::storage_has_key(b"first"));
+        assert_eq!(env::predecessor_account_id(), accounts(1));
+        assert_eq!(env::storage_usage(), snapshot.context().storage_usage);
+
+        env::storage_write(b"second", b"3");
+        assert!(!env::storage_has_key(b"first"));
+        assert!(env::storage_has_key(b"second"));
+    }
+
+    #[test]
+    fn diff() {
+        testing_env!(VMContextBuilder::new().build());
+        env::storage_write(&key(StorageKey::Balances, b"alice"), &10u128.to_le_bytes());
+        env::storage_write(&key(StorageKey::Balances, b"bob"), &5u128.to_le_bytes());
+        env::storage_write(b"STATE", b"state");
+        let before = StorageSnapshot::take();
+
+        env::storage_write(&key(StorageKey::Balances, b"alice"), &7u128.to_le_bytes());
+        env::storage_remove(&key(StorageKey::Balances, b"bob"));
+        env::storage_write(&key(StorageKey::Metadata, b""), b"meta");
+        let after = StorageSnapshot::take();
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.len(), 3);
+        assert!(after.diff(&after).is_empty());
+        assert_eq!(
+            diff.added().collect::<Vec<_>>(),
+            vec![(key(StorageKey::Metadata, b"").as_slice(), &b"meta"[..])]
+        );
+        assert_eq!(
+            diff.changed().collect::<Vec<_>>(),
+            vec![(
+                key(StorageKey::Balances, b"alice").as_slice(),
+                &10u128.to_le_bytes()[..],
+                &7u128.to_le_bytes()[..]
+            )]
+        );
+        assert_eq!(
+            diff.removed().collect::<Vec<_>>(),
+            vec![(key(StorageKey::Balances, b"bob").as_slice(), &5u128.to_le_bytes()[..])]
+        );
+
+        let prefixed: Vec<_> = diff
+            .with_prefixes::<StorageKey>()
+            .into_iter()
+            .map(|change| (change.prefix, change.suffix))
+            .collect();
+        assert_eq!(
+            prefixed,
+            vec![
+                (Some(StorageKey::Balances), &b"alice"[..]),
+                (Some(StorageKey::Balances), &b"bob"[..]),
+                (Some(StorageKey::Metadata), &b""[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn undecodable_prefix() {
+        testing_env!(VMContextBuilder::new().build());
+        let before = StorageSnapshot::take();
+        env::storage_write(b"STATE", b"state");
+        let diff = before.diff(&StorageSnapshot::take());
+        let prefixed = diff.with_prefixes::<StorageKey>();
+        assert_eq!(prefixed[0].prefix, None);
+        assert_eq!(prefixed[0].suffix, b"STATE");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut builder = VMContextBuilder::new();
+        testing_env!(builder.block_timestamp(42).attached_deposit(10u128.pow(30)).build());
+        env::storage_write(&[0, 255, 3], b"value");
+        // Storage is kept when switching to a view call.
+        testing_env!(builder.is_view(true).build());
+        let snapshot = StorageSnapshot::take();
+
+        let path =
+            std::env::temp_dir().join(format!("near-sdk-snapshot-{}.json", std::process::id()));
+        snapshot.save(&path).unwrap();
+        let loaded = StorageSnapshot::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.storage(), snapshot.storage());
+        assert!(snapshot.diff(&loaded).is_empty());
+        assert_eq!(loaded.context().block_timestamp, 42);
+        assert_eq!(loaded.context().attached_deposit, 10u128.pow(30));
+        assert!(loaded.context().is_view());
+
+        loaded.restore();
+        assert_eq!(env::storage_read(&[0, 255, 3]), Some(b"value".to_vec()));
+        assert_eq!(env::block_timestamp(), 42);
+    }
+}
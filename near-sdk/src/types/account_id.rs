@@ -62,6 +62,17 @@ impl fmt::Display for AccountId {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for AccountId {
+    fn schema_name() -> String {
+        "AccountId".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(generator)
+    }
+}
+
 impl From<AccountId> for String {
     fn from(id: AccountId) -> Self {
         id.0
@@ -0,0 +1,241 @@
+//! Types and input encoding for the `alt_bn128` host functions exposed through
+//! [`env::alt_bn128_g1_multiexp`](crate::env::alt_bn128_g1_multiexp),
+//! [`env::alt_bn128_g1_sum`](crate::env::alt_bn128_g1_sum) and
+//! [`env::alt_bn128_pairing_check`](crate::env::alt_bn128_pairing_check).
+//!
+//! All field elements are stored as 256-bit unsigned integers in little-endian byte order, which
+//! is the layout the runtime expects. Most tooling (e.g. Ethereum precompiles, `snarkjs`) uses
+//! big-endian encoding, so `from_be_bytes` constructors are provided for convenience.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::convert::TryFrom;
+
+/// Size in bytes of a single encoded field element or scalar.
+pub const SCALAR_SIZE: usize = 32;
+/// Size in bytes of an encoded [`G1Point`].
+pub const G1_POINT_SIZE: usize = SCALAR_SIZE * 2;
+/// Size in bytes of an encoded [`G2Point`].
+pub const G2_POINT_SIZE: usize = G1_POINT_SIZE * 2;
+
+fn reversed(bytes: [u8; SCALAR_SIZE]) -> [u8; SCALAR_SIZE] {
+    let mut bytes = bytes;
+    bytes.reverse();
+    bytes
+}
+
+fn u128_to_le_bytes(value: u128) -> [u8; SCALAR_SIZE] {
+    let mut bytes = [0u8; SCALAR_SIZE];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    bytes
+}
+
+/// Element of the base field `Fq` of the alt_bn128 curve, stored little-endian.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct Fq(pub [u8; SCALAR_SIZE]);
+
+impl Fq {
+    /// Creates a field element from its little-endian byte representation.
+    pub const fn from_le_bytes(bytes: [u8; SCALAR_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates a field element from its big-endian byte representation.
+    pub fn from_be_bytes(bytes: [u8; SCALAR_SIZE]) -> Self {
+        Self(reversed(bytes))
+    }
+
+    /// Returns the little-endian byte representation of the field element.
+    pub const fn to_le_bytes(self) -> [u8; SCALAR_SIZE] {
+        self.0
+    }
+
+    /// Returns the big-endian byte representation of the field element.
+    pub fn to_be_bytes(self) -> [u8; SCALAR_SIZE] {
+        reversed(self.0)
+    }
+}
+
+impl From<u128> for Fq {
+    fn from(value: u128) -> Self {
+        Self(u128_to_le_bytes(value))
+    }
+}
+
+/// Element of the scalar field `Fr` of the alt_bn128 curve, stored little-endian. This is the
+/// type of the multipliers passed to [`env::alt_bn128_g1_multiexp`](crate::env::alt_bn128_g1_multiexp).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct Fr(pub [u8; SCALAR_SIZE]);
+
+impl Fr {
+    /// Creates a scalar from its little-endian byte representation.
+    pub const fn from_le_bytes(bytes: [u8; SCALAR_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates a scalar from its big-endian byte representation.
+    pub fn from_be_bytes(bytes: [u8; SCALAR_SIZE]) -> Self {
+        Self(reversed(bytes))
+    }
+
+    /// Returns the little-endian byte representation of the scalar.
+    pub const fn to_le_bytes(self) -> [u8; SCALAR_SIZE] {
+        self.0
+    }
+
+    /// Returns the big-endian byte representation of the scalar.
+    pub fn to_be_bytes(self) -> [u8; SCALAR_SIZE] {
+        reversed(self.0)
+    }
+}
+
+impl From<u128> for Fr {
+    fn from(value: u128) -> Self {
+        Self(u128_to_le_bytes(value))
+    }
+}
+
+/// Element of the quadratic extension field `Fq2`, i.e. `re + im * i`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct Fq2 {
+    pub re: Fq,
+    pub im: Fq,
+}
+
+/// Point on the alt_bn128 curve `Y^2 = X^3 + 3` over `Fq`, in affine coordinates.
+///
+/// The point at infinity is represented as `(0, 0)`, which is also the [`Default`] value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct G1Point {
+    pub x: Fq,
+    pub y: Fq,
+}
+
+impl G1Point {
+    /// The point at infinity.
+    pub const ZERO: G1Point = G1Point { x: Fq([0; SCALAR_SIZE]), y: Fq([0; SCALAR_SIZE]) };
+
+    /// Returns `true` if this is the point at infinity.
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// Encodes the point as `x || y`, each coordinate little-endian.
+    pub fn to_le_bytes(&self) -> [u8; G1_POINT_SIZE] {
+        let mut res = [0u8; G1_POINT_SIZE];
+        res[..SCALAR_SIZE].copy_from_slice(&self.x.0);
+        res[SCALAR_SIZE..].copy_from_slice(&self.y.0);
+        res
+    }
+
+    /// Decodes a point from `x || y`, each coordinate little-endian. This does not check that
+    /// the point is on the curve, the host functions will fail on invalid points.
+    pub fn from_le_bytes(bytes: &[u8; G1_POINT_SIZE]) -> Self {
+        let mut x = [0u8; SCALAR_SIZE];
+        let mut y = [0u8; SCALAR_SIZE];
+        x.copy_from_slice(&bytes[..SCALAR_SIZE]);
+        y.copy_from_slice(&bytes[SCALAR_SIZE..]);
+        Self { x: Fq(x), y: Fq(y) }
+    }
+}
+
+/// Point on the twist `Y^2 = X^3 + 3/(i+9)` over `Fq2` belonging to the `Fr`-order subgroup,
+/// in affine coordinates.
+///
+/// The point at infinity is represented as `(0, 0)`, which is also the [`Default`] value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct G2Point {
+    pub x: Fq2,
+    pub y: Fq2,
+}
+
+impl G2Point {
+    /// Encodes the point as `x.re || x.im || y.re || y.im`, each component little-endian.
+    pub fn to_le_bytes(&self) -> [u8; G2_POINT_SIZE] {
+        let mut res = [0u8; G2_POINT_SIZE];
+        for (chunk, fq) in
+            res.chunks_exact_mut(SCALAR_SIZE).zip([self.x.re, self.x.im, self.y.re, self.y.im])
+        {
+            chunk.copy_from_slice(&fq.0);
+        }
+        res
+    }
+}
+
+// The runtime decodes its input as a borsh-serialized `Vec` of elements, so every encoding starts
+// with the `u32` little-endian number of elements, followed by the packed elements.
+fn encode_with_len(len: usize, element_size: usize, mut f: impl FnMut(&mut Vec<u8>)) -> Vec<u8> {
+    let len = u32::try_from(len).unwrap_or_else(|_| crate::env::panic_str("input is too long"));
+    let mut res = Vec::with_capacity(4 + len as usize * element_size);
+    res.extend_from_slice(&len.to_le_bytes());
+    f(&mut res);
+    res
+}
+
+/// Encodes the input of [`env::alt_bn128_g1_multiexp`](crate::env::alt_bn128_g1_multiexp).
+pub fn encode_g1_multiexp_input(elements: &[(G1Point, Fr)]) -> Vec<u8> {
+    encode_with_len(elements.len(), G1_POINT_SIZE + SCALAR_SIZE, |buf| {
+        for (point, scalar) in elements {
+            buf.extend_from_slice(&point.to_le_bytes());
+            buf.extend_from_slice(&scalar.0);
+        }
+    })
+}
+
+/// Encodes the input of [`env::alt_bn128_g1_sum`](crate::env::alt_bn128_g1_sum). The `bool` of
+/// every element is the sign, where `true` means the point is subtracted.
+pub fn encode_g1_sum_input(elements: &[(bool, G1Point)]) -> Vec<u8> {
+    encode_with_len(elements.len(), 1 + G1_POINT_SIZE, |buf| {
+        for (negative, point) in elements {
+            buf.push(*negative as u8);
+            buf.extend_from_slice(&point.to_le_bytes());
+        }
+    })
+}
+
+/// Encodes the input of [`env::alt_bn128_pairing_check`](crate::env::alt_bn128_pairing_check).
+pub fn encode_pairing_check_input(elements: &[(G1Point, G2Point)]) -> Vec<u8> {
+    encode_with_len(elements.len(), G1_POINT_SIZE + G2_POINT_SIZE, |buf| {
+        for (g1, g2) in elements {
+            buf.extend_from_slice(&g1.to_le_bytes());
+            buf.extend_from_slice(&g2.to_le_bytes());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fq_byte_order_roundtrip() {
+        let mut be = [0u8; SCALAR_SIZE];
+        be[SCALAR_SIZE - 1] = 7;
+        let fq = Fq::from_be_bytes(be);
+        assert_eq!(fq.to_le_bytes()[0], 7);
+        assert_eq!(fq.to_be_bytes(), be);
+        assert_eq!(Fr::from(7).to_be_bytes(), be);
+    }
+
+    #[test]
+    fn encoded_input_sizes() {
+        let g1 = G1Point::ZERO;
+        let g2 = G2Point::default();
+
+        let input = encode_g1_multiexp_input(&[(g1, Fr::from(1)), (g1, Fr::from(2))]);
+        assert_eq!(input.len(), 4 + 2 * (G1_POINT_SIZE + SCALAR_SIZE));
+        assert_eq!(&input[..4], &2u32.to_le_bytes());
+
+        let input = encode_g1_sum_input(&[(true, g1)]);
+        assert_eq!(input.len(), 4 + 1 + G1_POINT_SIZE);
+        assert_eq!(input[4], 1);
+
+        let input = encode_pairing_check_input(&[(g1, g2)]);
+        assert_eq!(input.len(), 4 + G1_POINT_SIZE + G2_POINT_SIZE);
+    }
+
+    #[test]
+    fn g1_le_bytes_roundtrip() {
+        let point = G1Point { x: Fq::from_be_bytes([1; 32]), y: Fq::from_be_bytes([2; 32]) };
+        assert_eq!(G1Point::from_le_bytes(&point.to_le_bytes()), point);
+    }
+}
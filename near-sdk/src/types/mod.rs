@@ -13,6 +13,9 @@ pub use self::account_id::{AccountId, ParseAccountIdError};
 mod gas;
 pub use self::gas::Gas;
 
+#[cfg(feature = "unstable")]
+pub mod alt_bn128;
+
 mod error;
 pub use self::error::Abort;
 pub use self::error::FunctionError;
@@ -8,9 +8,23 @@ pub type PromiseIndex = u64;
 pub type ReceiptIndex = u64;
 pub type IteratorIndex = u64;
 
+/// Weight of unused gas to assign to a function call, see
+/// [`env::promise_batch_action_function_call_weight`](crate::env::promise_batch_action_function_call_weight).
+///
+/// The default weight is `1`, so that function calls without an explicit weight split the unused
+/// gas evenly. A weight of `0` means the call only receives its static gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GasWeight(pub u64);
+
+impl Default for GasWeight {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
 /// When there is a callback attached to one or more contract calls the execution results of these
 /// calls are available to the contract invoked through the callback.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PromiseResult {
     /// Current version of the protocol never returns `PromiseResult::NotReady`.
     NotReady,
@@ -37,4 +51,7 @@ pub enum PromiseError {
     Failed,
     /// Current version of the protocol never returns this variant.
     NotReady,
+    /// Promise succeeded, but its result could not be deserialized into the expected type.
+    /// Contains the deserialization error message.
+    Deserialization(String),
 }
@@ -0,0 +1,187 @@
+use crate::env;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Prefix of the logs of events, followed by the JSON of the event.
+const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// An event in the [NEP-297](https://nomicon.io/Standards/EventsFormat) format, which is logged
+/// as `EVENT_JSON:` followed by a JSON object with the `standard` and `version` of the event, the
+/// name of the `event` and its `data`.
+///
+/// A struct is the `data` of a single event, named by [`EVENT`](Self::EVENT). An enum groups the
+/// events of a standard: each variant is an event named after the variant in snake case, with the
+/// content of the variant as its `data`.
+///
+/// This trait should be implemented with the [`near_event`](crate::near_event) attribute, which
+/// also derives `Serialize` and adds an `emit` method to the type, so that the trait doesn't have
+/// to be imported.
+///
+/// # Example
+///
+/// ```ignore
+/// use near_sdk::serde::Serialize;
+/// use near_sdk::{near_event, AccountId};
+///
+/// #[derive(Serialize)]
+/// #[serde(crate = "near_sdk::serde")]
+/// pub struct Transfer {
+///     pub old_owner_id: AccountId,
+///     pub new_owner_id: AccountId,
+/// }
+///
+/// #[near_event(standard = "nep171", version = "1.0.0")]
+/// pub enum NftEvent {
+///     NftTransfer(Vec<Transfer>),
+/// }
+///
+/// // Logs `EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_transfer","data":[...]}`
+/// NftEvent::NftTransfer(vec![transfer]).emit();
+/// ```
+pub trait NearEvent: Serialize {
+    /// The name of the standard of the event.
+    const STANDARD: &'static str;
+    /// The version of the standard of the event.
+    const VERSION: &'static str;
+    /// The name of the event if the type is a struct, which is then the `data` of the event.
+    /// `None` if the type is an enum serialized with the `event` and `data` of its variants.
+    const EVENT: Option<&'static str>;
+
+    /// Returns the log of the event: `EVENT_JSON:` followed by the JSON of the event.
+    fn to_event_log(&self) -> String {
+        #[derive(Serialize)]
+        struct EventJson<'a, T: ?Sized> {
+            standard: &'a str,
+            version: &'a str,
+            #[serde(flatten)]
+            event: &'a T,
+        }
+
+        #[derive(Serialize)]
+        struct EventDataJson<'a, T: ?Sized> {
+            standard: &'a str,
+            version: &'a str,
+            event: &'a str,
+            data: &'a T,
+        }
+
+        let (standard, version) = (Self::STANDARD, Self::VERSION);
+        // Events cannot fail to serialize so fine to panic on error
+        let json = match Self::EVENT {
+            Some(event) => {
+                serde_json::to_string(&EventDataJson { standard, version, event, data: self })
+            }
+            None => serde_json::to_string(&EventJson { standard, version, event: self }),
+        }
+        .unwrap_or_else(|_| env::abort());
+        format!("{}{}", EVENT_JSON_PREFIX, json)
+    }
+
+    /// Logs the event through [`env::log_str`].
+    fn emit(&self) {
+        env::log_str(&self.to_event_log());
+    }
+
+    /// Parses an event of this type back from its log. Returns `None` if the log isn't an event
+    /// of the standard and version of this type, isn't one of its events, or if the data of the
+    /// event can't be deserialized.
+    fn from_event_log(log: &str) -> Option<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        let json = log.strip_prefix(EVENT_JSON_PREFIX)?;
+        let mut event: serde_json::Map<String, Value> = serde_json::from_str(json).ok()?;
+        if event.remove("standard")? != Self::STANDARD || event.remove("version")? != Self::VERSION
+        {
+            return None;
+        }
+        match Self::EVENT {
+            Some(name) => {
+                if *event.get("event")? != name {
+                    return None;
+                }
+                serde_json::from_value(event.remove("data")?).ok()
+            }
+            None => serde_json::from_value(Value::Object(event)).ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_events;
+    use crate::test_utils::test_env::setup_free;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(tag = "event", content = "data", rename_all = "snake_case")]
+    enum NftEvent {
+        NftMint(Vec<String>),
+        NftBurn { token_ids: Vec<String> },
+    }
+
+    impl NearEvent for NftEvent {
+        const STANDARD: &'static str = "nep171";
+        const VERSION: &'static str = "1.0.0";
+        const EVENT: Option<&'static str> = None;
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Transfer {
+        amount: u64,
+    }
+
+    impl NearEvent for Transfer {
+        const STANDARD: &'static str = "nep141";
+        const VERSION: &'static str = "1.0.0";
+        const EVENT: Option<&'static str> = Some("ft_transfer");
+    }
+
+    #[test]
+    fn enum_event_log() {
+        let event = NftEvent::NftMint(vec!["0".to_string()]);
+        assert_eq!(
+            event.to_event_log(),
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":["0"]}"#
+        );
+        assert_eq!(NftEvent::from_event_log(&event.to_event_log()), Some(event));
+    }
+
+    #[test]
+    fn struct_event_log() {
+        let event = Transfer { amount: 5 };
+        assert_eq!(
+            event.to_event_log(),
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":{"amount":5}}"#
+        );
+        assert_eq!(Transfer::from_event_log(&event.to_event_log()), Some(event));
+    }
+
+    #[test]
+    fn rejects_other_events() {
+        let log = r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":{"amount":5}}"#;
+        assert_eq!(Transfer::from_event_log(log), None);
+        let log = r#"EVENT_JSON:{"standard":"nep141","version":"2.0.0","event":"ft_transfer","data":{"amount":5}}"#;
+        assert_eq!(Transfer::from_event_log(log), None);
+        let log =
+            r#"{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":{"amount":5}}"#;
+        assert_eq!(Transfer::from_event_log(log), None);
+        assert_eq!(NftEvent::from_event_log(&Transfer { amount: 5 }.to_event_log()), None);
+    }
+
+    #[test]
+    fn get_emitted_events() {
+        setup_free();
+        NftEvent::NftBurn { token_ids: vec!["1".to_string()] }.emit();
+        crate::log!("not an event");
+        Transfer { amount: 7 }.emit();
+        NftEvent::NftMint(vec![]).emit();
+        assert_eq!(
+            get_events::<NftEvent>(),
+            vec![NftEvent::NftBurn { token_ids: vec!["1".to_string()] }, NftEvent::NftMint(vec![])]
+        );
+        assert_eq!(get_events::<Transfer>(), vec![Transfer { amount: 7 }]);
+    }
+}
@@ -2,6 +2,15 @@
 
 pub(crate) mod storage_key_impl;
 
+mod storage_tracker;
+pub use self::storage_tracker::StorageTracker;
+
+pub(crate) mod event;
+pub use self::event::NearEvent;
+
+pub(crate) mod versioned_state;
+pub use self::versioned_state::VersionedState;
+
 #[cfg(feature = "unstable")]
 mod stable_map;
 #[cfg(feature = "unstable")]
@@ -1,9 +1,55 @@
+use borsh::BorshSerialize;
+
+use crate::env;
+
 /// Converts Self into a [`Vec<u8>`] that is used for a storage key through [`into_storage_key`].
 ///
 /// [`into_storage_key`]: IntoStorageKey::into_storage_key
 pub trait IntoStorageKey {
     /// Consumes self and returns [`Vec<u8>`] bytes which are used as a storage key.
     fn into_storage_key(self) -> Vec<u8>;
+
+    /// Returns the prefix of a collection nested under `key`, within the collection which has
+    /// self as its prefix. The prefix is the storage key of self followed by the sha256 hash of
+    /// the Borsh serialized `key`, so that the prefixes of all nested collections have the same
+    /// length and none of them is a prefix of another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use near_sdk::borsh::{self, BorshSerialize};
+    /// use near_sdk::collections::{LookupMap, UnorderedSet};
+    /// use near_sdk::{AccountId, BorshStorageKey, IntoStorageKey};
+    ///
+    /// #[derive(BorshSerialize, BorshStorageKey)]
+    /// enum StorageKey {
+    ///     TokensPerOwner,
+    /// }
+    ///
+    /// let mut tokens_per_owner: LookupMap<AccountId, UnorderedSet<String>> =
+    ///     LookupMap::new(StorageKey::TokensPerOwner);
+    ///
+    /// let owner_id: AccountId = "alice.near".parse().unwrap();
+    /// let mut tokens = UnorderedSet::new(StorageKey::TokensPerOwner.nest(&owner_id));
+    /// tokens.insert(&"token".to_string());
+    /// tokens_per_owner.insert(&owner_id, &tokens);
+    /// ```
+    fn nest<K: ?Sized>(self, key: &K) -> Vec<u8>
+    where
+        Self: Sized,
+        K: BorshSerialize,
+    {
+        let key = key.try_to_vec().unwrap_or_else(|_| env::abort());
+        nested_storage_key(self.into_storage_key(), &key)
+    }
+}
+
+/// Appends the hash of the serialized key of a nested collection to the prefix of its parent.
+/// Used by [`IntoStorageKey::nest`] and for the `#[nested]` variants of
+/// [`BorshStorageKey`](crate::BorshStorageKey).
+pub fn nested_storage_key(mut prefix: Vec<u8>, serialized_key: &[u8]) -> Vec<u8> {
+    prefix.extend_from_slice(&env::sha256_array(serialized_key));
+    prefix
 }
 
 impl IntoStorageKey for Vec<u8> {
@@ -0,0 +1,182 @@
+use crate::{env, require, AccountId, Balance, Promise, StorageUsage};
+
+/// Keeps track of the storage allocated and released by the closures passed to
+/// [`StorageTracker::track`], so that the caller can be charged for the storage it occupies.
+///
+/// # Example
+///
+/// ```no_run
+/// use near_sdk::StorageTracker;
+/// # use near_sdk::env;
+///
+/// let mut tracker = StorageTracker::default();
+/// tracker.track(|| env::storage_write(b"key", b"value"));
+/// // Panics if the attached deposit does not cover the new storage, otherwise refunds the rest
+/// // of the attached deposit to the predecessor.
+/// tracker.refund_deposit();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StorageTracker {
+    bytes_added: StorageUsage,
+    bytes_released: StorageUsage,
+}
+
+impl StorageTracker {
+    /// Calls `f` and records the change of storage usage of the contract made by it.
+    pub fn track<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        let initial_storage_usage = env::storage_usage();
+        let res = f();
+        let storage_usage = env::storage_usage();
+        if storage_usage >= initial_storage_usage {
+            self.bytes_added += storage_usage - initial_storage_usage;
+        } else {
+            self.bytes_released += initial_storage_usage - storage_usage;
+        }
+        res
+    }
+
+    /// Number of bytes allocated by the tracked closures.
+    pub fn bytes_added(&self) -> StorageUsage {
+        self.bytes_added
+    }
+
+    /// Number of bytes released by the tracked closures.
+    pub fn bytes_released(&self) -> StorageUsage {
+        self.bytes_released
+    }
+
+    /// Cost of the storage allocated on top of the released storage, which should be covered by
+    /// the attached deposit. Zero if more storage was released than allocated.
+    pub fn required_deposit(&self) -> Balance {
+        Balance::from(self.bytes_added.saturating_sub(self.bytes_released))
+            * env::storage_byte_cost()
+    }
+
+    /// Cost of the storage released on top of the allocated storage, which is no longer staked
+    /// by the contract. Zero if more storage was allocated than released.
+    pub fn released_deposit(&self) -> Balance {
+        Balance::from(self.bytes_released.saturating_sub(self.bytes_added))
+            * env::storage_byte_cost()
+    }
+
+    /// Asserts that the attached deposit covers the cost of the allocated storage and transfers
+    /// the rest of it to `account_id`. Amounts of 1 yoctoNEAR or less are not refunded.
+    ///
+    /// The cost of the released storage isn't refunded, as it wasn't necessarily paid by the
+    /// caller, see [`StorageTracker::refund_released_deposit_to`].
+    pub fn refund_deposit_to(self, account_id: AccountId) {
+        let required_deposit = self.required_deposit();
+        let attached_deposit = env::attached_deposit();
+        require!(
+            required_deposit <= attached_deposit,
+            format!(
+                "Must attach {} yoctoNEAR to cover storage of {} bytes, attached {} yoctoNEAR",
+                required_deposit,
+                self.bytes_added - self.bytes_released,
+                attached_deposit
+            )
+        );
+
+        let refund = attached_deposit - required_deposit;
+        if refund > 1 {
+            Promise::new(account_id).transfer(refund);
+        }
+    }
+
+    /// Same as [`StorageTracker::refund_deposit_to`], where the predecessor is refunded.
+    pub fn refund_deposit(self) {
+        self.refund_deposit_to(env::predecessor_account_id())
+    }
+
+    /// Transfers the cost of the released storage, see [`StorageTracker::released_deposit`], to
+    /// `account_id`, which should be the account that paid for that storage.
+    pub fn refund_released_deposit_to(&self, account_id: AccountId) {
+        let released_deposit = self.released_deposit();
+        if released_deposit > 0 {
+            Promise::new(account_id).transfer(released_deposit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::VmAction;
+    use crate::test_utils::{get_created_receipts, VMContextBuilder};
+    use crate::testing_env;
+
+    fn setup(attached_deposit: Balance) {
+        testing_env!(VMContextBuilder::new().attached_deposit(attached_deposit).build());
+    }
+
+    fn refunds() -> Vec<Balance> {
+        get_created_receipts()
+            .into_iter()
+            .flat_map(|receipt| receipt.actions)
+            .filter_map(|action| match action {
+                VmAction::Transfer { deposit } => Some(deposit),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tracks_added_and_released_bytes() {
+        setup(0);
+        let mut tracker = StorageTracker::default();
+        let inserted = tracker.track(|| env::storage_write(b"key", b"value"));
+        assert!(!inserted);
+        let added = tracker.bytes_added();
+        assert!(added > 0);
+
+        tracker.track(|| env::storage_remove(b"key"));
+        assert_eq!(tracker.bytes_released(), added);
+        assert_eq!(tracker.required_deposit(), 0);
+        assert_eq!(tracker.released_deposit(), 0);
+    }
+
+    #[test]
+    fn refunds_surplus() {
+        let mut tracker = StorageTracker::default();
+        setup(0);
+        tracker.track(|| env::storage_write(b"key", b"value"));
+        let required = tracker.required_deposit();
+        assert_eq!(required, Balance::from(tracker.bytes_added()) * env::storage_byte_cost());
+
+        setup(required + 100);
+        tracker.refund_deposit();
+        assert_eq!(refunds(), vec![100]);
+    }
+
+    #[test]
+    fn refunds_released_storage() {
+        setup(10);
+        env::storage_write(b"key", b"value");
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| env::storage_remove(b"key"));
+        let released = tracker.released_deposit();
+        assert!(released > 0);
+        tracker.refund_released_deposit_to("alice.near".parse().unwrap());
+        assert_eq!(refunds(), vec![released]);
+    }
+
+    #[test]
+    fn keeps_released_storage_on_refund() {
+        setup(10);
+        env::storage_write(b"key", b"value");
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| env::storage_remove(b"key"));
+        assert!(tracker.released_deposit() > 0);
+        tracker.refund_deposit();
+        assert_eq!(refunds(), vec![10]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn insufficient_deposit() {
+        setup(1);
+        let mut tracker = StorageTracker::default();
+        tracker.track(|| env::storage_write(b"key", b"value"));
+        tracker.refund_deposit();
+    }
+}
@@ -0,0 +1,217 @@
+use crate::env;
+use std::convert::TryInto;
+
+/// Storage key of the schema version of the contract state. It is namespaced away from the `STATE`
+/// key and from the prefixes of the collections.
+const STATE_VERSION_KEY: &[u8] = b"__near_sdk__/state_version";
+
+/// Tags the contract state with the version of its schema, so that the state written by a previous
+/// version of the contract can be migrated after the new code is deployed.
+///
+/// The version is stored next to the state by the `#[init]` methods generated by
+/// [`near_bindgen`](crate::near_bindgen), and by the methods which write the state of a contract
+/// created with `Default`. State that was written before the contract adopted versioning has no
+/// stored version and is treated as version `0`.
+///
+/// Migrations are methods of the contract marked with `#[migrate(from = N)]`, that take the state
+/// of version `N` and return the state of version `N + 1`. The last migration of the chain returns
+/// the contract itself. `near_bindgen` generates a private `migrate_state` method which reads the
+/// stored state with [`env::state_read`] and applies the pending migrations in order, so it is
+/// meant to be called in the same batch as the `DeployContract` action. It does nothing if the
+/// state is already at [`STATE_VERSION`](Self::STATE_VERSION). All migrations have to be declared
+/// in the same `impl` section.
+///
+/// This trait should be implemented with the [`VersionedState`](crate::VersionedState) derive.
+///
+/// # Example
+///
+/// ```ignore
+/// use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+/// use near_sdk::{near_bindgen, AccountId, VersionedState};
+///
+/// #[derive(BorshDeserialize, BorshSerialize)]
+/// pub struct ContractV1 {
+///     owner: AccountId,
+/// }
+///
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize, VersionedState)]
+/// #[state_version(2)]
+/// pub struct Contract {
+///     owner: AccountId,
+///     counter: u64,
+/// }
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[migrate(from = 1)]
+///     fn migrate_v1(old: ContractV1) -> Self {
+///         Self { owner: old.owner, counter: 0 }
+///     }
+/// }
+/// ```
+pub trait VersionedState {
+    /// The version of the schema of the state.
+    const STATE_VERSION: u32;
+}
+
+/// Returns the schema version of the stored contract state, `0` if it was never written.
+pub(crate) fn read_state_version() -> u32 {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|bytes| {
+            let bytes = bytes
+                .try_into()
+                .unwrap_or_else(|_| env::panic_str("Cannot deserialize the state version."));
+            u32::from_le_bytes(bytes)
+        })
+        .unwrap_or(0)
+}
+
+/// Stores `version` as the schema version of the contract state.
+pub(crate) fn write_state_version(version: u32) {
+    env::storage_write(STATE_VERSION_KEY, &version.to_le_bytes());
+}
+
+/// Whether a schema version of the contract state is stored.
+pub(crate) fn has_state_version() -> bool {
+    env::storage_has_key(STATE_VERSION_KEY)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::__private::{
+        migrate_state, read_previous_state, StateVersionWriter, WriteUnversionedState,
+        WriteVersionedState,
+    };
+    use crate::borsh::{self, BorshDeserialize, BorshSerialize};
+    use crate::test_utils::test_env::setup_free;
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct StateV1 {
+        value: u8,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct StateV2 {
+        value: u16,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Default)]
+    struct State {
+        value: u32,
+        name: String,
+    }
+
+    impl VersionedState for State {
+        const STATE_VERSION: u32 = 3;
+    }
+
+    fn migrate(from: u32) -> bool {
+        match from {
+            1 => {
+                let old: StateV1 = read_previous_state();
+                env::state_write(&StateV2 { value: u16::from(old.value) * 2 })
+            }
+            2 => {
+                let old: StateV2 = read_previous_state();
+                env::state_write(&State { value: u32::from(old.value) + 1, name: "v3".into() })
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    #[test]
+    fn writes_version_of_versioned_state() {
+        setup_free();
+        (&StateVersionWriter::<State>::new()).write_state_version();
+        assert_eq!(read_state_version(), 3);
+    }
+
+    #[test]
+    fn ignores_unversioned_state() {
+        setup_free();
+        (&StateVersionWriter::<StateV1>::new()).write_state_version();
+        assert_eq!(read_state_version(), 0);
+    }
+
+    /// Mirrors the state access of the methods generated by `near_bindgen`.
+    fn call_default_contract() {
+        let mut contract: State = env::state_read().unwrap_or_else(|| {
+            (&StateVersionWriter::<State>::new()).write_default_state_version();
+            Default::default()
+        });
+        contract.value += 1;
+        env::state_write(&contract);
+    }
+
+    #[test]
+    fn writes_version_of_default_state() {
+        setup_free();
+        call_default_contract();
+        assert_eq!(read_state_version(), 3);
+
+        migrate_state::<State>(|_| panic!("no migrations are pending"));
+        call_default_contract();
+        assert_eq!(env::state_read(), Some(State { value: 2, name: String::new() }));
+    }
+
+    #[test]
+    fn keeps_stored_version_of_default_state() {
+        setup_free();
+        write_state_version(2);
+        call_default_contract();
+        assert_eq!(read_state_version(), 2);
+    }
+
+    #[test]
+    fn ignores_default_unversioned_state() {
+        setup_free();
+        (&StateVersionWriter::<StateV1>::new()).write_default_state_version();
+        assert!(!has_state_version());
+    }
+
+    #[test]
+    fn migrates_chain() {
+        setup_free();
+        env::state_write(&StateV1 { value: 5 });
+        write_state_version(1);
+
+        migrate_state::<State>(migrate);
+        assert_eq!(read_state_version(), 3);
+        assert_eq!(env::state_read(), Some(State { value: 11, name: "v3".into() }));
+
+        // Running the migrations again is a no-op.
+        migrate_state::<State>(|_| panic!("no migrations are pending"));
+        assert_eq!(env::state_read(), Some(State { value: 11, name: "v3".into() }));
+    }
+
+    #[test]
+    fn migrates_from_intermediate_version() {
+        setup_free();
+        env::state_write(&StateV2 { value: 7 });
+        write_state_version(2);
+
+        migrate_state::<State>(migrate);
+        assert_eq!(env::state_read(), Some(State { value: 8, name: "v3".into() }));
+    }
+
+    #[test]
+    #[should_panic(expected = "No migration from state version 0")]
+    fn missing_migration() {
+        setup_free();
+        env::state_write(&StateV1 { value: 5 });
+        migrate_state::<State>(migrate);
+    }
+
+    #[test]
+    #[should_panic(expected = "is newer than the contract")]
+    fn newer_state() {
+        setup_free();
+        env::state_write(&State { value: 1, name: "v4".into() });
+        write_state_version(4);
+        migrate_state::<State>(migrate);
+    }
+}
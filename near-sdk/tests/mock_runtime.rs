@@ -0,0 +1,88 @@
+#![cfg(feature = "mock-runtime")]
+
+use near_sdk::mock::VmAction;
+use near_sdk::test_utils::{accounts, MockRuntime};
+use near_sdk::{AccountId, Gas};
+
+mod counter {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::near_bindgen;
+
+    #[near_bindgen]
+    #[derive(Default, BorshDeserialize, BorshSerialize)]
+    pub struct Counter {
+        value: u64,
+    }
+
+    #[near_bindgen]
+    impl Counter {
+        pub fn increment(&mut self) -> u64 {
+            self.value += 1;
+            self.value
+        }
+    }
+}
+
+mod caller {
+    use super::counter::Counter;
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::{env, near_bindgen, AccountId, Promise};
+
+    #[near_bindgen]
+    #[derive(Default, BorshDeserialize, BorshSerialize)]
+    pub struct Caller {}
+
+    #[near_bindgen]
+    impl Caller {
+        /// Increments the counter with the default configuration of the builders, so that both
+        /// calls only get a share of the unused gas.
+        pub fn call_counter(&self, counter_id: AccountId) -> Promise {
+            Counter::ext(counter_id)
+                .increment()
+                .then(Self::ext(env::current_account_id()).on_counter())
+        }
+
+        #[private]
+        pub fn on_counter(&self, #[callback_unwrap] value: u64) -> u64 {
+            value * 10
+        }
+    }
+}
+
+const GAS: Gas = Gas(300_000_000_000_000);
+
+fn counter_id() -> AccountId {
+    "counter.near".parse().unwrap()
+}
+
+fn caller_id() -> AccountId {
+    "caller.near".parse().unwrap()
+}
+
+#[test]
+fn ext_builders_receive_unused_gas() {
+    let mut runtime = MockRuntime::new();
+    runtime.add_account(accounts(0), 1000);
+    runtime.add_account(counter_id(), 100);
+    runtime.deploy(&counter_id(), &[counter::_methods::dispatch]);
+    runtime.add_account(caller_id(), 100);
+    runtime.deploy(&caller_id(), &[caller::_methods::dispatch]);
+
+    let args = format!(r#"{{"counter_id": "{}"}}"#, counter_id()).into_bytes();
+    let outcome = runtime.call(accounts(0), caller_id(), "call_counter", args.clone(), 0, GAS);
+    assert_eq!(outcome.unwrap_json::<u64>(), 10);
+
+    // Both calls have the default weight, so they split the unused gas evenly.
+    let gas: Vec<Gas> = outcome.receipts[1..]
+        .iter()
+        .map(|receipt| match receipt.actions.as_slice() {
+            [VmAction::FunctionCall { gas, .. }] => *gas,
+            actions => panic!("Unexpected actions {:?}", actions),
+        })
+        .collect();
+    assert!(gas[0] > Gas(0));
+    assert!(gas[1].0 - gas[0].0 <= 1);
+
+    let outcome = runtime.call(accounts(0), caller_id(), "call_counter", args, 0, GAS);
+    assert_eq!(outcome.unwrap_json::<u64>(), 20);
+}